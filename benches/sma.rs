@@ -0,0 +1,108 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use simple_moving_average::{DynamicSMA, NoSumSMA, SingleSumSMA, SumTreeSMA, SMA};
+use std::collections::VecDeque;
+
+macro_rules! bench_sma_impls {
+	($group:expr, $bench_fn:ident, $sample_type:ty, $($window_size:expr),+) => {
+		$(
+			$bench_fn(
+				&mut $group,
+				"SingleSumSMA",
+				SingleSumSMA::<$sample_type, $sample_type, $window_size>::new(),
+			);
+			$bench_fn(
+				&mut $group,
+				"SumTreeSMA",
+				SumTreeSMA::<$sample_type, $sample_type, $window_size>::new(),
+			);
+			$bench_fn(
+				&mut $group,
+				"NoSumSMA",
+				NoSumSMA::<$sample_type, $sample_type, $window_size>::new(),
+			);
+		)+
+	};
+}
+
+fn bench_add_sample<Sample: Copy + From<u8>, const WINDOW_SIZE: usize>(
+	group: &mut criterion::BenchmarkGroup<criterion::measurement::WallTime>,
+	name: &str,
+	mut sma: impl SMA<Sample, Sample, WINDOW_SIZE>,
+) {
+	let samples: Vec<Sample> = (0..=u8::MAX).map(Sample::from).collect();
+
+	group.bench_function(BenchmarkId::new(name, WINDOW_SIZE), |b| {
+		let mut samples = samples.iter().cycle();
+		b.iter(|| sma.add_sample(black_box(*samples.next().unwrap())))
+	});
+}
+
+fn bench_get_average<Sample: Copy + From<u8>, const WINDOW_SIZE: usize>(
+	group: &mut criterion::BenchmarkGroup<criterion::measurement::WallTime>,
+	name: &str,
+	mut sma: impl SMA<Sample, Sample, WINDOW_SIZE>,
+) {
+	// Fill the sample window, so that the whole of it is averaged
+	for sample in (0..=u8::MAX).cycle().take(WINDOW_SIZE) {
+		sma.add_sample(Sample::from(sample));
+	}
+
+	group.bench_function(BenchmarkId::new(name, WINDOW_SIZE), |b| {
+		b.iter(|| black_box(&sma).get_average())
+	});
+}
+
+fn add_sample(c: &mut Criterion) {
+	let mut group = c.benchmark_group("add_sample/f32");
+	bench_sma_impls!(group, bench_add_sample, f32, 8, 64, 1024);
+	group.finish();
+
+	let mut group = c.benchmark_group("add_sample/u32");
+	bench_sma_impls!(group, bench_add_sample, u32, 8, 64, 1024);
+	group.finish();
+}
+
+fn get_average(c: &mut Criterion) {
+	let mut group = c.benchmark_group("get_average/f32");
+	bench_sma_impls!(group, bench_get_average, f32, 8, 64, 1024);
+	group.finish();
+
+	let mut group = c.benchmark_group("get_average/u32");
+	bench_sma_impls!(group, bench_get_average, u32, 8, 64, 1024);
+	group.finish();
+}
+
+fn alternating_read(c: &mut Criterion) {
+	let mut group = c.benchmark_group("alternating_read/f32");
+	for window_size in [8, 64, 1024] {
+		let samples: Vec<f32> = (0..=u8::MAX).map(f32::from).collect();
+
+		// Reading through a contiguous slice rotates the deque whenever it has wrapped around
+		let mut deque = VecDeque::with_capacity(window_size);
+		group.bench_function(BenchmarkId::new("make_contiguous", window_size), |b| {
+			let mut samples = samples.iter().cycle();
+			b.iter(|| {
+				if deque.len() == window_size {
+					deque.pop_front();
+				}
+				deque.push_back(black_box(*samples.next().unwrap()));
+				black_box(deque.make_contiguous().iter().sum::<f32>())
+			})
+		});
+
+		let mut sma = DynamicSMA::<f32, f32>::new(window_size);
+		group.bench_function(BenchmarkId::new("for_each_sample", window_size), |b| {
+			let mut samples = samples.iter().cycle();
+			b.iter(|| {
+				sma.add_sample(black_box(*samples.next().unwrap()));
+				let mut sum = 0.0;
+				sma.for_each_sample(|sample| sum += sample);
+				black_box(sum)
+			})
+		});
+	}
+	group.finish();
+}
+
+criterion_group!(benches, add_sample, get_average, alternating_read);
+criterion_main!(benches);
@@ -0,0 +1,241 @@
+use crate::{common::cast_to_divisor_type, DivisorFromCount};
+use num_traits::Float;
+use std::collections::VecDeque;
+
+// The windowed variance must fall below this fraction of the variance target before the sample
+// window is allowed to grow again.
+const GROW_THRESHOLD_FACTOR: f64 = 0.5;
+
+/// A moving average with a sample window size that adapts, at runtime, to the variance of the
+/// samples in the sample window.
+///
+/// The sample window size is kept within the bounds given to
+/// [with_bounds](AdaptiveWindowMovingAverage::with_bounds), starting out at the upper bound. After
+/// each added sample, the population variance of the samples in the sample window is compared to
+/// the variance target set by
+/// [with_variance_target](AdaptiveWindowMovingAverage::with_variance_target):
+///  - If it exceeds the target, e.g. after a sudden change in the signal, the sample window shrinks
+///    by one sample, dropping the oldest sample, so that the average follows the signal closely.
+///  - If it falls below half of the target, i.e. the signal is calm, the sample window grows by one
+///    sample, meaning that the next added sample does not cause the oldest one to be dropped.
+///  - In between, the sample window size is left as it is.
+///
+/// The gap between the two thresholds is a hysteresis band, which keeps the sample window size
+/// from oscillating when the variance hovers around the target. Since the sample window only grows
+/// by retaining newly added samples, samples that were dropped when shrinking never reenter it.
+///
+/// Computing the variance requires a pass over the sample window, so adding a sample is an `O(n)`
+/// operation, while reading the average is `O(1)`. Only floating point `Sample` types are supported.
+#[derive(Clone, Debug)]
+pub struct AdaptiveWindowMovingAverage<Sample> {
+	samples: VecDeque<Sample>,
+	sum: Sample,
+	window_size: usize,
+	min_window_size: usize,
+	max_window_size: usize,
+	variance_target: Sample,
+}
+
+impl<Sample> AdaptiveWindowMovingAverage<Sample>
+where
+	Sample: Float + DivisorFromCount,
+{
+	/// Constructs a new [AdaptiveWindowMovingAverage] whose sample window size stays within
+	/// `min_window_size` and `max_window_size`, inclusive. Until a variance target is set, the
+	/// sample window never shrinks.
+	///
+	/// # Panics
+	///
+	/// Panics if `min_window_size` is zero or larger than `max_window_size`.
+	pub fn with_bounds(min_window_size: usize, max_window_size: usize) -> Self {
+		assert!(
+			min_window_size > 0,
+			"The minimum sample window size must be non-zero"
+		);
+		assert!(
+			min_window_size <= max_window_size,
+			"The minimum sample window size must not exceed the maximum sample window size"
+		);
+
+		Self {
+			samples: VecDeque::with_capacity(max_window_size),
+			sum: Sample::zero(),
+			window_size: max_window_size,
+			min_window_size,
+			max_window_size,
+			variance_target: Sample::infinity(),
+		}
+	}
+
+	/// Sets the variance target that the sample window size is adjusted against.
+	///
+	/// # Panics
+	///
+	/// Panics if `variance_target` is negative or NaN.
+	pub fn with_variance_target(mut self, variance_target: Sample) -> Self {
+		assert!(
+			variance_target >= Sample::zero(),
+			"The variance target must be non-negative"
+		);
+		self.variance_target = variance_target;
+		self
+	}
+
+	/// Adds a sample to the series of samples, and then adjusts the sample window size. If the
+	/// sample window is full, this will cause the oldest sample to be dropped, i.e. no longer
+	/// contribute to the average.
+	pub fn add_sample(&mut self, new_sample: Sample) {
+		self.sum = self.sum + new_sample;
+		self.samples.push_back(new_sample);
+		self.truncate();
+
+		let variance = self.get_variance();
+		if variance > self.variance_target {
+			self.window_size = self.min_window_size.max(self.window_size - 1);
+			self.truncate();
+		} else if variance < self.get_grow_threshold() {
+			self.window_size = self.max_window_size.min(self.window_size + 1);
+		}
+	}
+
+	/// Returns the average of all the samples in the sample window, or zero if it is empty.
+	pub fn get_average(&self) -> Sample {
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return Sample::zero();
+		}
+
+		self.sum / cast_to_divisor_type(num_samples)
+	}
+
+	/// Returns the population variance of the samples in the sample window, or zero if it is empty.
+	pub fn get_variance(&self) -> Sample {
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return Sample::zero();
+		}
+
+		let mean = self.get_average();
+		self.samples.iter().fold(Sample::zero(), |sum, sample| {
+			sum + (*sample - mean) * (*sample - mean)
+		}) / cast_to_divisor_type(num_samples)
+	}
+
+	/// Returns the variance target that the sample window size is adjusted against.
+	pub fn get_variance_target(&self) -> Sample {
+		self.variance_target
+	}
+
+	/// Returns the total number of samples currently in the in the sample window. This value never
+	/// exceeds the sample window size.
+	pub fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	/// Returns the current sample window size, i.e. the maximum number of samples that the sample
+	/// window holds until it is next adjusted.
+	pub fn get_sample_window_size(&self) -> usize {
+		self.window_size
+	}
+
+	/// Returns the lower and upper bound of the sample window size.
+	pub fn get_bounds(&self) -> (usize, usize) {
+		(self.min_window_size, self.max_window_size)
+	}
+
+	fn get_grow_threshold(&self) -> Sample {
+		self.variance_target * Sample::from(GROW_THRESHOLD_FACTOR).unwrap()
+	}
+
+	fn truncate(&mut self) {
+		while self.samples.len() > self.window_size {
+			if let Some(shifted_sample) = self.samples.pop_front() {
+				self.sum = self.sum - shifted_sample;
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn add_samples(average: &mut AdaptiveWindowMovingAverage<f64>, samples: &[f64]) {
+		for sample in samples {
+			average.add_sample(*sample);
+		}
+	}
+
+	#[test]
+	fn basics() {
+		let mut average = AdaptiveWindowMovingAverage::<f64>::with_bounds(2, 4);
+		assert_eq!(average.get_average(), 0.0);
+		assert_eq!(average.get_sample_window_size(), 4);
+		assert_eq!(average.get_bounds(), (2, 4));
+
+		// Without a variance target, this behaves like a plain SMA of the maximum window size
+		add_samples(&mut average, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+		assert_eq!(average.get_num_samples(), 4);
+		assert_eq!(average.get_average(), 4.5);
+		assert_eq!(average.get_variance(), 1.25);
+	}
+
+	#[test]
+	fn variance_spike_shrinks_and_calm_grows_window() {
+		let mut average =
+			AdaptiveWindowMovingAverage::<f64>::with_bounds(4, 16).with_variance_target(1.0);
+
+		let calm = [10.0; 32];
+		add_samples(&mut average, &calm);
+		assert_eq!(average.get_sample_window_size(), 16);
+		assert_eq!(average.get_average(), 10.0);
+
+		// A sudden spike in variance shrinks the sample window down to the lower bound
+		let spike: Vec<f64> = (0..32)
+			.map(|idx| if idx % 2 == 0 { 0.0 } else { 20.0 })
+			.collect();
+		add_samples(&mut average, &spike);
+		assert_eq!(average.get_sample_window_size(), 4);
+		assert_eq!(average.get_num_samples(), 4);
+
+		// A calm stretch grows the sample window back towards the upper bound
+		add_samples(&mut average, &calm[..8]);
+		let window_size = average.get_sample_window_size();
+		assert!(window_size > 4 && window_size < 16);
+		assert_eq!(average.get_average(), 10.0);
+
+		add_samples(&mut average, &calm);
+		assert_eq!(average.get_sample_window_size(), 16);
+		assert_eq!(average.get_num_samples(), 16);
+		assert_eq!(average.get_average(), 10.0);
+	}
+
+	#[test]
+	fn hysteresis() {
+		let mut average =
+			AdaptiveWindowMovingAverage::<f64>::with_bounds(2, 8).with_variance_target(1.0);
+
+		// A variance of 0.5625, between half the target and the target, leaves the size unchanged
+		add_samples(&mut average, &[0.0, 1.5, 0.0, 1.5, 0.0, 1.5, 0.0, 1.5]);
+		assert_eq!(average.get_sample_window_size(), 8);
+
+		let mut average =
+			AdaptiveWindowMovingAverage::<f64>::with_bounds(2, 8).with_variance_target(1.0);
+		add_samples(&mut average, &[0.0, 3.0, 0.0, 3.0]);
+		assert!(average.get_sample_window_size() < 8);
+	}
+
+	#[test]
+	#[should_panic(expected = "The minimum sample window size must not exceed")]
+	fn invalid_bounds() {
+		AdaptiveWindowMovingAverage::<f64>::with_bounds(4, 2);
+	}
+
+	#[test]
+	#[should_panic(expected = "The variance target must be non-negative")]
+	fn negative_variance_target() {
+		AdaptiveWindowMovingAverage::<f64>::with_bounds(2, 4).with_variance_target(-1.0);
+	}
+}
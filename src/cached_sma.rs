@@ -0,0 +1,152 @@
+use crate::{Iter, SMA};
+use std::cell::Cell;
+
+/// A wrapper around any [SMA] implementation that memoizes the average until the sample window
+/// next changes, so that repeatedly reading the average without adding samples in between is an
+/// `O(1)` operation. This is mostly useful for [NoSumSMA](crate::NoSumSMA), which otherwise sums
+/// the whole sample window on every read, in read-heavy loops.
+///
+/// Since the cached average is a full calculation by the wrapped implementation, just memoized,
+/// this does not introduce any accumulated rounding error. The cache is kept in a [Cell], which
+/// means that a [CachedSMA] is neither [Copy] nor [Sync]. Wrap it in a
+/// [SharedSMA](crate::SharedSMA) to share it between threads.
+#[derive(Clone, Debug, Default)]
+pub struct CachedSMA<M, Sample: Copy> {
+	sma: M,
+	// `None` whenever the sample window has changed since the average was last calculated
+	cached_average: Cell<Option<Sample>>,
+}
+
+impl<M, Sample: Copy> CachedSMA<M, Sample> {
+	/// Wraps the given [SMA] implementation.
+	pub fn new(sma: M) -> Self {
+		Self {
+			sma,
+			cached_average: Cell::new(None),
+		}
+	}
+
+	/// Returns a reference to the wrapped [SMA] implementation.
+	pub fn get_ref(&self) -> &M {
+		&self.sma
+	}
+
+	/// Returns a mutable reference to the wrapped [SMA] implementation, giving access to its full
+	/// interface, e.g. to [retain](crate::NoSumSMA::retain) samples. Since the sample window may be
+	/// changed through the reference, the cached average is discarded.
+	pub fn get_mut(&mut self) -> &mut M {
+		self.cached_average.set(None);
+		&mut self.sma
+	}
+
+	/// Consumes the wrapper, returning the wrapped [SMA] implementation.
+	pub fn into_inner(self) -> M {
+		self.sma
+	}
+}
+
+impl<M, Sample, Divisor, const WINDOW_SIZE: usize> SMA<Sample, Divisor, WINDOW_SIZE>
+	for CachedSMA<M, Sample>
+where
+	M: SMA<Sample, Divisor, WINDOW_SIZE>,
+	Sample: Copy,
+{
+	fn add_sample(&mut self, new_sample: Sample) {
+		self.sma.add_sample(new_sample);
+		self.cached_average.set(None);
+	}
+
+	fn get_average(&self) -> Sample {
+		if let Some(average) = self.cached_average.get() {
+			return average;
+		}
+
+		let average = self.sma.get_average();
+		self.cached_average.set(Some(average));
+		average
+	}
+
+	fn get_num_samples(&self) -> usize {
+		self.sma.get_num_samples()
+	}
+
+	fn get_sample_window_size(&self) -> usize {
+		self.sma.get_sample_window_size()
+	}
+
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
+		self.sma.get_sample_window_iter()
+	}
+
+	fn get_most_recent_sample(&self) -> Option<Sample> {
+		self.sma.get_most_recent_sample()
+	}
+
+	fn get_oldest_sample(&self) -> Option<Sample> {
+		self.sma.get_oldest_sample()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::NoSumSMA;
+
+	#[test]
+	fn cached_average() {
+		let mut sma = CachedSMA::new(NoSumSMA::<u32, u32, 3>::from_zero(0));
+		assert_eq!(sma.get_average(), 0);
+
+		sma.add_sample(3);
+		assert_eq!(sma.get_average(), 3);
+		assert_eq!(sma.get_average(), 3);
+
+		sma.add_sample(9);
+		assert_eq!(sma.get_average(), 6);
+		assert_eq!(sma.get_oldest_sample(), Some(3));
+		assert_eq!(sma.get_most_recent_sample(), Some(9));
+
+		let clone = sma.clone();
+		sma.get_mut().retain(|sample| *sample < 5);
+		assert_eq!(sma.get_average(), 3);
+		assert_eq!(clone.get_average(), 6);
+
+		sma.get_mut().restore(clone.get_ref().snapshot());
+		assert_eq!(sma.get_average(), 6);
+
+		assert_eq!(sma.get_mut().drain().0, 6);
+		assert_eq!(sma.get_average(), 0);
+
+		sma.add_samples(&[1, 2, 3, 4]);
+		assert_eq!(sma.get_average(), 3);
+		assert_eq!(sma.into_inner().get_average(), 3);
+	}
+
+	#[test]
+	fn cache_invalidation() {
+		let mut sma = CachedSMA::new(NoSumSMA::<f64, f64, 4>::from_zero(0.0));
+		sma.add_samples(&[1.0, 2.0, 3.0]);
+		assert_eq!(sma.get_average(), 2.0);
+
+		// Adding a sample
+		sma.add_sample(6.0);
+		assert_eq!(sma.get_average(), 3.0);
+		sma.add_sample(9.0);
+		assert_eq!(sma.get_average(), 5.0);
+
+		// Mutating the wrapped SMA
+		sma.get_mut().add_sample(14.0);
+		assert_eq!(sma.get_average(), 8.0);
+		assert_eq!(sma.get_mut().shift_out(), Some(3.0));
+		assert_eq!(sma.get_average(), 29.0 / 3.0);
+
+		// Clearing the wrapped SMA
+		sma.get_mut().clear();
+		assert_eq!(sma.get_average(), 0.0);
+		assert_eq!(sma.get_num_samples(), 0);
+		assert_eq!(sma.get_oldest_sample(), None);
+
+		sma.add_sample(7.0);
+		assert_eq!(sma.get_average(), 7.0);
+	}
+}
@@ -0,0 +1,180 @@
+use crate::{common::cast_to_divisor_type, DivisorFromCount};
+use num_traits::Zero;
+use std::{
+	marker::PhantomData,
+	ops::{AddAssign, Div},
+};
+
+/// How [CenteredMovingAverage] handles the positions near the start and end of its input, whose
+/// centered sample windows extend beyond the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EdgeHandling {
+	/// Averages only the samples of the sample window that lie within the input, i.e. the sample
+	/// window shrinks towards the edges.
+	Shrink,
+	/// Pads the input by repeating its first and last sample, so that every sample window contains
+	/// `WINDOW_SIZE` samples.
+	Pad,
+}
+
+/// Calculates the centered moving average of a whole series of samples at once, i.e. for each
+/// position, the average of the `WINDOW_SIZE` samples centered around it. This makes it suitable for
+/// offline analysis only, since the average at a position depends on later samples.
+///
+/// Unlike the other moving averages of this crate, this is not a streaming accumulator, which
+/// samples are added to one by one. Instead, all averages are calculated by
+/// [process](CenteredMovingAverage::process), which does not modify the [CenteredMovingAverage].
+///
+/// For an odd `WINDOW_SIZE`, the sample window contains equally many samples before and after its
+/// center. For an even `WINDOW_SIZE`, it contains one more sample before its center than after it.
+/// Either way, the centered moving average at position `i` equals the trailing simple moving average
+/// at position `i + (WINDOW_SIZE - 1) / 2`, as long as neither sample window extends beyond the
+/// input.
+#[derive(Clone, Copy, Debug)]
+pub struct CenteredMovingAverage<Sample, Divisor, const WINDOW_SIZE: usize> {
+	edge_handling: EdgeHandling,
+	_marker: PhantomData<(Sample, Divisor)>,
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> CenteredMovingAverage<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Zero + AddAssign + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	/// Constructs a new [CenteredMovingAverage] with window size `WINDOW_SIZE`, which shrinks the
+	/// sample windows at the edges of the input, see [EdgeHandling::Shrink].
+	pub fn new() -> Self {
+		Self {
+			edge_handling: EdgeHandling::Shrink,
+			_marker: PhantomData,
+		}
+	}
+
+	/// Sets how the positions near the edges of the input are handled.
+	pub fn with_edge_handling(mut self, edge_handling: EdgeHandling) -> Self {
+		self.edge_handling = edge_handling;
+		self
+	}
+
+	/// Returns how the positions near the edges of the input are handled.
+	pub fn get_edge_handling(&self) -> EdgeHandling {
+		self.edge_handling
+	}
+
+	/// Returns the centered moving average at each position of `input`. Calculating each average is
+	/// an `O(WINDOW_SIZE)` operation, so no rounding error accumulates across positions. For a
+	/// `WINDOW_SIZE` of zero, all averages are zero.
+	pub fn process(&self, input: &[Sample]) -> Vec<Sample> {
+		if WINDOW_SIZE == 0 {
+			return vec![Sample::zero(); input.len()];
+		}
+
+		let last_idx = input.len().saturating_sub(1);
+
+		(0..input.len())
+			.map(|idx| {
+				// The sample window starts `WINDOW_SIZE / 2` samples before `idx`, and may extend
+				// beyond either end of the input
+				let start_idx = idx as isize - (WINDOW_SIZE / 2) as isize;
+				let end_idx = start_idx + WINDOW_SIZE as isize;
+
+				match self.edge_handling {
+					EdgeHandling::Shrink => {
+						let window =
+							&input[start_idx.max(0) as usize..(end_idx as usize).min(input.len())];
+						let mut sum = Sample::zero();
+						for sample in window {
+							sum += *sample;
+						}
+						sum / cast_to_divisor_type(window.len())
+					}
+					EdgeHandling::Pad => {
+						let mut sum = Sample::zero();
+						for padded_idx in start_idx..end_idx {
+							sum += input[(padded_idx.max(0) as usize).min(last_idx)];
+						}
+						sum / cast_to_divisor_type(WINDOW_SIZE)
+					}
+				}
+			})
+			.collect()
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> Default
+	for CenteredMovingAverage<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Zero + AddAssign + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{SingleSumSMA, SMA};
+
+	fn assert_matches_shifted_trailing_sma<const WINDOW_SIZE: usize>(input: &[u32]) {
+		let centered = CenteredMovingAverage::<u32, u32, WINDOW_SIZE>::new().process(input);
+
+		let mut sma = SingleSumSMA::<u32, u32, WINDOW_SIZE>::new();
+		let trailing: Vec<u32> = input
+			.iter()
+			.map(|sample| {
+				sma.add_sample(*sample);
+				sma.get_average()
+			})
+			.collect();
+
+		let shift = (WINDOW_SIZE - 1) / 2;
+		let first_full_idx = WINDOW_SIZE / 2;
+		let last_full_idx = input.len() - 1 - shift;
+		for idx in first_full_idx..=last_full_idx {
+			assert_eq!(centered[idx], trailing[idx + shift]);
+		}
+	}
+
+	#[test]
+	fn matches_shifted_trailing_sma() {
+		let input: Vec<u32> = (0..40).map(|sample| sample * 37 % 101).collect();
+
+		assert_matches_shifted_trailing_sma::<1>(&input);
+		assert_matches_shifted_trailing_sma::<2>(&input);
+		assert_matches_shifted_trailing_sma::<3>(&input);
+		assert_matches_shifted_trailing_sma::<4>(&input);
+		assert_matches_shifted_trailing_sma::<7>(&input);
+	}
+
+	#[test]
+	fn edge_handling() {
+		let input = [3.0, 6.0, 9.0, 12.0];
+
+		let shrink = CenteredMovingAverage::<f64, f64, 3>::new();
+		assert_eq!(shrink.get_edge_handling(), EdgeHandling::Shrink);
+		assert_eq!(shrink.process(&input), vec![4.5, 6.0, 9.0, 10.5]);
+
+		let pad = shrink.with_edge_handling(EdgeHandling::Pad);
+		assert_eq!(pad.process(&input), vec![4.0, 6.0, 9.0, 11.0]);
+
+		// An even window size extends one sample further before the center than after it
+		let pad = CenteredMovingAverage::<f64, f64, 4>::new().with_edge_handling(EdgeHandling::Pad);
+		assert_eq!(pad.process(&input), vec![3.75, 5.25, 7.5, 9.75]);
+	}
+
+	#[test]
+	fn edge_cases() {
+		let average = CenteredMovingAverage::<f64, f64, 5>::new();
+		assert!(average.process(&[]).is_empty());
+		assert_eq!(average.process(&[2.0]), vec![2.0]);
+
+		let pad = average.with_edge_handling(EdgeHandling::Pad);
+		assert!(pad.process(&[]).is_empty());
+		assert_eq!(pad.process(&[2.0]), vec![2.0]);
+
+		let zero_sized = CenteredMovingAverage::<f64, f64, 0>::new();
+		assert_eq!(zero_sized.process(&[1.0, 2.0]), vec![0.0, 0.0]);
+	}
+}
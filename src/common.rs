@@ -1,15 +1,51 @@
-use std::any::type_name;
-
-use num_traits::FromPrimitive;
-
-pub fn cast_to_divisor_type<Divisor: FromPrimitive>(divisor: usize) -> Divisor {
-	Divisor::from_usize(divisor).unwrap_or_else(|| {
-		panic!(
-			"Failed to create a divisor of type {} from {}",
-			type_name::<Divisor>(),
-			divisor
-		)
-	})
+use std::{any::type_name, ops::AddAssign};
+
+use crate::{DivisorError, DivisorFromCount};
+
+pub fn cast_to_divisor_type<Divisor: DivisorFromCount>(divisor: usize) -> Divisor {
+	try_cast_to_divisor_type(divisor).unwrap_or_else(|error| panic!("{}", error))
+}
+
+pub fn try_cast_to_divisor_type<Divisor: DivisorFromCount>(
+	divisor: usize,
+) -> Result<Divisor, DivisorError> {
+	Divisor::from_count(divisor).ok_or_else(|| DivisorError::new(divisor, type_name::<Divisor>()))
+}
+
+// Sums the samples by recursively splitting them in halves and adding up the sums of the halves,
+// which makes the rounding error grow with O(log(N)) rather than O(N). The samples are given as two
+// slices, e.g. the two halves of a ring buffer, which are summed as if they were one contiguous
+// slice, so that the sum only depends on the order of the samples, not on where they are split.
+pub fn pairwise_sum<Sample: Clone + AddAssign>(
+	zero: &Sample,
+	older_samples: &[Sample],
+	newer_samples: &[Sample],
+) -> Sample {
+	const BASE_CASE_LEN: usize = 16;
+
+	let num_samples = older_samples.len() + newer_samples.len();
+
+	if num_samples <= BASE_CASE_LEN {
+		let mut sum = zero.clone();
+		for sample in older_samples.iter().chain(newer_samples) {
+			sum += sample.clone();
+		}
+		return sum;
+	}
+
+	let num_first_half_samples = num_samples / 2;
+	let mut sum;
+	if num_first_half_samples <= older_samples.len() {
+		let (first_half, older_second_half) = older_samples.split_at(num_first_half_samples);
+		sum = pairwise_sum(zero, first_half, &[]);
+		sum += pairwise_sum(zero, older_second_half, newer_samples);
+	} else {
+		let (newer_first_half, second_half) =
+			newer_samples.split_at(num_first_half_samples - older_samples.len());
+		sum = pairwise_sum(zero, older_samples, newer_first_half);
+		sum += pairwise_sum(zero, second_half, &[]);
+	}
+	sum
 }
 
 pub fn wrapping_add<const MAX_VAL: usize>(lhs: usize, rhs: usize) -> usize {
@@ -40,4 +76,37 @@ mod tests {
 	fn cast_to_divisor_type_fail() {
 		cast_to_divisor_type::<u32>(u32::MAX as usize + 1);
 	}
+
+	#[test]
+	fn try_cast_to_divisor_type_fail() {
+		let error = try_cast_to_divisor_type::<u8>(256).unwrap_err();
+		assert_eq!(error.count(), 256);
+		assert_eq!(error.divisor_type_name(), "u8");
+		assert_eq!(
+			error.to_string(),
+			"Failed to create a divisor of type u8 from 256"
+		);
+	}
+
+	#[test]
+	fn pairwise_sum_matches_sum() {
+		let samples: Vec<u64> = (0..100).collect();
+		for num_samples in 0..=samples.len() {
+			let samples = &samples[..num_samples];
+			assert_eq!(pairwise_sum(&0, samples, &[]), samples.iter().sum::<u64>());
+		}
+	}
+
+	#[test]
+	fn pairwise_sum_independent_of_split() {
+		let samples: Vec<f64> = (0..100).map(|idx| 1.0 / f64::from(idx + 1)).collect();
+		for num_samples in 0..=samples.len() {
+			let samples = &samples[..num_samples];
+			let sum = pairwise_sum(&0.0, samples, &[]);
+			for split_idx in 0..=num_samples {
+				let (older_samples, newer_samples) = samples.split_at(split_idx);
+				assert_eq!(pairwise_sum(&0.0, older_samples, newer_samples), sum);
+			}
+		}
+	}
 }
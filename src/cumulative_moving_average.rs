@@ -0,0 +1,110 @@
+use crate::{common::cast_to_divisor_type, DivisorFromCount};
+use num_traits::Zero;
+use std::{
+	marker::{self, PhantomData},
+	ops::{Add, Div, Sub},
+};
+
+/// Calculates the [cumulative moving average](https://en.wikipedia.org/wiki/Moving_average#Cumulative_average)
+/// (CMA) of a series of samples, i.e. the average of all samples added so far, rather than of the
+/// samples in a sliding sample window.
+///
+/// No samples are stored, only the sample count and the current average, which is updated as
+/// `average + (new_sample - average) / num_samples` every time a sample is added. This keeps the
+/// magnitude of the stored value close to that of the samples, which limits the floating point
+/// rounding errors compared to keeping a running sum. Since there is no sample window, this type
+/// does not implement the [SMA](crate::SMA) trait.
+///
+/// Note that the division truncates for integer `Sample` types, an error that accumulates with
+/// every added sample, and that `new_sample - average` underflows for unsigned `Sample` types,
+/// so this type is mainly useful for floating point samples.
+#[derive(Clone, Copy, Debug)]
+pub struct CumulativeMovingAverage<Sample, Divisor> {
+	average: Sample,
+	num_samples: usize,
+	_marker: marker::PhantomData<Divisor>,
+}
+
+impl<Sample, Divisor> CumulativeMovingAverage<Sample, Divisor>
+where
+	Sample: Copy + Add<Output = Sample> + Sub<Output = Sample> + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	/// Adds a sample to the series of samples.
+	pub fn add_sample(&mut self, new_sample: Sample) {
+		self.num_samples += 1;
+		self.average = self.average
+			+ (new_sample - self.average) / cast_to_divisor_type::<Divisor>(self.num_samples);
+	}
+
+	/// Returns the average of all samples added so far.
+	pub fn get_average(&self) -> Sample {
+		self.average
+	}
+
+	/// Returns the total number of samples added so far.
+	pub fn get_num_samples(&self) -> usize {
+		self.num_samples
+	}
+}
+
+impl<Sample: Copy + Zero, Divisor> CumulativeMovingAverage<Sample, Divisor> {
+	/// Constructs a new [CumulativeMovingAverage]. This constructor is only available for `Sample`
+	/// types that implement [num_traits::Zero]. If the `Sample` type does not, use the
+	/// [from_zero](CumulativeMovingAverage::from_zero) constructor instead.
+	///
+	/// Note that the `Divisor` type usually cannot be derived by the compiler when using this
+	/// constructor and must be explicitly stated, even if it is the same as the `Sample` type.
+	pub fn new() -> Self {
+		Self::from_zero(Sample::zero())
+	}
+}
+
+impl<Sample: Copy + Zero, Divisor> Default for CumulativeMovingAverage<Sample, Divisor> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<Sample: Copy, Divisor> CumulativeMovingAverage<Sample, Divisor> {
+	/// Constructs a new [CumulativeMovingAverage] from the given `zero` sample. If the `Sample` type
+	/// implements [num_traits::Zero], the [new](CumulativeMovingAverage::new) constructor might be
+	/// preferable to this.
+	pub const fn from_zero(zero: Sample) -> Self {
+		Self {
+			average: zero,
+			num_samples: 0,
+			_marker: PhantomData,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{NoSumSMA, SMA};
+
+	#[test]
+	fn matches_sma_within_window() {
+		let mut cma = CumulativeMovingAverage::<f64, f64>::new();
+		let mut sma = NoSumSMA::<f64, f64, 10>::new();
+		assert_eq!(cma.get_average(), 0.0);
+
+		for sample in [4.0, 8.0, 3.0, 7.0, 11.0, 0.0, -23.0] {
+			cma.add_sample(sample);
+			sma.add_sample(sample);
+			assert!((cma.get_average() - sma.get_average()).abs() < 1e-12);
+			assert_eq!(cma.get_num_samples(), sma.get_num_samples());
+		}
+	}
+
+	#[test]
+	fn all_time_average() {
+		let mut cma = CumulativeMovingAverage::<f32, f32>::new();
+		for sample in 1..=100 {
+			cma.add_sample(sample as f32);
+		}
+		assert!((cma.get_average() - 50.5).abs() < 1e-4);
+		assert_eq!(cma.get_num_samples(), 100);
+	}
+}
@@ -0,0 +1,214 @@
+use super::SMA;
+use crate::{ring_buffer::RingBuffer, Iter};
+use num_traits::{Float, Zero};
+use std::ops::{Add, Div, Mul, Sub};
+
+/// Calculates a weighted moving average of the samples in the sample window, whose weights decay
+/// geometrically with the age of the samples: the most recent sample has weight `1`, the sample
+/// before it weight `r`, then `r^2` and so on, down to `r^(N - 1)` for the oldest sample, `N` being
+/// the number of samples in the sample window. Unlike for an
+/// [ExponentialMovingAverage](crate::ExponentialMovingAverage), the weight of a sample drops to zero
+/// once it leaves the sample window.
+///
+/// The weighted sum of the samples in the sample window is cached. When a sample is added, the
+/// cached sum is multiplied by `r`, which shifts all weights by one position, after which the
+/// dropped sample's term is subtracted and the new sample added. This makes adding a sample and
+/// reading the average `O(1)` operations, at the cost of floating point rounding errors
+/// accumulating in the cached sum, like for [SingleSumSMA](crate::SingleSumSMA). For `r < 1`,
+/// every rounding error is damped by the multiplication with `r`, so the accumulated error stays
+/// bounded. The weight of the dropped sample and the sums of the weights are calculated once, when
+/// the instance is constructed.
+#[derive(Clone, Copy, Debug)]
+pub struct DecayWeightedMovingAverage<Sample, Divisor, const WINDOW_SIZE: usize> {
+	samples: RingBuffer<Sample, WINDOW_SIZE>,
+	weighted_sum: Sample,
+	decay: Divisor,
+	// r^WINDOW_SIZE, the weight that the oldest sample would have after being shifted
+	shifted_sample_weight: Divisor,
+	// The sums of the weights of `1` through `WINDOW_SIZE` samples
+	weight_sums: [Divisor; WINDOW_SIZE],
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> SMA<Sample, Divisor, WINDOW_SIZE>
+	for DecayWeightedMovingAverage<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy
+		+ Add<Output = Sample>
+		+ Sub<Output = Sample>
+		+ Mul<Divisor, Output = Sample>
+		+ Div<Divisor, Output = Sample>,
+	Divisor: Float,
+{
+	fn add_sample(&mut self, new_sample: Sample) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+
+		self.weighted_sum = match self.samples.shift(new_sample) {
+			Some(shifted_sample) => {
+				self.weighted_sum * self.decay - shifted_sample * self.shifted_sample_weight
+					+ new_sample
+			}
+			None => self.weighted_sum * self.decay + new_sample,
+		};
+	}
+
+	fn get_average(&self) -> Sample {
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return self.weighted_sum;
+		}
+
+		self.weighted_sum / self.weight_sums[num_samples - 1]
+	}
+
+	fn get_most_recent_sample(&self) -> Option<Sample> {
+		self.samples.front().cloned()
+	}
+
+	fn get_oldest_sample(&self) -> Option<Sample> {
+		self.samples.back().cloned()
+	}
+
+	fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
+		self.samples.iter()
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize>
+	DecayWeightedMovingAverage<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy,
+	Divisor: Float,
+{
+	/// Constructs a new [DecayWeightedMovingAverage] with window size `WINDOW_SIZE` and decay factor
+	/// `decay`. This constructor is only available for `Sample` types that implement
+	/// [num_traits::Zero]. If the `Sample` type does not, use the
+	/// [with_decay_from_zero](DecayWeightedMovingAverage::with_decay_from_zero) constructor instead.
+	///
+	/// # Panics
+	///
+	/// Panics if `decay` does not lie in the interval `(0, 1]`.
+	pub fn with_decay(decay: Divisor) -> Self
+	where
+		Sample: Zero,
+	{
+		Self::with_decay_from_zero(Sample::zero(), decay)
+	}
+
+	/// Constructs a new [DecayWeightedMovingAverage] with window size `WINDOW_SIZE` and decay factor
+	/// `decay` from the given `zero` sample.
+	///
+	/// # Panics
+	///
+	/// Panics if `decay` does not lie in the interval `(0, 1]`.
+	pub fn with_decay_from_zero(zero: Sample, decay: Divisor) -> Self {
+		assert!(
+			Divisor::zero() < decay && decay <= Divisor::one(),
+			"The decay factor must lie in the interval (0, 1]"
+		);
+
+		let mut weight_sums = [Divisor::zero(); WINDOW_SIZE];
+		let mut weight = Divisor::one();
+		let mut weight_sum = Divisor::zero();
+		for sum in weight_sums.iter_mut() {
+			weight_sum = weight_sum + weight;
+			*sum = weight_sum;
+			weight = weight * decay;
+		}
+
+		Self {
+			samples: RingBuffer::new(zero),
+			weighted_sum: zero,
+			decay,
+			shifted_sample_weight: weight,
+			weight_sums,
+		}
+	}
+
+	/// Returns the decay factor, i.e. the ratio between the weights of two consecutive samples.
+	pub fn get_decay(&self) -> Divisor {
+		self.decay
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn brute_force_average(window: &[f64], decay: f64) -> f64 {
+		let (weighted_sum, weight_sum) = window.iter().rev().enumerate().fold(
+			(0.0, 0.0),
+			|(weighted_sum, weight_sum), (age, sample)| {
+				let weight = decay.powi(age as i32);
+				(weighted_sum + sample * weight, weight_sum + weight)
+			},
+		);
+		weighted_sum / weight_sum
+	}
+
+	#[test]
+	fn basics() {
+		let mut ma = DecayWeightedMovingAverage::<f64, f64, 3>::with_decay(0.5);
+		assert_eq!(ma.get_average(), 0.0);
+		assert_eq!(ma.get_decay(), 0.5);
+
+		ma.add_sample(8.0);
+		assert_eq!(ma.get_average(), 8.0);
+
+		// (4 + 0.5 * 8) / 1.5
+		ma.add_sample(4.0);
+		assert!((ma.get_average() - 8.0 / 1.5).abs() < 1e-12);
+
+		// (1 + 0.5 * 4 + 0.25 * 8) / 1.75
+		ma.add_sample(1.0);
+		assert!((ma.get_average() - 5.0 / 1.75).abs() < 1e-12);
+
+		// (2 + 0.5 * 1 + 0.25 * 4) / 1.75
+		ma.add_sample(2.0);
+		assert!((ma.get_average() - 3.5 / 1.75).abs() < 1e-12);
+	}
+
+	#[test]
+	fn matches_brute_force() {
+		use rand::{distributions::Uniform, rngs::SmallRng, Rng, SeedableRng};
+
+		let samples: Vec<f64> = SmallRng::seed_from_u64(0xCAFEBABE)
+			.sample_iter(&Uniform::from(-100.0..100.0))
+			.take(1000)
+			.collect();
+
+		for decay in [0.1, 0.5, 0.9, 1.0] {
+			let mut ma = DecayWeightedMovingAverage::<f64, f64, 10>::with_decay(decay);
+			for (idx, sample) in samples.iter().enumerate() {
+				ma.add_sample(*sample);
+
+				let window = &samples[(idx + 1).saturating_sub(10)..=idx];
+				assert!((ma.get_average() - brute_force_average(window, decay)).abs() < 1e-9);
+			}
+		}
+	}
+
+	#[test]
+	#[should_panic(expected = "The decay factor must lie in the interval (0, 1]")]
+	fn invalid_decay() {
+		DecayWeightedMovingAverage::<f64, f64, 3>::with_decay(1.5);
+	}
+
+	#[test]
+	fn edge_case_zero_sized() {
+		let mut ma = DecayWeightedMovingAverage::<f32, f32, 0>::with_decay(0.5);
+		ma.add_sample(1.0);
+		assert_eq!(ma.get_average(), 0.0);
+		assert_eq!(ma.get_num_samples(), 0);
+	}
+}
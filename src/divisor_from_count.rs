@@ -0,0 +1,89 @@
+#[cfg(feature = "num-traits")]
+use num_traits::FromPrimitive;
+
+/// Conversion from a sample count to the `Divisor` type, which the sum of the samples in a sample
+/// window is divided by to get their average.
+///
+/// This trait is implemented for all types that implement [num_traits::FromPrimitive], which
+/// includes all primitive numeric types, so existing code does not need to change. Scalar types
+/// that can represent a sample count, but do not implement [num_traits::FromPrimitive], can
+/// implement this trait directly in order to be used as `Divisor` type.
+///
+/// Wide integer types, such as `i64`, `u128` and `i128`, can be used as both `Sample` and `Divisor`
+/// type. Since a sample count never exceeds the size of a sample window held in memory, converting
+/// it to any integer type at least as wide as `usize` cannot fail in practice. Conversions to
+/// narrower types, e.g. `u8`, fail for counts that the type can't represent, which causes a panic
+/// in [get_average](crate::SMA::get_average), but can be handled using
+/// [try_get_average](crate::SMA::try_get_average).
+///
+/// Without the `num-traits` feature, this trait is implemented for the primitive numeric types
+/// only.
+///
+/// ```
+/// # use simple_moving_average::{DivisorFromCount, SMA, SingleSumSMA};
+/// # use std::ops::{AddAssign, Div, SubAssign};
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct Meters(f64);
+/// # impl AddAssign for Meters { fn add_assign(&mut self, rhs: Self) { self.0 += rhs.0 } }
+/// # impl SubAssign for Meters { fn sub_assign(&mut self, rhs: Self) { self.0 -= rhs.0 } }
+///
+/// struct Count(f64);
+///
+/// impl DivisorFromCount for Count {
+///     fn from_count(count: usize) -> Option<Self> {
+///         Some(Count(count as f64))
+///     }
+/// }
+///
+/// impl Div<Count> for Meters {
+///     type Output = Meters;
+///     fn div(self, rhs: Count) -> Meters {
+///         Meters(self.0 / rhs.0)
+///     }
+/// }
+///
+/// let mut ma = SingleSumSMA::<_, Count, 2>::from_zero(Meters(0.0));
+/// ma.add_sample(Meters(1.0));
+/// ma.add_sample(Meters(2.0));
+/// assert_eq!(ma.get_average(), Meters(1.5));
+/// ```
+pub trait DivisorFromCount: Sized {
+	/// Converts a sample count to the `Divisor` type, returning `None` if the count can't be
+	/// represented by the type.
+	fn from_count(count: usize) -> Option<Self>;
+}
+
+#[cfg(feature = "num-traits")]
+impl<Divisor: FromPrimitive> DivisorFromCount for Divisor {
+	fn from_count(count: usize) -> Option<Self> {
+		Divisor::from_usize(count)
+	}
+}
+
+#[cfg(not(feature = "num-traits"))]
+macro_rules! impl_divisor_from_count_for_int {
+	($($int:ty),*) => {$(
+		impl DivisorFromCount for $int {
+			fn from_count(count: usize) -> Option<Self> {
+				<$int as std::convert::TryFrom<usize>>::try_from(count).ok()
+			}
+		}
+	)*};
+}
+
+#[cfg(not(feature = "num-traits"))]
+impl_divisor_from_count_for_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+#[cfg(not(feature = "num-traits"))]
+impl DivisorFromCount for f32 {
+	fn from_count(count: usize) -> Option<Self> {
+		Some(count as f32)
+	}
+}
+
+#[cfg(not(feature = "num-traits"))]
+impl DivisorFromCount for f64 {
+	fn from_count(count: usize) -> Option<Self> {
+		Some(count as f64)
+	}
+}
@@ -0,0 +1,99 @@
+use crate::ExponentialMovingAverage;
+use num_traits::{Float, Zero};
+use std::ops::{Add, Mul, Sub};
+
+/// Calculates the [double exponential moving average](https://en.wikipedia.org/wiki/Double_exponential_moving_average)
+/// (DEMA) of a series of samples, defined as `2 * EMA(samples) - EMA(EMA(samples))`, where both
+/// [ExponentialMovingAverage] stages use the same smoothing factor. This cancels out much of the
+/// lag of a single EMA, at the cost of overshooting when the samples change abruptly.
+#[derive(Clone, Copy, Debug)]
+pub struct DoubleExponentialMovingAverage<Sample, Divisor> {
+	ema: ExponentialMovingAverage<Sample, Divisor>,
+	ema_of_ema: ExponentialMovingAverage<Sample, Divisor>,
+}
+
+impl<Sample, Divisor> DoubleExponentialMovingAverage<Sample, Divisor>
+where
+	Sample: Copy + Add<Output = Sample> + Sub<Output = Sample> + Mul<Divisor, Output = Sample>,
+	Divisor: Float,
+{
+	/// Adds a sample to the series of samples.
+	pub fn add_sample(&mut self, new_sample: Sample) {
+		self.ema.add_sample(new_sample);
+		self.ema_of_ema.add_sample(self.ema.get_average());
+	}
+
+	/// Returns the double exponential moving average of all samples added so far.
+	pub fn get_average(&self) -> Sample {
+		let ema = self.ema.get_average();
+		ema + ema - self.ema_of_ema.get_average()
+	}
+
+	/// Returns the total number of samples added so far.
+	pub fn get_num_samples(&self) -> usize {
+		self.ema.get_num_samples()
+	}
+}
+
+impl<Sample: Copy + Zero, Divisor: Float> DoubleExponentialMovingAverage<Sample, Divisor> {
+	/// Constructs a new [DoubleExponentialMovingAverage] with smoothing factor `alpha`. This
+	/// constructor is only available for `Sample` types that implement [num_traits::Zero]. If the
+	/// `Sample` type does not, use the [from_zero](DoubleExponentialMovingAverage::from_zero)
+	/// constructor instead.
+	///
+	/// # Panics
+	///
+	/// Panics if `alpha` does not lie in the interval `(0, 1]`.
+	pub fn new(alpha: Divisor) -> Self {
+		Self::from_zero(Sample::zero(), alpha)
+	}
+}
+
+impl<Sample: Copy, Divisor: Float> DoubleExponentialMovingAverage<Sample, Divisor> {
+	/// Constructs a new [DoubleExponentialMovingAverage] with smoothing factor `alpha` from the
+	/// given `zero` sample. If the `Sample` type implements [num_traits::Zero], the
+	/// [new](DoubleExponentialMovingAverage::new) constructor might be preferable to this.
+	///
+	/// # Panics
+	///
+	/// Panics if `alpha` does not lie in the interval `(0, 1]`.
+	pub fn from_zero(zero: Sample, alpha: Divisor) -> Self {
+		Self {
+			ema: ExponentialMovingAverage::from_zero(zero, alpha),
+			ema_of_ema: ExponentialMovingAverage::from_zero(zero, alpha),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn basics() {
+		let mut dema = DoubleExponentialMovingAverage::<f64, f64>::new(0.5);
+		assert_eq!(dema.get_average(), 0.0);
+
+		dema.add_sample(4.0);
+		assert_eq!(dema.get_average(), 4.0);
+
+		// EMA = 6, EMA(EMA) = 5
+		dema.add_sample(8.0);
+		assert_eq!(dema.get_average(), 7.0);
+		assert_eq!(dema.get_num_samples(), 2);
+	}
+
+	#[test]
+	fn step_response_lag() {
+		let mut ema = ExponentialMovingAverage::<f64, f64>::new(0.2);
+		let mut dema = DoubleExponentialMovingAverage::<f64, f64>::new(0.2);
+		ema.add_sample(0.0);
+		dema.add_sample(0.0);
+
+		for _ in 0..5 {
+			ema.add_sample(1.0);
+			dema.add_sample(1.0);
+			assert!((1.0 - dema.get_average()).abs() < (1.0 - ema.get_average()).abs());
+		}
+	}
+}
@@ -0,0 +1,147 @@
+use crate::{common::cast_to_divisor_type, DivisorFromCount};
+#[cfg(feature = "num-traits")]
+use num_traits::Zero;
+use std::{
+	collections::{vec_deque, VecDeque},
+	marker::{self, PhantomData},
+	ops::{AddAssign, Div, SubAssign},
+};
+
+/// An SMA implementation with a sample window size that is set at runtime, rather than at compile
+/// time. Apart from that, it behaves identically to [SingleSumSMA](crate::SingleSumSMA).
+///
+/// Since the [SMA](crate::SMA) trait is tied to a compile time sample window size, this type does
+/// not implement it, but provides the same interface through inherent methods instead. It can
+/// therefore not be used as a `dyn SMA`, and when wrapped in a [SharedSMA](crate::SharedSMA), it
+/// is only accessible through [lock](crate::SharedSMA::lock). The samples are stored in a
+/// [VecDeque], which is allocated once, when the instance is constructed.
+#[derive(Clone, Debug)]
+pub struct DynamicSMA<Sample, Divisor> {
+	samples: VecDeque<Sample>,
+	sum: Sample,
+	window_size: usize,
+	_marker: marker::PhantomData<Divisor>,
+}
+
+impl<Sample, Divisor> DynamicSMA<Sample, Divisor>
+where
+	Sample: Copy + AddAssign + SubAssign + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	/// Adds a sample to the series of samples. If the sample window is full, this will cause the
+	/// oldest sample to be dropped, i.e. no longer contribute to the average.
+	pub fn add_sample(&mut self, new_sample: Sample) {
+		if self.window_size == 0 {
+			return;
+		}
+
+		self.sum += new_sample;
+
+		if self.samples.len() == self.window_size {
+			if let Some(shifted_sample) = self.samples.pop_front() {
+				self.sum -= shifted_sample;
+			}
+		}
+
+		self.samples.push_back(new_sample);
+	}
+
+	/// Returns the simple moving average value of all the samples in the sample window.
+	pub fn get_average(&self) -> Sample {
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return self.sum;
+		}
+
+		self.sum / cast_to_divisor_type(num_samples)
+	}
+
+	/// Changes the sample window size to `new_window_size`, keeping as many of the most recent
+	/// samples as fit in the resized sample window. Shrinking the sample window drops the oldest
+	/// samples, which no longer contribute to the average.
+	pub fn resize_window(&mut self, new_window_size: usize) {
+		while new_window_size < self.samples.len() {
+			if let Some(shifted_sample) = self.samples.pop_front() {
+				self.sum -= shifted_sample;
+			}
+		}
+
+		self.samples
+			.reserve(new_window_size.saturating_sub(self.samples.len()));
+		self.window_size = new_window_size;
+	}
+
+	/// Returns the most recently added sample, if any.
+	pub fn get_most_recent_sample(&self) -> Option<Sample> {
+		self.samples.back().cloned()
+	}
+
+	/// Returns the oldest sample in the sample window, i.e. the sample that will be dropped next,
+	/// if any.
+	pub fn get_oldest_sample(&self) -> Option<Sample> {
+		self.samples.front().cloned()
+	}
+
+	/// Returns the total number of samples currently in the in the sample window. This value never
+	/// exceeds the sample window size.
+	pub fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	/// Returns the maximum number of samples that fit in the sample window.
+	pub fn get_sample_window_size(&self) -> usize {
+		self.window_size
+	}
+
+	/// Returns an iterator over the samples currently in the sample window, oldest first.
+	pub fn get_sample_window_iter(&self) -> vec_deque::Iter<'_, Sample> {
+		self.samples.iter()
+	}
+
+	/// Calls `f` on each sample in the sample window, oldest first. Walks the two halves of the
+	/// underlying [VecDeque] directly, so unlike [VecDeque::make_contiguous], it never rotates the
+	/// storage.
+	pub fn for_each_sample<F: FnMut(&Sample)>(&self, f: F) {
+		let (front, back) = self.samples.as_slices();
+		front.iter().chain(back).for_each(f);
+	}
+}
+
+#[cfg(feature = "num-traits")]
+impl<Sample: Copy + Zero, Divisor> DynamicSMA<Sample, Divisor> {
+	/// Constructs a new [DynamicSMA] with window size `window_size`. This constructor is only
+	/// available for `Sample` types that implement [num_traits::Zero]. If the `Sample` type does
+	/// not, use the [from_zero](DynamicSMA::from_zero) constructor instead.
+	///
+	/// Note that the `Divisor` type usually cannot be derived by the compiler when using this
+	/// constructor and must be explicitly stated, even if it is the same as the `Sample` type.
+	pub fn new(window_size: usize) -> Self {
+		Self::from_zero(Sample::zero(), window_size)
+	}
+}
+
+impl<Sample: Copy, Divisor> DynamicSMA<Sample, Divisor> {
+	/// Constructs a new [DynamicSMA] with window size `window_size` from the given `zero` sample.
+	/// If the `Sample` type implements [num_traits::Zero], the [new](DynamicSMA::new) constructor
+	/// might be preferable to this.
+	pub fn from_zero(zero: Sample, window_size: usize) -> Self {
+		Self {
+			samples: VecDeque::with_capacity(window_size),
+			sum: zero,
+			window_size,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<Sample: PartialEq, Divisor> PartialEq for DynamicSMA<Sample, Divisor> {
+	/// Two instances are equal if they have the same sample window size and their sample windows
+	/// contain the same samples, in the same order. The cached sums are not compared, as they only
+	/// depend on the samples, apart from rounding errors.
+	fn eq(&self, other: &Self) -> bool {
+		self.window_size == other.window_size && self.samples == other.samples
+	}
+}
+
+impl<Sample: Eq, Divisor> Eq for DynamicSMA<Sample, Divisor> {}
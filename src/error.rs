@@ -0,0 +1,111 @@
+use std::{error::Error, fmt};
+
+/// Error returned when the number of samples in a sample window can't be represented by the
+/// `Divisor` type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DivisorError {
+	count: usize,
+	divisor_type_name: &'static str,
+}
+
+impl DivisorError {
+	#[cfg_attr(not(feature = "num-traits"), allow(dead_code))]
+	pub(crate) fn new(count: usize, divisor_type_name: &'static str) -> Self {
+		Self {
+			count,
+			divisor_type_name,
+		}
+	}
+
+	/// Returns the sample count that failed to be converted.
+	pub fn count(&self) -> usize {
+		self.count
+	}
+
+	/// Returns the name of the `Divisor` type that the sample count failed to be converted to.
+	pub fn divisor_type_name(&self) -> &'static str {
+		self.divisor_type_name
+	}
+}
+
+impl fmt::Display for DivisorError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"Failed to create a divisor of type {} from {}",
+			self.divisor_type_name, self.count
+		)
+	}
+}
+
+impl Error for DivisorError {}
+
+/// Error returned when trying to construct an SMA from more samples than fit in its sample window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WindowSizeError {
+	num_samples: usize,
+	window_size: usize,
+}
+
+impl WindowSizeError {
+	#[cfg_attr(not(feature = "num-traits"), allow(dead_code))]
+	pub(crate) fn new(num_samples: usize, window_size: usize) -> Self {
+		Self {
+			num_samples,
+			window_size,
+		}
+	}
+
+	/// Returns the number of samples that failed to fit in the sample window.
+	pub fn num_samples(&self) -> usize {
+		self.num_samples
+	}
+
+	/// Returns the size of the sample window that the samples failed to fit in.
+	pub fn window_size(&self) -> usize {
+		self.window_size
+	}
+}
+
+impl fmt::Display for WindowSizeError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"Failed to fit {} samples in a sample window of size {}",
+			self.num_samples, self.window_size
+		)
+	}
+}
+
+impl Error for WindowSizeError {}
+
+/// Error returned when adding a sample would overflow the cached sum of the samples in a sample
+/// window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OverflowError {
+	sample_type_name: &'static str,
+}
+
+impl OverflowError {
+	#[cfg_attr(not(feature = "num-traits"), allow(dead_code))]
+	pub(crate) fn new(sample_type_name: &'static str) -> Self {
+		Self { sample_type_name }
+	}
+
+	/// Returns the name of the `Sample` type whose range the sum would have exceeded.
+	pub fn sample_type_name(&self) -> &'static str {
+		self.sample_type_name
+	}
+}
+
+impl fmt::Display for OverflowError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"Adding the sample would overflow the sum of type {}",
+			self.sample_type_name
+		)
+	}
+}
+
+impl Error for OverflowError {}
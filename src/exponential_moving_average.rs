@@ -0,0 +1,191 @@
+use num_traits::{Float, Zero};
+use std::ops::{Add, Mul, Sub};
+
+/// Calculates the [exponential moving average](https://en.wikipedia.org/wiki/Moving_average#Exponential_moving_average)
+/// (EMA) of a series of samples, i.e. a weighted average of all samples added so far, whose
+/// weights decrease exponentially with the age of the samples.
+///
+/// Every time a sample is added, the average is updated as
+/// `average + (new_sample - average) * alpha`, where the smoothing factor `alpha` lies in the
+/// interval `(0, 1]`. A larger `alpha` makes the average follow the samples more closely. The
+/// average is seeded with the first sample added. Only the current average is stored, so adding a
+/// sample and reading the average are both `O(1)` operations. Since there is no sample window,
+/// this type does not implement the [SMA](crate::SMA) trait.
+///
+/// Alternatively, the average can be seeded with zero and bias corrected, see
+/// [with_bias_correction](ExponentialMovingAverage::with_bias_correction).
+#[derive(Clone, Copy, Debug)]
+pub struct ExponentialMovingAverage<Sample, Divisor> {
+	average: Sample,
+	alpha: Divisor,
+	num_samples: usize,
+	is_bias_corrected: bool,
+	// (1 - alpha)^num_samples, the total weight of the zero seed
+	seed_weight: Divisor,
+}
+
+impl<Sample, Divisor> ExponentialMovingAverage<Sample, Divisor>
+where
+	Sample: Copy + Add<Output = Sample> + Sub<Output = Sample> + Mul<Divisor, Output = Sample>,
+	Divisor: Float,
+{
+	/// Adds a sample to the series of samples.
+	pub fn add_sample(&mut self, new_sample: Sample) {
+		self.average = if self.num_samples == 0 && !self.is_bias_corrected {
+			new_sample
+		} else {
+			self.average + (new_sample - self.average) * self.alpha
+		};
+		self.num_samples = self.num_samples.saturating_add(1);
+		self.seed_weight = self.seed_weight * (Divisor::one() - self.alpha);
+	}
+
+	/// Returns the exponential moving average of all samples added so far. If bias correction is
+	/// enabled, this is the zero seeded average divided by `1 - (1 - alpha)^t`, `t` being the number
+	/// of samples added so far.
+	pub fn get_average(&self) -> Sample {
+		if !self.is_bias_corrected || self.num_samples == 0 {
+			return self.average;
+		}
+
+		self.average * (Divisor::one() / (Divisor::one() - self.seed_weight))
+	}
+
+	/// Returns the total number of samples added so far.
+	pub fn get_num_samples(&self) -> usize {
+		self.num_samples
+	}
+
+	/// Returns the smoothing factor.
+	pub fn get_alpha(&self) -> Divisor {
+		self.alpha
+	}
+
+	/// Returns `true` if bias correction is enabled.
+	pub fn is_bias_corrected(&self) -> bool {
+		self.is_bias_corrected
+	}
+}
+
+impl<Sample: Copy + Zero, Divisor: Float> ExponentialMovingAverage<Sample, Divisor> {
+	/// Constructs a new [ExponentialMovingAverage] with smoothing factor `alpha`. This constructor is
+	/// only available for `Sample` types that implement [num_traits::Zero]. If the `Sample` type
+	/// does not, use the [from_zero](ExponentialMovingAverage::from_zero) constructor instead.
+	///
+	/// # Panics
+	///
+	/// Panics if `alpha` does not lie in the interval `(0, 1]`.
+	pub fn new(alpha: Divisor) -> Self {
+		Self::from_zero(Sample::zero(), alpha)
+	}
+}
+
+impl<Sample: Copy, Divisor: Float> ExponentialMovingAverage<Sample, Divisor> {
+	/// Constructs a new [ExponentialMovingAverage] with smoothing factor `alpha` from the given
+	/// `zero` sample. If the `Sample` type implements [num_traits::Zero], the
+	/// [new](ExponentialMovingAverage::new) constructor might be preferable to this.
+	///
+	/// # Panics
+	///
+	/// Panics if `alpha` does not lie in the interval `(0, 1]`.
+	pub fn from_zero(zero: Sample, alpha: Divisor) -> Self {
+		assert!(
+			Divisor::zero() < alpha && alpha <= Divisor::one(),
+			"The smoothing factor must lie in the interval (0, 1]"
+		);
+		Self {
+			average: zero,
+			alpha,
+			num_samples: 0,
+			is_bias_corrected: false,
+			seed_weight: Divisor::one(),
+		}
+	}
+
+	/// Enables bias correction, as used by e.g. the
+	/// [Adam](https://en.wikipedia.org/wiki/Stochastic_gradient_descent#Adam) optimizer. Instead of
+	/// being seeded with the first sample, the average is then seeded with zero and the
+	/// [get_average](ExponentialMovingAverage::get_average) result is divided by the total weight
+	/// of the samples added so far, which cancels out the bias towards zero of the early averages.
+	///
+	/// # Panics
+	///
+	/// Panics if any samples have already been added.
+	pub fn with_bias_correction(mut self) -> Self {
+		assert_eq!(
+			self.num_samples, 0,
+			"Bias correction must be enabled before adding samples"
+		);
+		self.is_bias_corrected = true;
+		self
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn basics() {
+		let mut ema = ExponentialMovingAverage::<f64, f64>::new(0.5);
+		assert_eq!(ema.get_average(), 0.0);
+		assert_eq!(ema.get_alpha(), 0.5);
+
+		ema.add_sample(4.0);
+		assert_eq!(ema.get_average(), 4.0);
+
+		ema.add_sample(8.0);
+		assert_eq!(ema.get_average(), 6.0);
+
+		ema.add_sample(2.0);
+		assert_eq!(ema.get_average(), 4.0);
+		assert_eq!(ema.get_num_samples(), 3);
+	}
+
+	#[test]
+	fn alpha_one() {
+		let mut ema = ExponentialMovingAverage::<f32, f32>::new(1.0);
+		for sample in [3.0, -1.0, 7.5] {
+			ema.add_sample(sample);
+			assert_eq!(ema.get_average(), sample);
+		}
+	}
+
+	#[test]
+	fn bias_correction() {
+		let samples = [10.0, 12.0, 11.0, 13.0];
+
+		let mut ema = ExponentialMovingAverage::<f64, f64>::new(0.1).with_bias_correction();
+		let mut zero_seeded_average = 0.0;
+		assert!(ema.is_bias_corrected());
+		assert_eq!(ema.get_average(), 0.0);
+
+		for (idx, sample) in samples.iter().enumerate() {
+			ema.add_sample(*sample);
+			zero_seeded_average += (sample - zero_seeded_average) * 0.1;
+
+			let simple_average = samples[..=idx].iter().sum::<f64>() / (idx + 1) as f64;
+			assert!((ema.get_average() - simple_average).abs() < 0.2);
+			assert!((zero_seeded_average - simple_average).abs() > 5.0);
+		}
+
+		ema.add_sample(5.0);
+		let expected =
+			(zero_seeded_average + (5.0 - zero_seeded_average) * 0.1) / (1.0 - 0.9f64.powi(5));
+		assert!((ema.get_average() - expected).abs() < 1e-12);
+	}
+
+	#[test]
+	#[should_panic]
+	fn bias_correction_after_samples() {
+		let mut ema = ExponentialMovingAverage::<f64, f64>::new(0.1);
+		ema.add_sample(1.0);
+		ema.with_bias_correction();
+	}
+
+	#[test]
+	#[should_panic]
+	fn invalid_alpha() {
+		ExponentialMovingAverage::<f32, f32>::new(0.0);
+	}
+}
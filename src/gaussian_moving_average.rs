@@ -0,0 +1,176 @@
+use super::SMA;
+use crate::{common::cast_to_divisor_type, ring_buffer::RingBuffer, DivisorFromCount, Iter};
+use num_traits::{Float, Zero};
+use std::ops::{Add, Div, Mul};
+
+/// Calculates a Gaussian-weighted moving average of the samples in the sample window, where the
+/// weight of each position in the sample window follows a Gaussian curve with standard deviation
+/// `sigma`, centered on the middle of the sample window.
+///
+/// The weights are calculated and normalized once, when the instance is constructed, and stored in
+/// an array along with the samples. Adding a sample is an `O(1)` operation and reading the average
+/// is an `O(N)` operation. Until the sample window is full, the samples are aligned with the end
+/// of the sample window, i.e. the most recent sample always has the weight of the last position,
+/// and the average is normalized by the sum of the weights of the occupied positions.
+#[derive(Clone, Copy, Debug)]
+pub struct GaussianMovingAverage<Sample, Divisor, const WINDOW_SIZE: usize> {
+	samples: RingBuffer<Sample, WINDOW_SIZE>,
+	weights: [Divisor; WINDOW_SIZE],
+	zero: Sample,
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> SMA<Sample, Divisor, WINDOW_SIZE>
+	for GaussianMovingAverage<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample:
+		Copy + Add<Output = Sample> + Mul<Divisor, Output = Sample> + Div<Divisor, Output = Sample>,
+	Divisor: Float,
+{
+	fn add_sample(&mut self, new_sample: Sample) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+		self.samples.push_front(new_sample);
+	}
+
+	fn get_average(&self) -> Sample {
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return self.zero;
+		}
+
+		let weights = &self.weights[WINDOW_SIZE - num_samples..];
+		let weighted_sum = self
+			.samples
+			.iter()
+			.zip(weights)
+			.fold(self.zero, |sum, (sample, weight)| sum + *sample * *weight);
+
+		if num_samples == WINDOW_SIZE {
+			return weighted_sum;
+		}
+
+		let weight_sum = weights
+			.iter()
+			.fold(Divisor::zero(), |sum, weight| sum + *weight);
+		weighted_sum / weight_sum
+	}
+
+	fn get_most_recent_sample(&self) -> Option<Sample> {
+		self.samples.front().cloned()
+	}
+
+	fn get_oldest_sample(&self) -> Option<Sample> {
+		self.samples.back().cloned()
+	}
+
+	fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
+		self.samples.iter()
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> GaussianMovingAverage<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy,
+	Divisor: Float + DivisorFromCount,
+{
+	/// Constructs a new [GaussianMovingAverage] with window size `WINDOW_SIZE`, whose weights
+	/// follow a Gaussian curve with standard deviation `sigma`, measured in sample window
+	/// positions. This constructor is only available for `Sample` types that implement
+	/// [num_traits::Zero]. If the `Sample` type does not, use the
+	/// [with_sigma_from_zero](GaussianMovingAverage::with_sigma_from_zero) constructor instead.
+	pub fn with_sigma(sigma: Divisor) -> Self
+	where
+		Sample: Zero,
+	{
+		Self::with_sigma_from_zero(Sample::zero(), sigma)
+	}
+
+	/// Constructs a new [GaussianMovingAverage] with window size `WINDOW_SIZE` from the given
+	/// `zero` sample, whose weights follow a Gaussian curve with standard deviation `sigma`.
+	pub fn with_sigma_from_zero(zero: Sample, sigma: Divisor) -> Self {
+		let mut weights = [Divisor::zero(); WINDOW_SIZE];
+
+		let two = Divisor::one() + Divisor::one();
+		let center = cast_to_divisor_type::<Divisor>(WINDOW_SIZE.saturating_sub(1)) / two;
+		for (idx, weight) in weights.iter_mut().enumerate() {
+			let distance = cast_to_divisor_type::<Divisor>(idx) - center;
+			*weight = (-(distance * distance) / (two * sigma * sigma)).exp();
+		}
+
+		let weight_sum = weights
+			.iter()
+			.fold(Divisor::zero(), |sum, weight| sum + *weight);
+		for weight in weights.iter_mut() {
+			*weight = *weight / weight_sum;
+		}
+
+		Self {
+			samples: RingBuffer::new(zero),
+			weights,
+			zero,
+		}
+	}
+
+	/// Returns the normalized weights of the sample window positions, oldest position first.
+	pub fn get_weights(&self) -> &[Divisor; WINDOW_SIZE] {
+		&self.weights
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn weights() {
+		let gma = GaussianMovingAverage::<f64, f64, 6>::with_sigma(1.5);
+		let weights = gma.get_weights();
+
+		assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+		for idx in 0..3 {
+			assert!((weights[idx] - weights[5 - idx]).abs() < 1e-12);
+		}
+		assert!(weights[0] < weights[1] && weights[1] < weights[2]);
+	}
+
+	#[test]
+	fn symmetric_samples() {
+		let mut gma = GaussianMovingAverage::<f64, f64, 5>::with_sigma(1.0);
+		for sample in [1.0, 2.0, 3.0, 2.0, 1.0] {
+			gma.add_sample(sample);
+		}
+
+		let (w1, w2) = ((-0.5f64).exp(), (-2.0f64).exp());
+		let expected = (3.0 + 2.0 * 2.0 * w1 + 2.0 * 1.0 * w2) / (1.0 + 2.0 * w1 + 2.0 * w2);
+		assert!((gma.get_average() - expected).abs() < 1e-12);
+	}
+
+	#[test]
+	fn constant_samples() {
+		let mut gma = GaussianMovingAverage::<f32, f32, 4>::with_sigma(0.8);
+		assert_eq!(gma.get_average(), 0.0);
+
+		for _ in 0..6 {
+			gma.add_sample(5.0);
+			assert!((gma.get_average() - 5.0).abs() < 1e-6);
+		}
+	}
+
+	#[test]
+	fn edge_case_zero_sized() {
+		let mut gma = GaussianMovingAverage::<f32, f32, 0>::with_sigma(1.0);
+		gma.add_sample(1.0);
+		assert_eq!(gma.get_average(), 0.0);
+		assert_eq!(gma.get_num_samples(), 0);
+	}
+}
@@ -0,0 +1,199 @@
+use super::SMA;
+use crate::{common::cast_to_divisor_type, ring_buffer::RingBuffer, DivisorFromCount, Iter};
+use num_traits::Float;
+use std::{
+	marker::{self, PhantomData},
+	ops::Div,
+};
+
+/// Calculates the [geometric mean](https://en.wikipedia.org/wiki/Geometric_mean) of the samples in
+/// the sample window, i.e. the `N`th root of their product, which is the appropriate average of
+/// e.g. ratios and growth rates. Only available for floating point `Sample` types.
+///
+/// The sum of the natural logarithms of the samples is cached, like the sum of the samples is in
+/// [SingleSumSMA](crate::SingleSumSMA), and the average is calculated as `exp(sum / N)`. This makes
+/// adding a sample and reading the average `O(1)` operations, at the cost of accumulating floating
+/// point rounding errors in the cached sum.
+///
+/// The logarithm is only defined for positive samples. Zero and negative samples are therefore
+/// kept out of the cached sum and counted separately instead. While there is a negative sample in
+/// the sample window, the average is `NaN`. Otherwise, while there is a zero sample in the sample
+/// window, the average is zero.
+#[derive(Clone, Copy, Debug)]
+pub struct GeometricMovingAverage<Sample, Divisor, const WINDOW_SIZE: usize> {
+	samples: RingBuffer<Sample, WINDOW_SIZE>,
+	ln_sum: Sample,
+	num_zero_samples: usize,
+	num_negative_samples: usize,
+	_marker: marker::PhantomData<Divisor>,
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> SMA<Sample, Divisor, WINDOW_SIZE>
+	for GeometricMovingAverage<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Float + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	fn add_sample(&mut self, new_sample: Sample) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+
+		self.add_to_sum(new_sample);
+
+		if let Some(shifted_sample) = self.samples.shift(new_sample) {
+			self.remove_from_sum(shifted_sample);
+		}
+	}
+
+	fn get_average(&self) -> Sample {
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return Sample::zero();
+		}
+
+		if 0 < self.num_negative_samples {
+			return Sample::nan();
+		}
+
+		if 0 < self.num_zero_samples {
+			return Sample::zero();
+		}
+
+		(self.ln_sum / cast_to_divisor_type::<Divisor>(num_samples)).exp()
+	}
+
+	fn get_most_recent_sample(&self) -> Option<Sample> {
+		self.samples.front().cloned()
+	}
+
+	fn get_oldest_sample(&self) -> Option<Sample> {
+		self.samples.back().cloned()
+	}
+
+	fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
+		self.samples.iter()
+	}
+}
+
+impl<Sample: Float, Divisor, const WINDOW_SIZE: usize>
+	GeometricMovingAverage<Sample, Divisor, WINDOW_SIZE>
+{
+	/// Constructs a new [GeometricMovingAverage] with window size `WINDOW_SIZE`.
+	///
+	/// Note that the `Divisor` type usually cannot be derived by the compiler when using this
+	/// constructor and must be explicitly stated, even if it is the same as the `Sample` type.
+	pub fn new() -> Self {
+		Self {
+			samples: RingBuffer::new(Sample::zero()),
+			ln_sum: Sample::zero(),
+			num_zero_samples: 0,
+			num_negative_samples: 0,
+			_marker: PhantomData,
+		}
+	}
+
+	fn add_to_sum(&mut self, sample: Sample) {
+		if sample > Sample::zero() {
+			self.ln_sum = self.ln_sum + sample.ln();
+		} else if sample == Sample::zero() {
+			self.num_zero_samples += 1;
+		} else {
+			self.num_negative_samples += 1;
+		}
+	}
+
+	fn remove_from_sum(&mut self, sample: Sample) {
+		if sample > Sample::zero() {
+			self.ln_sum = self.ln_sum - sample.ln();
+		} else if sample == Sample::zero() {
+			self.num_zero_samples -= 1;
+		} else {
+			self.num_negative_samples -= 1;
+		}
+	}
+}
+
+impl<Sample: Float, Divisor, const WINDOW_SIZE: usize> Default
+	for GeometricMovingAverage<Sample, Divisor, WINDOW_SIZE>
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn product_and_root(window: &[f64]) -> f64 {
+		window
+			.iter()
+			.product::<f64>()
+			.powf(1.0 / window.len() as f64)
+	}
+
+	#[test]
+	fn matches_product_and_root() {
+		let samples = [1.05, 0.98, 1.10, 1.02, 0.95, 1.20, 1.01];
+
+		let mut gma = GeometricMovingAverage::<f64, f64, 4>::new();
+		assert_eq!(gma.get_average(), 0.0);
+
+		for idx in 0..samples.len() {
+			gma.add_sample(samples[idx]);
+			let window = &samples[(idx + 1).saturating_sub(4)..=idx];
+			assert!((gma.get_average() - product_and_root(window)).abs() < 1e-12);
+		}
+	}
+
+	#[test]
+	fn basics() {
+		let mut gma = GeometricMovingAverage::<f64, f64, 3>::new();
+		gma.add_sample(2.0);
+		gma.add_sample(8.0);
+		assert!((gma.get_average() - 4.0).abs() < 1e-12);
+
+		gma.add_sample(4.0);
+		assert!((gma.get_average() - 4.0).abs() < 1e-12);
+
+		gma.add_sample(0.25);
+		assert!((gma.get_average() - 2.0).abs() < 1e-12);
+	}
+
+	#[test]
+	fn non_positive_samples() {
+		let mut gma = GeometricMovingAverage::<f32, f32, 2>::new();
+
+		gma.add_sample(3.0);
+		gma.add_sample(0.0);
+		assert_eq!(gma.get_average(), 0.0);
+
+		gma.add_sample(-1.0);
+		assert!(gma.get_average().is_nan());
+
+		gma.add_sample(2.0);
+		assert!(gma.get_average().is_nan());
+
+		// Once the non-positive samples have left the sample window, the average recovers
+		gma.add_sample(8.0);
+		assert!((gma.get_average() - 4.0).abs() < 1e-5);
+	}
+
+	#[test]
+	fn edge_case_zero_sized() {
+		let mut gma = GeometricMovingAverage::<f32, f32, 0>::new();
+		gma.add_sample(1.0);
+		assert_eq!(gma.get_average(), 0.0);
+		assert_eq!(gma.get_num_samples(), 0);
+	}
+}
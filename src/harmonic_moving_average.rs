@@ -0,0 +1,176 @@
+use super::SMA;
+use crate::{common::cast_to_divisor_type, ring_buffer::RingBuffer, DivisorFromCount, Iter};
+use num_traits::Float;
+use std::{
+	marker::{self, PhantomData},
+	ops::Mul,
+};
+
+/// Calculates the [harmonic mean](https://en.wikipedia.org/wiki/Harmonic_mean) of the samples in
+/// the sample window, i.e. `N / sum(1 / sample)`, which is the appropriate average of e.g. speeds
+/// over equal distances. Only available for floating point `Sample` types.
+///
+/// The sum of the reciprocals of the samples is cached, like the sum of the samples is in
+/// [SingleSumSMA](crate::SingleSumSMA). This makes adding a sample and reading the average `O(1)`
+/// operations, at the cost of accumulating floating point rounding errors in the cached sum.
+///
+/// The reciprocal of a zero sample is infinite, which would poison the cached sum. Zero samples
+/// are therefore kept out of the cached sum and counted separately instead. While there is a zero
+/// sample in the sample window, the average is zero, which is the limit of the harmonic mean as
+/// any one sample approaches zero.
+#[derive(Clone, Copy, Debug)]
+pub struct HarmonicMovingAverage<Sample, Divisor, const WINDOW_SIZE: usize> {
+	samples: RingBuffer<Sample, WINDOW_SIZE>,
+	reciprocal_sum: Sample,
+	num_zero_samples: usize,
+	_marker: marker::PhantomData<Divisor>,
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> SMA<Sample, Divisor, WINDOW_SIZE>
+	for HarmonicMovingAverage<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Float + Mul<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	fn add_sample(&mut self, new_sample: Sample) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+
+		self.add_to_sum(new_sample);
+
+		if let Some(shifted_sample) = self.samples.shift(new_sample) {
+			self.remove_from_sum(shifted_sample);
+		}
+	}
+
+	fn get_average(&self) -> Sample {
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 || 0 < self.num_zero_samples {
+			return Sample::zero();
+		}
+
+		self.reciprocal_sum.recip() * cast_to_divisor_type::<Divisor>(num_samples)
+	}
+
+	fn get_most_recent_sample(&self) -> Option<Sample> {
+		self.samples.front().cloned()
+	}
+
+	fn get_oldest_sample(&self) -> Option<Sample> {
+		self.samples.back().cloned()
+	}
+
+	fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
+		self.samples.iter()
+	}
+}
+
+impl<Sample: Float, Divisor, const WINDOW_SIZE: usize>
+	HarmonicMovingAverage<Sample, Divisor, WINDOW_SIZE>
+{
+	/// Constructs a new [HarmonicMovingAverage] with window size `WINDOW_SIZE`.
+	///
+	/// Note that the `Divisor` type usually cannot be derived by the compiler when using this
+	/// constructor and must be explicitly stated, even if it is the same as the `Sample` type.
+	pub fn new() -> Self {
+		Self {
+			samples: RingBuffer::new(Sample::zero()),
+			reciprocal_sum: Sample::zero(),
+			num_zero_samples: 0,
+			_marker: PhantomData,
+		}
+	}
+
+	fn add_to_sum(&mut self, sample: Sample) {
+		if sample == Sample::zero() {
+			self.num_zero_samples += 1;
+		} else {
+			self.reciprocal_sum = self.reciprocal_sum + sample.recip();
+		}
+	}
+
+	fn remove_from_sum(&mut self, sample: Sample) {
+		if sample == Sample::zero() {
+			self.num_zero_samples -= 1;
+		} else {
+			self.reciprocal_sum = self.reciprocal_sum - sample.recip();
+		}
+	}
+}
+
+impl<Sample: Float, Divisor, const WINDOW_SIZE: usize> Default
+	for HarmonicMovingAverage<Sample, Divisor, WINDOW_SIZE>
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::NoSumSMA;
+
+	fn harmonic_mean(window: &[f64]) -> f64 {
+		window.len() as f64 / window.iter().map(|sample| sample.recip()).sum::<f64>()
+	}
+
+	#[test]
+	fn matches_direct_calculation() {
+		let samples = [60.0, 40.0, 30.0, 120.0, 45.0, 90.0, 20.0];
+
+		let mut hma = HarmonicMovingAverage::<f64, f64, 3>::new();
+		assert_eq!(hma.get_average(), 0.0);
+
+		for (idx, sample) in samples.iter().enumerate() {
+			hma.add_sample(*sample);
+			let window = &samples[(idx + 1).saturating_sub(3)..=idx];
+			assert!((hma.get_average() - harmonic_mean(window)).abs() < 1e-9);
+		}
+	}
+
+	#[test]
+	fn round_trip_speeds() {
+		// Driving equal distances at 60 and 40 km/h averages 48 km/h, not 50 km/h
+		let mut hma = HarmonicMovingAverage::<f64, f64, 2>::new();
+		let mut sma = NoSumSMA::<f64, f64, 2>::new();
+		for speed in [60.0, 40.0] {
+			hma.add_sample(speed);
+			sma.add_sample(speed);
+		}
+
+		assert!((hma.get_average() - 48.0).abs() < 1e-12);
+		assert_eq!(sma.get_average(), 50.0);
+	}
+
+	#[test]
+	fn zero_samples() {
+		let mut hma = HarmonicMovingAverage::<f32, f32, 2>::new();
+
+		hma.add_sample(0.0);
+		hma.add_sample(4.0);
+		assert_eq!(hma.get_average(), 0.0);
+
+		hma.add_sample(12.0);
+		assert!((hma.get_average() - 6.0).abs() < 1e-5);
+		assert!(hma.get_average().is_finite());
+	}
+
+	#[test]
+	fn edge_case_zero_sized() {
+		let mut hma = HarmonicMovingAverage::<f32, f32, 0>::new();
+		hma.add_sample(1.0);
+		assert_eq!(hma.get_average(), 0.0);
+		assert_eq!(hma.get_num_samples(), 0);
+	}
+}
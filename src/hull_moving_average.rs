@@ -0,0 +1,207 @@
+use super::SMA;
+use crate::{DivisorFromCount, Iter, WeightedMovingAverage};
+use num_traits::Zero;
+use std::ops::{Add, Div, Mul, Sub};
+
+/// Calculates the [Hull moving average](https://alanhull.com/hull-moving-average) (HMA) of the
+/// samples in the sample window, defined as
+/// `WMA(2 * WMA(samples, N / 2) - WMA(samples, N), round(sqrt(N)))`, where `WMA` is a
+/// [WeightedMovingAverage] and `N` the sample window size. This combines low lag with a smooth
+/// average.
+///
+/// Since the sub-window sizes can't be derived from `WINDOW_SIZE` at compile time on stable Rust,
+/// they must be given as the `HALF_WINDOW_SIZE` and `SQRT_WINDOW_SIZE` parameters, which are
+/// checked at compile time to equal `WINDOW_SIZE / 2` and `sqrt(WINDOW_SIZE)` rounded to the
+/// nearest integer. `WINDOW_SIZE` must be at least 2.
+///
+/// ```
+/// # use simple_moving_average::{HullMovingAverage, SMA};
+/// let mut hma = HullMovingAverage::<f32, f32, 16, 8, 4>::new();
+/// hma.add_sample(1.0);
+/// assert_eq!(hma.get_average(), 1.0);
+/// ```
+///
+/// Mismatching sub-window sizes fail to compile:
+///
+/// ```compile_fail
+/// # use simple_moving_average::HullMovingAverage;
+/// let hma = HullMovingAverage::<f32, f32, 16, 8, 3>::new();
+/// ```
+///
+/// Note that `2 * WMA(samples, N / 2) - WMA(samples, N)` may underflow for unsigned `Sample` types.
+#[derive(Clone, Copy, Debug)]
+pub struct HullMovingAverage<
+	Sample,
+	Divisor,
+	const WINDOW_SIZE: usize,
+	const HALF_WINDOW_SIZE: usize,
+	const SQRT_WINDOW_SIZE: usize,
+> {
+	wma: WeightedMovingAverage<Sample, Divisor, WINDOW_SIZE>,
+	half_wma: WeightedMovingAverage<Sample, Divisor, HALF_WINDOW_SIZE>,
+	hull_wma: WeightedMovingAverage<Sample, Divisor, SQRT_WINDOW_SIZE>,
+}
+
+impl<
+		Sample,
+		Divisor,
+		const WINDOW_SIZE: usize,
+		const HALF_WINDOW_SIZE: usize,
+		const SQRT_WINDOW_SIZE: usize,
+	> SMA<Sample, Divisor, WINDOW_SIZE>
+	for HullMovingAverage<Sample, Divisor, WINDOW_SIZE, HALF_WINDOW_SIZE, SQRT_WINDOW_SIZE>
+where
+	Sample: Copy
+		+ Add<Output = Sample>
+		+ Sub<Output = Sample>
+		+ Mul<Divisor, Output = Sample>
+		+ Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	fn add_sample(&mut self, new_sample: Sample) {
+		self.wma.add_sample(new_sample);
+		self.half_wma.add_sample(new_sample);
+
+		let half_average = self.half_wma.get_average();
+		self.hull_wma
+			.add_sample(half_average + half_average - self.wma.get_average());
+	}
+
+	fn get_average(&self) -> Sample {
+		self.hull_wma.get_average()
+	}
+
+	fn get_most_recent_sample(&self) -> Option<Sample> {
+		self.wma.get_most_recent_sample()
+	}
+
+	fn get_oldest_sample(&self) -> Option<Sample> {
+		self.wma.get_oldest_sample()
+	}
+
+	fn get_num_samples(&self) -> usize {
+		self.wma.get_num_samples()
+	}
+
+	fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
+		self.wma.get_sample_window_iter()
+	}
+}
+
+impl<
+		Sample: Copy + Zero,
+		Divisor,
+		const WINDOW_SIZE: usize,
+		const HALF_WINDOW_SIZE: usize,
+		const SQRT_WINDOW_SIZE: usize,
+	> HullMovingAverage<Sample, Divisor, WINDOW_SIZE, HALF_WINDOW_SIZE, SQRT_WINDOW_SIZE>
+{
+	/// Constructs a new [HullMovingAverage] with window size `WINDOW_SIZE`. This constructor is
+	/// only available for `Sample` types that implement [num_traits::Zero]. If the `Sample` type
+	/// does not, use the [from_zero](HullMovingAverage::from_zero) constructor instead.
+	///
+	/// Note that the `Divisor` type usually cannot be derived by the compiler when using this
+	/// constructor and must be explicitly stated, even if it is the same as the `Sample` type.
+	pub fn new() -> Self {
+		Self::from_zero(Sample::zero())
+	}
+}
+
+impl<
+		Sample: Copy + Zero,
+		Divisor,
+		const WINDOW_SIZE: usize,
+		const HALF_WINDOW_SIZE: usize,
+		const SQRT_WINDOW_SIZE: usize,
+	> Default for HullMovingAverage<Sample, Divisor, WINDOW_SIZE, HALF_WINDOW_SIZE, SQRT_WINDOW_SIZE>
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<
+		Sample: Copy,
+		Divisor,
+		const WINDOW_SIZE: usize,
+		const HALF_WINDOW_SIZE: usize,
+		const SQRT_WINDOW_SIZE: usize,
+	> HullMovingAverage<Sample, Divisor, WINDOW_SIZE, HALF_WINDOW_SIZE, SQRT_WINDOW_SIZE>
+{
+	const WINDOW_SIZES_CHECK: () = assert!(
+		2 <= WINDOW_SIZE
+			&& HALF_WINDOW_SIZE == WINDOW_SIZE / 2
+			&& SQRT_WINDOW_SIZE == rounded_sqrt(WINDOW_SIZE),
+		"HALF_WINDOW_SIZE and SQRT_WINDOW_SIZE must equal WINDOW_SIZE / 2 and round(sqrt(WINDOW_SIZE))"
+	);
+
+	/// Constructs a new [HullMovingAverage] with window size `WINDOW_SIZE` from the given `zero`
+	/// sample. If the `Sample` type implements [num_traits::Zero], the
+	/// [new](HullMovingAverage::new) constructor might be preferable to this.
+	pub const fn from_zero(zero: Sample) -> Self {
+		let () = Self::WINDOW_SIZES_CHECK;
+
+		Self {
+			wma: WeightedMovingAverage::from_zero(zero),
+			half_wma: WeightedMovingAverage::from_zero(zero),
+			hull_wma: WeightedMovingAverage::from_zero(zero),
+		}
+	}
+}
+
+const fn rounded_sqrt(value: usize) -> usize {
+	let mut sqrt = 0;
+	while (sqrt + 1) * (sqrt + 1) <= value {
+		sqrt += 1;
+	}
+	// sqrt + 0.5 squared is sqrt^2 + sqrt + 0.25
+	if value - sqrt * sqrt > sqrt {
+		sqrt + 1
+	} else {
+		sqrt
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::weighted_moving_average::tests::wma_pass;
+
+	#[test]
+	fn rounded_sqrt_values() {
+		let sqrts: Vec<usize> = (0..=12).map(rounded_sqrt).collect();
+		assert_eq!(sqrts, vec![0, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3]);
+		assert_eq!(rounded_sqrt(16), 4);
+		assert_eq!(rounded_sqrt(20), 4);
+		assert_eq!(rounded_sqrt(21), 5);
+	}
+
+	#[test]
+	fn matches_reference() {
+		let samples = [
+			4.0, 8.0, 3.0, 7.0, 11.0, 0.0, -23.0, 5.5, 1.25, 9.0, 2.0, 6.0,
+		];
+
+		let wma = wma_pass(&samples, 9);
+		let half_wma = wma_pass(&samples, 4);
+		let diff: Vec<f64> = half_wma
+			.iter()
+			.zip(wma)
+			.map(|(half_wma, wma)| 2.0 * half_wma - wma)
+			.collect();
+		let expected = wma_pass(&diff, 3);
+
+		let mut hma = HullMovingAverage::<f64, f64, 9, 4, 3>::new();
+		for (sample, expected) in samples.iter().zip(expected) {
+			hma.add_sample(*sample);
+			assert!((hma.get_average() - expected).abs() < 1e-12);
+		}
+
+		assert_eq!(hma.get_num_samples(), 9);
+		assert_eq!(hma.get_most_recent_sample(), Some(6.0));
+	}
+}
@@ -32,4 +32,83 @@ impl<'a, Item, const CAPACITY: usize> Iterator for Iter<'a, Item, CAPACITY> {
 
 		Some(&self.items[cursor_idx])
 	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.num_items_left, Some(self.num_items_left))
+	}
+}
+
+impl<'a, Item, const CAPACITY: usize> DoubleEndedIterator for Iter<'a, Item, CAPACITY> {
+	fn next_back(&mut self) -> Option<Self::Item> {
+		if self.num_items_left == 0 {
+			return None;
+		}
+
+		self.num_items_left -= 1;
+
+		Some(&self.items[wrapping_add::<CAPACITY>(self.cursor_idx, self.num_items_left)])
+	}
+}
+
+impl<'a, Item, const CAPACITY: usize> ExactSizeIterator for Iter<'a, Item, CAPACITY> {
+	fn len(&self) -> usize {
+		self.num_items_left
+	}
+}
+
+/// An iterator that moves the samples out of a sample window, oldest sample first.
+#[derive(Clone, Debug)]
+pub struct IntoIter<Item, const CAPACITY: usize> {
+	items: [Item; CAPACITY],
+	cursor_idx: usize,
+	num_items_left: usize,
+}
+
+impl<Item, const CAPACITY: usize> IntoIter<Item, CAPACITY> {
+	pub(crate) fn new(items: [Item; CAPACITY], end_idx: usize, num_items: usize) -> Self {
+		Self {
+			items,
+			cursor_idx: wrapping_sub::<CAPACITY>(end_idx, num_items),
+			num_items_left: num_items,
+		}
+	}
+}
+
+impl<Item: Copy, const CAPACITY: usize> Iterator for IntoIter<Item, CAPACITY> {
+	type Item = Item;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.num_items_left == 0 {
+			return None;
+		}
+
+		self.num_items_left -= 1;
+
+		let cursor_idx = self.cursor_idx;
+		self.cursor_idx = wrapping_add::<CAPACITY>(self.cursor_idx, 1);
+
+		Some(self.items[cursor_idx])
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.num_items_left, Some(self.num_items_left))
+	}
+}
+
+impl<Item: Copy, const CAPACITY: usize> DoubleEndedIterator for IntoIter<Item, CAPACITY> {
+	fn next_back(&mut self) -> Option<Self::Item> {
+		if self.num_items_left == 0 {
+			return None;
+		}
+
+		self.num_items_left -= 1;
+
+		Some(self.items[wrapping_add::<CAPACITY>(self.cursor_idx, self.num_items_left)])
+	}
+}
+
+impl<Item: Copy, const CAPACITY: usize> ExactSizeIterator for IntoIter<Item, CAPACITY> {
+	fn len(&self) -> usize {
+		self.num_items_left
+	}
 }
@@ -0,0 +1,123 @@
+use super::SMA;
+use crate::{common::cast_to_divisor_type, ring_buffer::RingBuffer, DivisorFromCount, Iter};
+use num_traits::Float;
+use std::{
+	marker::{self, PhantomData},
+	ops::Div,
+};
+
+/// An SMA implementation that caches the sum of all samples currently in the sample window as a
+/// single value, along with a compensation term that keeps track of the rounding error of that sum,
+/// using [Kahan-Babuška-Neumaier](https://en.wikipedia.org/wiki/Kahan_summation_algorithm#Further_enhancements)
+/// summation. Only available for floating point `Sample` types.
+#[derive(Clone, Copy, Debug)]
+pub struct KahanSumSMA<Sample, Divisor, const WINDOW_SIZE: usize> {
+	samples: RingBuffer<Sample, WINDOW_SIZE>,
+	sum: Sample,
+	compensation: Sample,
+	_marker: marker::PhantomData<Divisor>,
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> SMA<Sample, Divisor, WINDOW_SIZE>
+	for KahanSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Float + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	fn add_sample(&mut self, new_sample: Sample) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+
+		self.add_to_sum(new_sample);
+
+		if let Some(shifted_sample) = self.samples.shift(new_sample) {
+			self.add_to_sum(-shifted_sample);
+		}
+	}
+
+	fn get_average(&self) -> Sample {
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return self.sum;
+		}
+
+		(self.sum + self.compensation) / cast_to_divisor_type::<Divisor>(num_samples)
+	}
+
+	fn get_most_recent_sample(&self) -> Option<Sample> {
+		self.samples.front().cloned()
+	}
+
+	fn get_oldest_sample(&self) -> Option<Sample> {
+		self.samples.back().cloned()
+	}
+
+	fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
+		self.samples.iter()
+	}
+}
+
+impl<Sample: Float, Divisor, const WINDOW_SIZE: usize> KahanSumSMA<Sample, Divisor, WINDOW_SIZE> {
+	/// Constructs a new [KahanSumSMA] with window size `WINDOW_SIZE`.
+	///
+	/// Note that the `Divisor` type usually cannot be derived by the compiler when using this
+	/// constructor and must be explicitly stated, even if it is the same as the `Sample` type.
+	pub fn new() -> Self {
+		Self {
+			samples: RingBuffer::new(Sample::zero()),
+			sum: Sample::zero(),
+			compensation: Sample::zero(),
+			_marker: PhantomData,
+		}
+	}
+
+	fn add_to_sum(&mut self, value: Sample) {
+		let sum = self.sum + value;
+
+		// The low-order bits of the smaller operand are the ones lost in the addition
+		self.compensation = self.compensation
+			+ if self.sum.abs() >= value.abs() {
+				(self.sum - sum) + value
+			} else {
+				(value - sum) + self.sum
+			};
+
+		self.sum = sum;
+	}
+}
+
+impl<Sample: Float, Divisor, const WINDOW_SIZE: usize> Default
+	for KahanSumSMA<Sample, Divisor, WINDOW_SIZE>
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> PartialEq
+	for KahanSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + PartialEq,
+{
+	/// Two instances are equal if their sample windows contain the same samples, in the same order.
+	/// Any cached sums are not compared, as they only depend on the samples, apart from rounding
+	/// errors.
+	fn eq(&self, other: &Self) -> bool {
+		self.samples.iter().eq(other.samples.iter())
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> Eq for KahanSumSMA<Sample, Divisor, WINDOW_SIZE> where
+	Sample: Copy + Eq
+{
+}
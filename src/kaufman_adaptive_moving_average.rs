@@ -0,0 +1,228 @@
+use super::SMA;
+use crate::{common::cast_to_divisor_type, ring_buffer::RingBuffer, DivisorFromCount, Iter};
+use num_traits::{Float, Signed, ToPrimitive};
+use std::ops::Mul;
+
+/// Calculates [Kaufman's adaptive moving average](https://en.wikipedia.org/wiki/Kaufman%27s_Adaptive_Moving_Average)
+/// (KAMA), which behaves like an exponential moving average whose smoothing factor adapts to how
+/// efficiently the samples move in one direction.
+///
+/// The efficiency ratio is the absolute change over the sample window divided by the sum of the
+/// absolute changes between consecutive samples in the sample window, i.e. `1` for a monotonic
+/// trend and close to `0` for noise. Every time a sample is added, the average is updated as
+/// `average + (new_sample - average) * sc`, where the smoothing constant
+/// `sc = (efficiency_ratio * (fast_alpha - slow_alpha) + slow_alpha)^2` lies between the squared
+/// smoothing factors of a fast and a slow EMA. The average is seeded with the first sample added.
+///
+/// The sum of absolute changes is cached, so adding a sample and reading the average are both
+/// `O(1)` operations. Like [SingleSumSMA](crate::SingleSumSMA), this means that floating point
+/// rounding errors accumulate in the cached sum.
+#[derive(Clone, Copy, Debug)]
+pub struct KaufmanAdaptiveMovingAverage<Sample, Divisor, const WINDOW_SIZE: usize> {
+	samples: RingBuffer<Sample, WINDOW_SIZE>,
+	// Sum of the absolute changes between consecutive samples in the sample window
+	volatility: Sample,
+	average: Sample,
+	fast_alpha: Divisor,
+	slow_alpha: Divisor,
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> SMA<Sample, Divisor, WINDOW_SIZE>
+	for KaufmanAdaptiveMovingAverage<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Signed + ToPrimitive + Mul<Divisor, Output = Sample>,
+	Divisor: Float + DivisorFromCount,
+{
+	fn add_sample(&mut self, new_sample: Sample) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+
+		let previous_sample = match self.samples.front() {
+			Some(previous_sample) => *previous_sample,
+			None => {
+				self.samples.push_front(new_sample);
+				self.average = new_sample;
+				return;
+			}
+		};
+
+		if WINDOW_SIZE > 1 {
+			self.volatility = self.volatility + (new_sample - previous_sample).abs();
+			if self.samples.len() == WINDOW_SIZE {
+				if let (Some(oldest_sample), Some(second_oldest_sample)) =
+					(self.samples.get(0), self.samples.get(1))
+				{
+					self.volatility =
+						self.volatility - (*second_oldest_sample - *oldest_sample).abs();
+				}
+			}
+		}
+		self.samples.shift(new_sample);
+
+		let smoothing_constant = self.get_smoothing_constant();
+		self.average = self.average + (new_sample - self.average) * smoothing_constant;
+	}
+
+	fn get_average(&self) -> Sample {
+		self.average
+	}
+
+	fn get_most_recent_sample(&self) -> Option<Sample> {
+		self.samples.front().cloned()
+	}
+
+	fn get_oldest_sample(&self) -> Option<Sample> {
+		self.samples.back().cloned()
+	}
+
+	fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
+		self.samples.iter()
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize>
+	KaufmanAdaptiveMovingAverage<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Signed + ToPrimitive,
+	Divisor: Float + DivisorFromCount,
+{
+	/// Constructs a new [KaufmanAdaptiveMovingAverage] with window size `WINDOW_SIZE`, using the
+	/// customary fast and slow EMA periods of `2` and `30` samples.
+	pub fn new() -> Self {
+		Self::with_periods(2, 30)
+	}
+
+	/// Constructs a new [KaufmanAdaptiveMovingAverage] with window size `WINDOW_SIZE`, whose
+	/// smoothing constant lies between those of a fast and a slow EMA, with smoothing factors
+	/// `2 / (fast_period + 1)` and `2 / (slow_period + 1)`.
+	///
+	/// # Panics
+	///
+	/// Panics if `fast_period` is zero or larger than `slow_period`.
+	pub fn with_periods(fast_period: usize, slow_period: usize) -> Self {
+		assert!(
+			0 < fast_period && fast_period <= slow_period,
+			"The fast period must be non-zero and no larger than the slow period"
+		);
+
+		let two = Divisor::one() + Divisor::one();
+		Self {
+			samples: RingBuffer::new(Sample::zero()),
+			volatility: Sample::zero(),
+			average: Sample::zero(),
+			fast_alpha: two / cast_to_divisor_type::<Divisor>(fast_period + 1),
+			slow_alpha: two / cast_to_divisor_type::<Divisor>(slow_period + 1),
+		}
+	}
+
+	/// Returns the efficiency ratio of the samples in the sample window, which lies in the interval
+	/// `[0, 1]`. It is zero if there are fewer than two samples, or if all samples are equal.
+	pub fn get_efficiency_ratio(&self) -> Divisor {
+		let change = match (self.samples.front(), self.samples.back()) {
+			(Some(newest_sample), Some(oldest_sample)) => (*newest_sample - *oldest_sample).abs(),
+			_ => return Divisor::zero(),
+		};
+
+		match (Divisor::from(change), Divisor::from(self.volatility)) {
+			(Some(change), Some(volatility)) if volatility > Divisor::zero() => {
+				(change / volatility).min(Divisor::one())
+			}
+			_ => Divisor::zero(),
+		}
+	}
+
+	fn get_smoothing_constant(&self) -> Divisor {
+		let alpha =
+			self.get_efficiency_ratio() * (self.fast_alpha - self.slow_alpha) + self.slow_alpha;
+		alpha * alpha
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> Default
+	for KaufmanAdaptiveMovingAverage<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Signed + ToPrimitive,
+	Divisor: Float + DivisorFromCount,
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn basics() {
+		let mut kama = KaufmanAdaptiveMovingAverage::<f64, f64, 3>::new();
+		assert_eq!(kama.get_average(), 0.0);
+		assert_eq!(kama.get_efficiency_ratio(), 0.0);
+
+		kama.add_sample(10.0);
+		assert_eq!(kama.get_average(), 10.0);
+
+		// Efficiency ratio 1, so the smoothing constant is (2 / 3)^2
+		kama.add_sample(19.0);
+		assert_eq!(kama.get_efficiency_ratio(), 1.0);
+		assert!((kama.get_average() - 14.0).abs() < 1e-12);
+
+		// |16 - 10| / (9 + 3)
+		kama.add_sample(16.0);
+		assert!((kama.get_efficiency_ratio() - 0.5).abs() < 1e-12);
+		let alpha = 0.5 * (2.0 / 3.0 - 2.0 / 31.0) + 2.0 / 31.0;
+		assert!((kama.get_average() - (14.0 + 2.0 * alpha * alpha)).abs() < 1e-12);
+
+		// |22 - 19| / (3 + 6), after the sample 10 has left the sample window
+		kama.add_sample(22.0);
+		assert!((kama.get_efficiency_ratio() - 1.0 / 3.0).abs() < 1e-12);
+	}
+
+	#[test]
+	fn trending_then_choppy() {
+		let mut kama = KaufmanAdaptiveMovingAverage::<f64, f64, 10>::new();
+
+		for sample in 0..50 {
+			kama.add_sample(f64::from(sample));
+		}
+		assert_eq!(kama.get_efficiency_ratio(), 1.0);
+		assert!((49.0 - kama.get_average()).abs() < 1.5);
+
+		let mut previous_average = kama.get_average();
+		for idx in 0..50 {
+			kama.add_sample(if idx % 2 == 0 { 45.0 } else { 53.0 });
+			let average = kama.get_average();
+			if 10 <= idx {
+				assert!(kama.get_efficiency_ratio() < 0.12);
+				assert!((average - previous_average).abs() < 0.2);
+			}
+			previous_average = average;
+		}
+	}
+
+	#[test]
+	fn edge_case_window_size_one() {
+		let mut kama = KaufmanAdaptiveMovingAverage::<f32, f32, 1>::new();
+		kama.add_sample(1.0);
+		kama.add_sample(5.0);
+		assert_eq!(kama.get_efficiency_ratio(), 0.0);
+		assert!((kama.get_average() - (1.0 + 4.0 * (2.0f32 / 31.0).powi(2))).abs() < 1e-6);
+	}
+
+	#[test]
+	fn edge_case_zero_sized() {
+		let mut kama = KaufmanAdaptiveMovingAverage::<f32, f32, 0>::new();
+		kama.add_sample(1.0);
+		assert_eq!(kama.get_average(), 0.0);
+		assert_eq!(kama.get_num_samples(), 0);
+	}
+}
@@ -26,7 +26,7 @@ motivating it. Bug reports, feature requests and pull requests are welcome throu
 *Scalars*
 ```
 # use simple_moving_average::{SMA, SumTreeSMA};
-let mut ma = SumTreeSMA::<_, f32, 2>::new(); // Sample window size = 2
+let mut ma = SumTreeSMA::<_, f32, 2>::from_zero(0.0); // Sample window size = 2
 ma.add_sample(1.0);
 ma.add_sample(2.0);
 ma.add_sample(3.0);
@@ -38,7 +38,7 @@ assert_eq!(ma.get_average(), 2.5); // = (2 + 3) / 2
 # use simple_moving_average::{SMA, NoSumSMA};
 # use std::time::{Duration, Instant};
 # use nalgebra::Vector3;
-let mut ma = NoSumSMA::<_, f64, 2>::new();
+let mut ma = NoSumSMA::<_, f64, 2>::from_zero(Vector3::zeros());
 ma.add_sample(Vector3::new(1.0, 2.0, 3.0));
 ma.add_sample(Vector3::new(-4.0, -2.0, -1.0));
 assert_eq!(ma.get_average(), Vector3::new(-1.5, 0.0, 1.0));
@@ -58,6 +58,18 @@ loop {
 }
 ```
 
+## Cargo features
+
+The `num-traits` feature is enabled by default. Disabling it removes the dependency on the
+[num-traits](https://docs.rs/num-traits/) crate, which leaves [NoSumSMA], [SingleSumSMA],
+[SumTreeSMA] and [DynamicSMA], constructed using their `from_zero` constructors. Constructors that
+rely on [num_traits::Zero], the methods that are only available for primitive integer or floating
+point samples, and the remaining moving average types require the feature.
+
+The optional `serde` feature implements `Serialize` for [SumTreeSMA], and `Deserialize` if the
+`num-traits` feature is enabled as well. Only the samples in the sample window are serialized,
+oldest first, since the rest of the sum tree can be rebuilt from them.
+
 ## Algorithm implementations
 
 One way to achieve good performance when calculating simple moving averages is to cache previous
@@ -69,6 +81,7 @@ has both pros and cons, which is what motivates the three different implementati
 | [NoSumSMA]     | `O(1)`      | `O(N)`      | -                                           |
 | [SingleSumSMA] | `O(1)`      | `O(1)`      | Accumulates floating point rounding errors. |
 | [SumTreeSMA]   | `O(log(N))` | `O(1)`      | -                                           |
+| [KahanSumSMA]  | `O(1)`      | `O(1)`      | Floating point samples only.                |
 
 `N` refers to the size of the sample window.
 
@@ -178,26 +191,178 @@ with [NoSumSMA].
  - In most cases where floating point data is involved, unless writes are much more common than
    reads.
 
+### KahanSumSMA
+
+A variant of [SingleSumSMA] that, in addition to the sum, caches a compensation term keeping track
+of the low-order bits lost every time a sample is added to or removed from the sum, using
+[Kahan-Babuška-Neumaier summation](https://en.wikipedia.org/wiki/Kahan_summation_algorithm#Further_enhancements).
+This keeps the accumulated rounding error orders of magnitude smaller than that of [SingleSumSMA],
+at the cost of a few extra floating point operations per sample.
+
+**When to use**
+ - When floating point data is involved and both reads and writes need to be `O(1)`.
+
 */
 
+#[cfg(feature = "num-traits")]
+mod adaptive_window_moving_average;
+mod cached_sma;
+#[cfg(feature = "num-traits")]
+mod centered_moving_average;
 mod common;
+#[cfg(feature = "num-traits")]
+mod cumulative_moving_average;
+#[cfg(feature = "num-traits")]
+mod decay_weighted_moving_average;
+mod divisor_from_count;
+#[cfg(feature = "num-traits")]
+mod double_exponential_moving_average;
+mod dynamic_sma;
+mod error;
+#[cfg(feature = "num-traits")]
+mod exponential_moving_average;
+#[cfg(feature = "num-traits")]
+mod gaussian_moving_average;
+#[cfg(feature = "num-traits")]
+mod geometric_moving_average;
+#[cfg(feature = "num-traits")]
+mod harmonic_moving_average;
+#[cfg(feature = "num-traits")]
+mod hull_moving_average;
 mod iterator;
+#[cfg(feature = "num-traits")]
+mod kahan_sum_sma;
+#[cfg(feature = "num-traits")]
+mod kaufman_adaptive_moving_average;
+#[cfg(feature = "num-traits")]
+mod moving_average_builder;
+#[cfg(feature = "num-traits")]
+mod moving_variance;
 mod no_sum_sma;
+mod no_sum_sma_cloned;
 mod ring_buffer;
+#[cfg(feature = "num-traits")]
+mod rms_moving_average;
+#[cfg(feature = "num-traits")]
+mod robust_sma;
+#[cfg(feature = "num-traits")]
+mod rolling_correlation;
+mod rolling_extremes;
+mod rolling_median;
+#[cfg(feature = "num-traits")]
+mod rolling_quantile;
+#[cfg(feature = "num-traits")]
+mod rounding_mode;
+#[cfg(feature = "num-traits")]
+mod running_average;
+#[cfg(feature = "num-traits")]
+mod saturating_sum_sma;
+#[cfg(feature = "num-traits")]
+mod savitzky_golay_filter;
+mod shared_sma;
 mod single_sum_sma;
 mod sma;
+#[cfg(feature = "num-traits")]
+mod sma_variance_ext;
+#[cfg(feature = "num-traits")]
+mod smoothed_moving_average;
+mod snapshot;
 mod sum_tree;
 mod sum_tree_sma;
-
-pub use crate::iterator::Iter;
+#[cfg(feature = "num-traits")]
+mod time_decay_moving_average;
+#[cfg(feature = "num-traits")]
+mod triangular_sma;
+#[cfg(feature = "num-traits")]
+mod triple_exponential_moving_average;
+#[cfg(feature = "num-traits")]
+mod volume_weighted_moving_average;
+#[cfg(feature = "num-traits")]
+mod weighted_moving_average;
+#[cfg(feature = "num-traits")]
+mod widening_sum_sma;
+
+#[cfg(feature = "num-traits")]
+pub use crate::adaptive_window_moving_average::AdaptiveWindowMovingAverage;
+pub use crate::cached_sma::CachedSMA;
+#[cfg(feature = "num-traits")]
+pub use crate::centered_moving_average::{CenteredMovingAverage, EdgeHandling};
+#[cfg(feature = "num-traits")]
+pub use crate::cumulative_moving_average::CumulativeMovingAverage;
+#[cfg(feature = "num-traits")]
+pub use crate::decay_weighted_moving_average::DecayWeightedMovingAverage;
+pub use crate::divisor_from_count::DivisorFromCount;
+#[cfg(feature = "num-traits")]
+pub use crate::double_exponential_moving_average::DoubleExponentialMovingAverage;
+pub use crate::dynamic_sma::DynamicSMA;
+pub use crate::error::{DivisorError, OverflowError, WindowSizeError};
+#[cfg(feature = "num-traits")]
+pub use crate::exponential_moving_average::ExponentialMovingAverage;
+#[cfg(feature = "num-traits")]
+pub use crate::gaussian_moving_average::GaussianMovingAverage;
+#[cfg(feature = "num-traits")]
+pub use crate::geometric_moving_average::GeometricMovingAverage;
+#[cfg(feature = "num-traits")]
+pub use crate::harmonic_moving_average::HarmonicMovingAverage;
+#[cfg(feature = "num-traits")]
+pub use crate::hull_moving_average::HullMovingAverage;
+pub use crate::iterator::{IntoIter, Iter};
+#[cfg(feature = "num-traits")]
+pub use crate::kahan_sum_sma::KahanSumSMA;
+#[cfg(feature = "num-traits")]
+pub use crate::kaufman_adaptive_moving_average::KaufmanAdaptiveMovingAverage;
+#[cfg(feature = "num-traits")]
+pub use crate::moving_average_builder::MovingAverageBuilder;
+#[cfg(feature = "num-traits")]
+pub use crate::moving_variance::MovingVariance;
 pub use crate::no_sum_sma::NoSumSMA;
+pub use crate::no_sum_sma_cloned::NoSumSMACloned;
+#[cfg(feature = "num-traits")]
+pub use crate::rms_moving_average::RmsMovingAverage;
+#[cfg(feature = "num-traits")]
+pub use crate::robust_sma::RobustSMA;
+#[cfg(feature = "num-traits")]
+pub use crate::rolling_correlation::RollingCorrelation;
+pub use crate::rolling_extremes::RollingExtremes;
+pub use crate::rolling_median::RollingMedian;
+#[cfg(feature = "num-traits")]
+pub use crate::rolling_quantile::{Interpolate, RollingQuantile};
+#[cfg(feature = "num-traits")]
+pub use crate::rounding_mode::RoundingMode;
+#[cfg(feature = "num-traits")]
+pub use crate::running_average::{RunningAverage, SMAIterExt};
+#[cfg(feature = "num-traits")]
+pub use crate::saturating_sum_sma::SaturatingSumSMA;
+#[cfg(feature = "num-traits")]
+pub use crate::savitzky_golay_filter::SavitzkyGolayFilter;
+pub use crate::shared_sma::SharedSMA;
 pub use crate::single_sum_sma::SingleSumSMA;
 pub use crate::sma::SMA;
+#[cfg(feature = "num-traits")]
+pub use crate::sma_variance_ext::SMAVarianceExt;
+#[cfg(feature = "num-traits")]
+pub use crate::smoothed_moving_average::SmoothedMovingAverage;
+pub use crate::snapshot::Snapshot;
 pub use crate::sum_tree_sma::SumTreeSMA;
-
-#[cfg(test)]
+#[cfg(feature = "num-traits")]
+pub use crate::time_decay_moving_average::TimeDecayMovingAverage;
+#[cfg(feature = "num-traits")]
+pub use crate::triangular_sma::TriangularSMA;
+#[cfg(feature = "num-traits")]
+pub use crate::triple_exponential_moving_average::TripleExponentialMovingAverage;
+#[cfg(feature = "num-traits")]
+pub use crate::volume_weighted_moving_average::VolumeWeightedMovingAverage;
+#[cfg(feature = "num-traits")]
+pub use crate::weighted_moving_average::WeightedMovingAverage;
+#[cfg(feature = "num-traits")]
+pub use crate::widening_sum_sma::WideningSumSMA;
+
+#[cfg(all(test, feature = "num-traits"))]
 mod tests {
-	use crate::{NoSumSMA, SingleSumSMA, SumTreeSMA, SMA};
+	use crate::{
+		DynamicSMA, KahanSumSMA, MovingAverageBuilder, NoSumSMA, SMAVarianceExt, SaturatingSumSMA,
+		SingleSumSMA, SumTreeSMA, WideningSumSMA, SMA,
+	};
 
 	macro_rules! get_sma_impls {
 		(
@@ -295,6 +460,59 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn u128_samples() {
+		// Sums that would overflow any narrower integer type
+		let large = u128::MAX / 4;
+		for sma in &mut get_sma_impls!(u128, 3, new) {
+			sma.add_sample(large);
+			sma.add_sample(large + 2);
+			assert_eq!(sma.get_average(), large + 1);
+
+			sma.add_sample(large + 4);
+			assert_eq!(sma.get_average(), large + 2);
+
+			sma.add_sample(0);
+			sma.add_sample(0);
+			sma.add_sample(3);
+			assert_eq!(sma.get_average(), 1);
+		}
+	}
+
+	#[test]
+	fn i128_samples() {
+		let large = i128::MIN / 4;
+		for sma in &mut get_sma_impls!(i128, 3, new) {
+			sma.add_sample(large);
+			sma.add_sample(large - 2);
+			assert_eq!(sma.get_average(), large - 1);
+
+			sma.add_sample(-3);
+			sma.add_sample(-4);
+			sma.add_sample(0);
+			// Integer division truncates towards zero
+			assert_eq!(sma.get_average(), -2);
+
+			sma.add_sample(-9);
+			assert_eq!(sma.get_average(), -4);
+		}
+	}
+
+	#[test]
+	fn i64_samples() {
+		for sma in &mut get_sma_impls!(i64, 4, new) {
+			for sample in [i64::MIN / 4, i64::MIN / 4 + 4, -6, 10] {
+				sma.add_sample(sample);
+			}
+			assert_eq!(sma.get_average(), i64::MIN / 8 + 2);
+
+			for sample in [-5, -6, -7, 2] {
+				sma.add_sample(sample);
+			}
+			assert_eq!(sma.get_average(), -4);
+		}
+	}
+
 	#[test]
 	fn nalgebra_vector2_f32_samples() {
 		use nalgebra::Vector2;
@@ -401,6 +619,58 @@ mod tests {
 			assert_eq!(sma.get_average(), 0);
 			assert_eq!(sma.get_num_samples(), 0);
 		}
+
+		for sma in &mut get_sma_impls!(u32, 0, new) {
+			assert!(sma.is_disabled());
+
+			sma.add_samples(&[1, 2, 3]);
+			assert_eq!(sma.scan_averages(&[4, 5]), vec![0, 0]);
+			sma.add_sample_opt(Some(6));
+
+			assert_eq!(sma.get_average(), 0);
+			assert_eq!(sma.try_get_average(), Ok(0));
+			assert_eq!(sma.get_average_if_nonempty(), None);
+			assert_eq!(sma.get_rounded_average(crate::RoundingMode::HalfUp), 0);
+			assert_eq!(sma.get_average_of_last(1), None);
+			assert_eq!(sma.get_average_when_full(), None);
+
+			assert_eq!(sma.get_num_samples(), 0);
+			assert_eq!(sma.get_sample_window_size(), 0);
+			assert_eq!(sma.get_sample_window_iter().count(), 0);
+			assert_eq!(sma.get_sample_window_rev_iter().count(), 0);
+			assert_eq!(sma.get_most_recent_sample(), None);
+			assert_eq!(sma.get_oldest_sample(), None);
+			assert_eq!(sma.peek_would_evict(), None);
+			assert_eq!(sma.get_momentum(), None);
+			assert_eq!(sma.get_rate_of_change(), None);
+
+			assert!(sma.is_empty());
+			assert!(sma.is_full());
+			assert_eq!(sma.fill_ratio(), 1.0);
+		}
+
+		for sma in &mut get_sma_impls!(f32, 0, new) {
+			assert!(sma.add_finite_sample(1.0));
+			assert_eq!(sma.get_variance(), 0.0);
+			assert_eq!(sma.get_std_dev(), 0.0);
+			assert_eq!(sma.get_rms(), 0.0);
+			assert_eq!(sma.get_num_samples(), 0);
+		}
+
+		macro_rules! assert_sized_methods_zero_sized {
+			($sma_type:ident) => {
+				let sma = $sma_type::<u32, u32, 0>::new();
+				assert_eq!(sma.get_average_as::<f64>(), 0.0);
+				let mut num_samples = 0;
+				sma.for_each_sample(|_| num_samples += 1);
+				assert_eq!(num_samples, 0);
+				assert!(!$sma_type::<u32, u32, 5>::new().is_disabled());
+			};
+		}
+
+		assert_sized_methods_zero_sized!(NoSumSMA);
+		assert_sized_methods_zero_sized!(SingleSumSMA);
+		assert_sized_methods_zero_sized!(SumTreeSMA);
 	}
 
 	#[test]
@@ -435,74 +705,1977 @@ mod tests {
 	}
 
 	#[test]
-	fn f32_random_samples_max_algorithm_diffs() {
-		use rand::{distributions::Uniform, rngs::SmallRng, Rng, SeedableRng};
-		use rayon::prelude::*;
+	fn with_initial() {
+		for sma in &mut get_sma_impls!(f32, 4, with_initial, 5.0) {
+			assert_eq!(sma.get_num_samples(), 4);
+			assert_eq!(sma.get_average(), 5.0);
+			assert_eq!(sma.get_most_recent_sample(), Some(5.0));
 
-		const WINDOW_SIZE: usize = 10;
-		const VALUE_RANGES: [(usize, usize); 6] = [
-			(0, 10),
-			(10, 100),
-			(100, 1000),
-			(1000, 10000),
-			(10000, 100000),
-			(100000, 1000000),
+			sma.add_sample(9.0);
+			assert_eq!(sma.get_num_samples(), 4);
+			assert_eq!(sma.get_average(), 6.0);
+		}
+
+		for sma in &mut get_sma_impls!(u32, 0, with_initial, 5) {
+			assert_eq!(sma.get_num_samples(), 0);
+			assert_eq!(sma.get_average(), 0);
+		}
+	}
+
+	#[test]
+	fn with_initial_from_zero() {
+		use std::time::Duration;
+
+		let initial = Duration::from_secs(3);
+		let ma_impls: [Box<dyn SMA<_, u32, 3>>; 3] = [
+			Box::new(SingleSumSMA::<_, _, 3>::with_initial_from_zero(
+				Duration::ZERO,
+				initial,
+			)),
+			Box::new(SumTreeSMA::<_, _, 3>::with_initial_from_zero(
+				Duration::ZERO,
+				initial,
+			)),
+			Box::new(NoSumSMA::<_, _, 3>::with_initial_from_zero(
+				Duration::ZERO,
+				initial,
+			)),
 		];
 
-		let seeds: Vec<u64> = SmallRng::seed_from_u64(0xCAFEBABE)
-			.sample_iter(&Uniform::from(0..u64::MAX))
-			.take(100)
-			.collect();
+		for mut sma in ma_impls {
+			assert_eq!(sma.get_num_samples(), 3);
+			assert_eq!(sma.get_average(), initial);
 
-		let averages_array_vec: Vec<[[f32; 3]; VALUE_RANGES.len()]> = seeds
-			.par_iter()
-			.map(|seed| {
-				let random_values: Vec<f32> = SmallRng::seed_from_u64(*seed)
-					.sample_iter(&Uniform::from(-100.0..100.0))
-					.take(1000000)
-					.collect();
+			sma.add_sample(Duration::from_secs(6));
+			assert_eq!(sma.get_average(), Duration::from_secs(4));
+		}
+	}
 
-				let mut single_sum_sma = SingleSumSMA::<_, f32, WINDOW_SIZE>::new();
-				let mut sum_tree_sma = SumTreeSMA::<_, f32, WINDOW_SIZE>::new();
-				let mut no_sum_sma = NoSumSMA::<_, f32, WINDOW_SIZE>::new();
+	#[test]
+	fn from_iter_and_extend() {
+		let sma: SumTreeSMA<_, f32, 3> = (1..=10).map(|sample| sample as f32).collect();
+		assert_eq!(sma.get_num_samples(), 3);
+		assert_eq!(sma.get_average(), 9.0);
+
+		let mut sma: SingleSumSMA<_, u32, 3> = vec![1, 2].into_iter().collect();
+		assert_eq!(sma.get_average(), 1);
+		sma.extend([6, 7, 8]);
+		assert_eq!(sma.get_average(), 7);
+
+		let mut sma: NoSumSMA<_, u32, 3> = std::iter::empty().collect();
+		assert_eq!(sma.get_num_samples(), 0);
+		sma.extend(vec![4, 8]);
+		assert_eq!(sma.get_average(), 6);
+	}
 
-				VALUE_RANGES.map(|value_range| {
-					for random_value in &random_values[value_range.0..value_range.1] {
-						single_sum_sma.add_sample(*random_value);
-						sum_tree_sma.add_sample(*random_value);
-						no_sum_sma.add_sample(*random_value);
-					}
-					[
-						single_sum_sma.get_average(),
-						sum_tree_sma.get_average(),
-						no_sum_sma.get_average(),
-					]
-				})
-			})
-			.collect();
+	#[test]
+	fn clones_are_independent() {
+		fn assert_clone_diverges<T: SMA<u32, u32, 3> + Clone>(mut sma: T) {
+			sma.add_sample(3);
+			sma.add_sample(6);
 
-		let mut maximum_absolute_diffs_array = [[0.0f32; VALUE_RANGES.len()]; 2];
+			let mut clone = sma.clone();
+			assert_eq!(clone.get_average(), 4);
 
-		for averages_array in averages_array_vec {
-			for (idx, averages) in averages_array.iter().enumerate() {
-				for i in 0..2 {
-					let abs_diff = (averages[i] - averages[2]).abs();
-					if maximum_absolute_diffs_array[i][idx] < abs_diff {
-						maximum_absolute_diffs_array[i][idx] = abs_diff;
-					}
+			sma.add_sample(12);
+			assert_eq!(sma.get_average(), 7);
+			assert_eq!(sma.get_num_samples(), 3);
+			assert_eq!(clone.get_average(), 4);
+			assert_eq!(clone.get_num_samples(), 2);
+
+			clone.add_sample(0);
+			clone.add_sample(0);
+			assert_eq!(clone.get_average(), 2);
+			assert_eq!(sma.get_average(), 7);
+		}
+
+		assert_clone_diverges(SingleSumSMA::<_, _, 3>::new());
+		assert_clone_diverges(SumTreeSMA::<_, _, 3>::new());
+		assert_clone_diverges(NoSumSMA::<_, _, 3>::new());
+	}
+
+	#[test]
+	fn sample_window_iter_shared_borrow() {
+		for sma in &mut get_sma_impls!(u32, 3, new) {
+			sma.add_sample(1);
+			sma.add_sample(2);
+
+			let sma: &dyn SMA<u32, u32, 3> = sma.as_ref();
+			let other_borrow = &sma;
+			let samples = sma.get_sample_window_iter();
+
+			assert_eq!(other_borrow.get_average(), 1);
+			assert_eq!(samples.collect::<Vec<&u32>>(), vec![&1, &2]);
+		}
+	}
+
+	#[test]
+	fn try_get_average() {
+		for sma in &mut get_sma_impls!(u8, 300, new) {
+			assert_eq!(sma.try_get_average(), Ok(0));
+
+			for _ in 0..255 {
+				sma.add_sample(0);
+			}
+			assert_eq!(sma.try_get_average(), Ok(0));
+
+			sma.add_sample(0);
+			let error = sma.try_get_average().unwrap_err();
+			assert_eq!(error.count(), 256);
+			assert_eq!(error.divisor_type_name(), "u8");
+		}
+	}
+
+	#[test]
+	fn oldest_sample() {
+		for sma in &mut get_sma_impls!(u32, 3, new) {
+			assert_eq!(sma.get_oldest_sample(), None);
+
+			sma.add_sample(1);
+			assert_eq!(sma.get_oldest_sample(), Some(1));
+
+			sma.add_sample(2);
+			sma.add_sample(3);
+			assert_eq!(sma.get_oldest_sample(), Some(1));
+
+			sma.add_sample(4);
+			assert_eq!(sma.get_oldest_sample(), Some(2));
+
+			sma.add_sample(5);
+			sma.add_sample(6);
+			sma.add_sample(7);
+			assert_eq!(sma.get_oldest_sample(), Some(5));
+			assert_eq!(sma.get_most_recent_sample(), Some(7));
+		}
+
+		for sma in &mut get_sma_impls!(u32, 0, new) {
+			sma.add_sample(1);
+			assert_eq!(sma.get_oldest_sample(), None);
+		}
+	}
+
+	#[test]
+	fn add_samples() {
+		let samples = [4.0, 8.0, 3.0, 7.0, 11.0, 0.0, -23.0];
+
+		for split_idx in 0..=samples.len() {
+			let batched_impls = &mut get_sma_impls!(f32, 3, new);
+			let sequential_impls = &mut get_sma_impls!(f32, 3, new);
+
+			for (batched, sequential) in batched_impls.iter_mut().zip(sequential_impls.iter_mut()) {
+				batched.add_samples(&samples[..split_idx]);
+				batched.add_samples(&samples[split_idx..]);
+				for sample in samples {
+					sequential.add_sample(sample);
 				}
+
+				assert_eq!(batched.get_average(), sequential.get_average());
+				assert_eq!(batched.get_num_samples(), sequential.get_num_samples());
+				assert_eq!(
+					batched.get_most_recent_sample(),
+					sequential.get_most_recent_sample()
+				);
+				assert_eq!(batched.get_oldest_sample(), sequential.get_oldest_sample());
+				assert!(batched
+					.get_sample_window_iter()
+					.eq(sequential.get_sample_window_iter()));
 			}
 		}
 
-		let [single_sum_maximum_absolute_diff, sum_tree_maximum_absolute_diff]: [f32; 2] =
-			maximum_absolute_diffs_array.map(|maximum_absolute_diffs| {
-				*maximum_absolute_diffs
-					.iter()
-					.max_by(|a, b| a.abs().partial_cmp(&b.abs()).unwrap())
-					.unwrap()
-			});
+		for sma in &mut get_sma_impls!(u32, 0, new) {
+			sma.add_samples(&[1, 2, 3]);
+			assert_eq!(sma.get_num_samples(), 0);
+		}
+	}
 
-		assert!(single_sum_maximum_absolute_diff < 0.002);
-		assert!(sum_tree_maximum_absolute_diff < 0.000006);
+	#[test]
+	fn edge_case_single_sample_window() {
+		for sma in &mut get_sma_impls!(u32, 1, new) {
+			sma.add_sample(3);
+			assert_eq!(sma.get_average(), 3);
+
+			sma.add_sample(5);
+			assert_eq!(sma.get_average(), 5);
+			assert_eq!(sma.get_num_samples(), 1);
+
+			sma.add_samples(&[7, 9]);
+			assert_eq!(sma.get_average(), 9);
+		}
+	}
+
+	#[test]
+	fn debug_format() {
+		let mut sma = SingleSumSMA::<_, u32, 3>::new();
+		assert_eq!(
+			format!("{:?}", sma),
+			"SingleSumSMA { window_size: 3, num_samples: 0, samples: [], sum: 0, \
+			 divisor: \"u32\" }"
+		);
+
+		sma.add_sample(4);
+		sma.add_sample(8);
+		assert_eq!(
+			format!("{:?}", sma),
+			"SingleSumSMA { window_size: 3, num_samples: 2, samples: [4, 8], sum: 12, \
+			 divisor: \"u32\" }"
+		);
+
+		let mut sma = SumTreeSMA::<_, f32, 2>::new();
+		sma.add_sample(1.0);
+		assert_eq!(
+			format!("{:?}", sma),
+			"SumTreeSMA { window_size: 2, num_samples: 1, samples: [1.0], sum: 1.0, \
+			 divisor: \"f32\" }"
+		);
+
+		let mut sma = NoSumSMA::<_, u32, 2>::new();
+		sma.add_samples(&[1, 2, 3]);
+		assert_eq!(
+			format!("{:?}", sma),
+			"NoSumSMA { window_size: 2, num_samples: 2, samples: [2, 3], divisor: \"u32\" }"
+		);
+
+		// Formatting does not calculate the average, whose sum and sample count would both overflow
+		// an i8
+		let mut sma = NoSumSMA::<i8, i8, 200>::new();
+		sma.add_samples(&[1; 200]);
+		assert!(format!("{:?}", sma)
+			.starts_with("NoSumSMA { window_size: 200, num_samples: 200, samples: [1, 1,"));
+	}
+
+	#[test]
+	fn display_format() {
+		macro_rules! assert_display {
+			($sma_type:ident) => {
+				let mut sma = $sma_type::<f32, f32, 3>::new();
+				assert_eq!(format!("{}", sma), "0");
+				assert_eq!(format!("{:.2}", sma), "0.00");
+
+				sma.add_samples(&[1.0, 2.0, 2.0]);
+				assert_eq!(format!("{}", sma), format!("{}", sma.get_average()));
+				assert_eq!(format!("{:.2}", sma), "1.67");
+				assert_eq!(format!("{:>8.3}", sma), "   1.667");
+
+				let mut sma = $sma_type::<u32, u32, 2>::new();
+				sma.add_samples(&[7, 9]);
+				assert_eq!(format!("{:03}", sma), "008");
+			};
+		}
+
+		assert_display!(NoSumSMA);
+		assert_display!(SingleSumSMA);
+		assert_display!(SumTreeSMA);
+	}
+
+	#[test]
+	fn from_array_and_slice() {
+		use std::convert::TryFrom;
+
+		macro_rules! assert_from {
+			($sma_type:ident) => {
+				let sma = $sma_type::<_, u32, 3>::from([1, 2, 6]);
+				assert_eq!(sma.get_average(), 3);
+				assert_eq!(sma.get_num_samples(), 3);
+				assert_eq!(sma.get_most_recent_sample(), Some(6));
+
+				let sma = $sma_type::<_, u32, 3>::try_from(&[1, 2, 6][..]).unwrap();
+				assert_eq!(sma.get_average(), 3);
+				assert_eq!(sma.get_num_samples(), 3);
+
+				let sma = $sma_type::<_, u32, 3>::try_from(&[4, 8][..]).unwrap();
+				assert_eq!(sma.get_average(), 6);
+				assert_eq!(sma.get_num_samples(), 2);
+				assert_eq!(sma.get_oldest_sample(), Some(4));
+
+				let error = $sma_type::<_, u32, 3>::try_from(&[1, 2, 3, 4][..]).unwrap_err();
+				assert_eq!(error.num_samples(), 4);
+				assert_eq!(error.window_size(), 3);
+			};
+		}
+
+		assert_from!(NoSumSMA);
+		assert_from!(SingleSumSMA);
+		assert_from!(SumTreeSMA);
+	}
+
+	#[test]
+	fn add_finite_sample() {
+		for sma in &mut get_sma_impls!(f64, 3, new) {
+			assert!(sma.add_finite_sample(4.0));
+			assert!(!sma.add_finite_sample(f64::NAN));
+			assert!(sma.add_finite_sample(8.0));
+			assert!(!sma.add_finite_sample(f64::INFINITY));
+			assert!(!sma.add_finite_sample(f64::NEG_INFINITY));
+			assert_eq!(sma.get_average(), 6.0);
+			assert_eq!(sma.get_num_samples(), 2);
+
+			assert!(sma.add_finite_sample(3.0));
+			assert!(sma.add_finite_sample(7.0));
+			assert!(!sma.add_finite_sample(f64::NAN));
+			assert_eq!(sma.get_average(), 6.0);
+			assert_eq!(sma.get_most_recent_sample(), Some(7.0));
+		}
+	}
+
+	#[test]
+	fn sum_tree_from_slice() {
+		let samples = [4.0, 8.0, 3.0, 7.0, 11.0, 0.0, -23.0];
+
+		for num_samples in 0..=samples.len() {
+			let sma = SumTreeSMA::<_, f32, 5>::from_slice(&samples[..num_samples]);
+			let mut reference_sma = SumTreeSMA::<_, f32, 5>::new();
+			reference_sma.add_samples(&samples[..num_samples]);
+
+			assert_eq!(sma.get_average(), reference_sma.get_average());
+			assert_eq!(sma.get_num_samples(), reference_sma.get_num_samples());
+			assert_eq!(
+				sma.get_most_recent_sample(),
+				reference_sma.get_most_recent_sample()
+			);
+			assert_eq!(sma.get_oldest_sample(), reference_sma.get_oldest_sample());
+		}
+
+		let mut sma = SumTreeSMA::<_, u32, 3>::from_slice(&[1, 2, 3]);
+		sma.add_sample(10);
+		assert_eq!(sma.get_average(), 5);
+		assert_eq!(sma.get_oldest_sample(), Some(2));
+
+		let sma = SumTreeSMA::<u32, u32, 0>::from_slice(&[1, 2, 3]);
+		assert_eq!(sma.get_num_samples(), 0);
+	}
+
+	#[test]
+	fn average_if_nonempty() {
+		for sma in &mut get_sma_impls!(f32, 3, new) {
+			assert_eq!(sma.get_average_if_nonempty(), None);
+			sma.add_sample(0.0);
+			assert_eq!(sma.get_average_if_nonempty(), Some(0.0));
+			sma.add_sample(3.0);
+			assert_eq!(sma.get_average_if_nonempty(), Some(1.5));
+		}
+
+		for sma in &mut get_sma_impls!(f32, 0, new) {
+			sma.add_sample(1.0);
+			assert_eq!(sma.get_average_if_nonempty(), None);
+		}
+	}
+
+	#[test]
+	fn eq() {
+		macro_rules! assert_eq_by_window {
+			($sma_type:ident) => {
+				let a: $sma_type<u32, u32, 3> = [1, 2, 3, 4, 5].iter().cloned().collect();
+				let b: $sma_type<u32, u32, 3> = [9, 3, 4, 5].iter().cloned().collect();
+				let mut c = $sma_type::<u32, u32, 3>::new();
+				c.add_samples(&[3, 4]);
+
+				assert_eq!(
+					a.get_sample_window_iter().collect::<Vec<&u32>>(),
+					vec![&3, &4, &5]
+				);
+				assert_eq!(
+					b.get_sample_window_iter().collect::<Vec<&u32>>(),
+					vec![&3, &4, &5]
+				);
+				assert_eq!(a, b);
+				assert_ne!(a, c);
+
+				c.add_sample(5);
+				assert_eq!(a, c);
+
+				c.add_sample(6);
+				assert_ne!(a, c);
+				assert_eq!(
+					c.get_sample_window_iter().collect::<Vec<&u32>>(),
+					vec![&4, &5, &6]
+				);
+			};
+		}
+
+		assert_eq_by_window!(NoSumSMA);
+		assert_eq_by_window!(SingleSumSMA);
+		assert_eq_by_window!(SumTreeSMA);
+
+		let mut a = KahanSumSMA::<f64, f64, 2>::new();
+		let mut b = KahanSumSMA::<f64, f64, 2>::new();
+		a.add_samples(&[0.1, 0.2, 0.3]);
+		b.add_samples(&[0.2, 0.3]);
+		assert_eq!(a, b);
+
+		let mut a = SaturatingSumSMA::<u8, u8, 2>::new();
+		let mut b = SaturatingSumSMA::<u8, u8, 2>::new();
+		a.add_samples(&[200, 100, 5]);
+		b.add_samples(&[100, 5]);
+		assert_eq!(a, b);
+
+		let mut a = DynamicSMA::<u32, u32>::new(2);
+		let mut b = DynamicSMA::<u32, u32>::new(2);
+		a.add_sample(1);
+		a.add_sample(2);
+		a.add_sample(3);
+		b.add_sample(2);
+		b.add_sample(3);
+		assert_eq!(a, b);
+		assert_ne!(a, DynamicSMA::<u32, u32>::new(3));
+	}
+
+	#[test]
+	fn hash() {
+		use std::collections::HashSet;
+
+		macro_rules! assert_hash_by_window {
+			($sma_type:ident) => {
+				let a: $sma_type<u32, u32, 3> = [1, 2, 3, 4, 5].iter().cloned().collect();
+				let b: $sma_type<u32, u32, 3> = [9, 3, 4, 5].iter().cloned().collect();
+				let c: $sma_type<u32, u32, 3> = [4, 5].iter().cloned().collect();
+
+				let set: HashSet<_> = vec![a.clone(), b].into_iter().collect();
+				assert_eq!(set.len(), 1);
+				assert!(set.contains(&a));
+				assert!(!set.contains(&c));
+			};
+		}
+
+		assert_hash_by_window!(NoSumSMA);
+		assert_hash_by_window!(SingleSumSMA);
+		assert_hash_by_window!(SumTreeSMA);
+	}
+
+	#[test]
+	fn sample_window_rev_iter() {
+		let samples = [4, 8, 3, 7, 11, 0, 23];
+
+		for num_samples in 0..=samples.len() {
+			let mut expected: Vec<&u32> = samples[num_samples.saturating_sub(5)..num_samples]
+				.iter()
+				.collect();
+			expected.reverse();
+
+			for sma in &mut get_sma_impls!(u32, 5, new) {
+				sma.add_samples(&samples[..num_samples]);
+				assert_eq!(
+					sma.get_sample_window_rev_iter().collect::<Vec<&u32>>(),
+					expected
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn momentum_and_rate_of_change() {
+		for sma in &mut get_sma_impls!(i32, 4, new) {
+			assert_eq!(sma.get_momentum(), None);
+			assert_eq!(sma.get_rate_of_change(), None);
+
+			sma.add_sample(10);
+			assert_eq!(sma.get_momentum(), None);
+			assert_eq!(sma.get_rate_of_change(), None);
+
+			sma.add_sample(13);
+			assert_eq!(sma.get_momentum(), Some(3));
+			assert_eq!(sma.get_rate_of_change(), Some(3));
+
+			sma.add_sample(16);
+			assert_eq!(sma.get_momentum(), Some(6));
+			assert_eq!(sma.get_rate_of_change(), Some(3));
+
+			for sample in (19..=40).step_by(3) {
+				sma.add_sample(sample);
+				assert_eq!(sma.get_momentum(), Some(9));
+				assert_eq!(sma.get_rate_of_change(), Some(3));
+			}
+
+			sma.add_sample(0);
+			assert_eq!(sma.get_momentum(), Some(-34));
+		}
+	}
+
+	#[test]
+	fn derive_default() {
+		#[derive(Default)]
+		struct Stats {
+			frame_time: SumTreeSMA<f32, f32, 16>,
+			frame_count: SingleSumSMA<u32, u32, 16>,
+			latency: NoSumSMA<f64, f64, 16>,
+		}
+
+		let stats = Stats::default();
+		assert_eq!(stats.frame_time.get_average(), 0.0);
+		assert_eq!(stats.frame_time.get_sample_window_size(), 16);
+		assert_eq!(stats.frame_count.get_num_samples(), 0);
+		assert_eq!(stats.latency.get_average(), 0.0);
+	}
+
+	#[test]
+	fn no_sum_pairwise_summation() {
+		use rand::{distributions::Uniform, rngs::SmallRng, Rng, SeedableRng};
+
+		const WINDOW_SIZE: usize = 4096;
+
+		let samples: Vec<f32> = SmallRng::seed_from_u64(0xDEADBEEF)
+			.sample_iter(&Uniform::from(0.0..1000.0))
+			.take(WINDOW_SIZE + 1000)
+			.collect();
+		let window = &samples[samples.len() - WINDOW_SIZE..];
+
+		let mut sma = NoSumSMA::<_, f32, WINDOW_SIZE>::new();
+		sma.add_samples(&samples);
+
+		let reference_average =
+			window.iter().map(|sample| *sample as f64).sum::<f64>() / WINDOW_SIZE as f64;
+		let naive_average = window.iter().sum::<f32>() / WINDOW_SIZE as f32;
+
+		let pairwise_error = (sma.get_average() as f64 - reference_average).abs();
+		let naive_error = (naive_average as f64 - reference_average).abs();
+		assert!(pairwise_error <= naive_error);
+		assert!(pairwise_error < 1e-4);
+	}
+
+	#[test]
+	fn no_sum_average_independent_of_wraparound() {
+		const WINDOW_SIZE: usize = 40;
+
+		let window: Vec<f64> = (0..WINDOW_SIZE)
+			.map(|idx| 1.0 / (idx as f64 + 1.0) + 1e8)
+			.collect();
+
+		let mut sma = NoSumSMA::<_, f64, WINDOW_SIZE>::new();
+		sma.add_samples(&window);
+		let average = sma.get_average();
+
+		for num_leading_samples in 1..WINDOW_SIZE {
+			let mut wrapped_sma = NoSumSMA::<_, f64, WINDOW_SIZE>::new();
+			wrapped_sma.add_samples(&vec![0.0; num_leading_samples]);
+			wrapped_sma.add_samples(&window);
+
+			assert!(wrapped_sma == sma);
+			assert_eq!(wrapped_sma.get_average(), average);
+		}
+	}
+
+	#[test]
+	fn difference_average() {
+		macro_rules! assert_difference_average {
+			($sma_type:ident) => {
+				let mut long_sma = $sma_type::<u32, u32, 6>::new();
+				let mut short_sma = $sma_type::<u32, u32, 2>::new();
+				assert_eq!(long_sma.get_difference_average(&short_sma), None);
+
+				for sample in [1, 2, 3, 4, 10, 20, 30, 40] {
+					long_sma.add_sample(sample);
+					short_sma.add_sample(sample);
+				}
+
+				// Window: [3, 4, 10, 20, 30, 40], sub-window: [30, 40]
+				assert_eq!(long_sma.get_difference_average(&short_sma), Some(9));
+				assert_eq!(short_sma.get_difference_average(&long_sma), None);
+				assert_eq!(long_sma.get_difference_average(&long_sma), None);
+			};
+		}
+
+		assert_difference_average!(SingleSumSMA);
+		assert_difference_average!(SumTreeSMA);
+	}
+
+	#[test]
+	fn sum_tree_depth_and_node_count() {
+		macro_rules! assert_depth {
+			($($window_size:expr),+) => {
+				$(
+					let sma = SumTreeSMA::<u32, u32, $window_size>::new();
+					let log2 = ($window_size as f64).log2().ceil() as usize;
+					assert_eq!(sma.get_tree_depth(), log2 + 1);
+					assert_eq!(sma.get_tree_node_count(), 2 * (1 << log2) - 1);
+				)+
+			};
+		}
+
+		assert_depth!(1, 2, 3, 7, 8, 10, 64, 100, 1000);
+	}
+
+	#[test]
+	fn into_iter() {
+		macro_rules! assert_into_iter {
+			($sma_type:ident) => {
+				for num_samples in 0..8 {
+					let sma: $sma_type<u32, u32, 5> = (0..num_samples).collect();
+					let samples: Vec<u32> = sma.get_sample_window_iter().cloned().collect();
+
+					let mut into_iter = sma.clone().into_iter();
+					assert_eq!(into_iter.len(), samples.len());
+					assert_eq!(into_iter.next_back(), samples.last().cloned());
+					assert_eq!(sma.into_iter().collect::<Vec<u32>>(), samples);
+				}
+			};
+		}
+
+		assert_into_iter!(NoSumSMA);
+		assert_into_iter!(SingleSumSMA);
+		assert_into_iter!(SumTreeSMA);
+	}
+
+	#[test]
+	fn const_construction() {
+		const SINGLE_SUM_SMA: SingleSumSMA<u32, u32, 8> = SingleSumSMA::from_zero(0);
+		const NO_SUM_SMA: NoSumSMA<u32, u32, 8> = NoSumSMA::from_zero(0);
+
+		let mut single_sum_sma = SINGLE_SUM_SMA;
+		let mut no_sum_sma = NO_SUM_SMA;
+		for sample in [1, 2, 3, 6] {
+			single_sum_sma.add_sample(sample);
+			no_sum_sma.add_sample(sample);
+		}
+		assert_eq!(single_sum_sma.get_average(), 3);
+		assert_eq!(no_sum_sma.get_average(), 3);
+		assert_eq!(SINGLE_SUM_SMA.get_num_samples(), 0);
+	}
+
+	#[test]
+	fn no_sum_average_with_acc() {
+		let mut sma = NoSumSMA::<f32, f32, 4096>::new();
+		assert_eq!(sma.get_average_with_acc::<f64>(), 0.0);
+
+		let samples: Vec<f32> = (0..4096)
+			.map(|idx| 10_000.0 + (idx % 7) as f32 * 0.013)
+			.collect();
+		sma.add_samples(&samples);
+
+		let exact_average =
+			samples.iter().map(|sample| f64::from(*sample)).sum::<f64>() / samples.len() as f64;
+		let naive_average = samples.iter().fold(0.0_f32, |sum, sample| sum + sample) / 4096.0;
+		let widened_average = sma.get_average_with_acc::<f64>();
+
+		let widened_error = (f64::from(widened_average) - exact_average).abs();
+		assert!(widened_error < (f64::from(naive_average) - exact_average).abs());
+		assert_eq!(widened_average, exact_average as f32);
+
+		// The sum of these samples does not fit in a u32
+		let mut sma = NoSumSMA::<u32, u32, 3>::new();
+		sma.add_samples(&[u32::MAX, u32::MAX - 2, u32::MAX - 4]);
+		assert_eq!(sma.get_average_with_acc::<u64>(), u32::MAX - 2);
+	}
+
+	#[test]
+	fn snapshot_and_restore() {
+		let samples = [0.1, 0.7, 0.3, 0.9, 0.4, 0.8, 0.2];
+
+		let mut no_sum_sma = NoSumSMA::<f64, f64, 3>::new();
+		no_sum_sma.add_samples(&samples[..4]);
+		let snapshot = no_sum_sma.snapshot();
+		let average = no_sum_sma.get_average();
+		no_sum_sma.add_samples(&samples[4..]);
+		let mut restored_sma = NoSumSMA::<f64, f64, 3>::new();
+		restored_sma.restore(snapshot.clone());
+		no_sum_sma.restore(snapshot);
+		assert_eq!(no_sum_sma.get_average(), average);
+		assert_eq!(restored_sma, no_sum_sma);
+
+		let mut single_sum_sma = SingleSumSMA::<f64, f64, 3>::new();
+		single_sum_sma.add_samples(&samples[..4]);
+		let snapshot = single_sum_sma.snapshot();
+		let average = single_sum_sma.get_average();
+		single_sum_sma.add_samples(&samples[4..]);
+		let mut restored_sma = SingleSumSMA::<f64, f64, 3>::new();
+		restored_sma.restore(snapshot.clone());
+		single_sum_sma.restore(snapshot);
+		assert_eq!(single_sum_sma.get_average(), average);
+		assert_eq!(restored_sma.get_average(), average);
+		assert_eq!(restored_sma, single_sum_sma);
+
+		let mut sum_tree_sma = SumTreeSMA::<f64, f64, 3>::new();
+		sum_tree_sma.add_samples(&samples[..4]);
+		let snapshot = sum_tree_sma.snapshot();
+		let average = sum_tree_sma.get_average();
+		sum_tree_sma.add_samples(&samples[4..]);
+		let mut restored_sma = SumTreeSMA::<f64, f64, 3>::new();
+		restored_sma.restore(snapshot.clone());
+		sum_tree_sma.restore(snapshot);
+		assert_eq!(sum_tree_sma.get_average(), average);
+		assert_eq!(restored_sma, sum_tree_sma);
+
+		// Restoring continues from the snapshot state
+		sum_tree_sma.add_sample(1.0);
+		assert_eq!(
+			sum_tree_sma.get_sample_window_iter().collect::<Vec<_>>(),
+			[&0.3, &0.9, &1.0]
+		);
+	}
+
+	#[test]
+	fn restore_into_smaller_window() {
+		let mut sma = SingleSumSMA::<u32, u32, 4>::new();
+		sma.add_samples(&[1, 2, 3, 4]);
+		let snapshot = sma.snapshot();
+		assert_eq!(snapshot.get_num_samples(), 4);
+
+		let mut single_sum_sma = SingleSumSMA::<u32, u32, 2>::new();
+		single_sum_sma.add_sample(100);
+		single_sum_sma.restore(snapshot.clone());
+		assert_eq!(single_sum_sma.get_average(), 3);
+
+		let mut sum_tree_sma = SumTreeSMA::<u32, u32, 3>::new();
+		sum_tree_sma.restore(snapshot.clone());
+		assert_eq!(sum_tree_sma.get_average(), 3);
+
+		let mut no_sum_sma = NoSumSMA::<u32, u32, 0>::new();
+		no_sum_sma.restore(snapshot);
+		assert_eq!(no_sum_sma.get_num_samples(), 0);
+	}
+
+	#[test]
+	fn restore_discards_accumulated_rounding_error() {
+		use rand::{distributions::Uniform, rngs::SmallRng, Rng, SeedableRng};
+
+		let mut sma = SingleSumSMA::<f64, f64, 5>::new();
+		sma.add_samples(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+		let snapshot = sma.snapshot();
+
+		// The snapshot holds more samples than fit, so their sum is recalculated when restoring
+		let mut restored_sma = SingleSumSMA::<f64, f64, 3>::new();
+		for _ in 0..100 {
+			restored_sma.add_samples(
+				&SmallRng::seed_from_u64(0xCAFEBABE)
+					.sample_iter(&Uniform::from(-1e6..1e6))
+					.take(1000)
+					.collect::<Vec<f64>>(),
+			);
+			restored_sma.restore(snapshot.clone());
+			assert_eq!(restored_sma.get_average(), 4.0);
+		}
+	}
+
+	#[test]
+	fn add_sample_opt() {
+		let samples = [
+			None,
+			Some(4),
+			None,
+			Some(8),
+			None,
+			None,
+			Some(3),
+			Some(7),
+			None,
+		];
+
+		for sma in &mut get_sma_impls!(u32, 3, new) {
+			sma.add_sample_opt(samples[0]);
+			assert!(sma.is_empty());
+
+			for sample in samples {
+				sma.add_sample_opt(sample);
+			}
+			assert_eq!(sma.get_num_samples(), 3);
+			assert_eq!(sma.get_average(), (8 + 3 + 7) / 3);
+			assert_eq!(sma.get_most_recent_sample(), Some(7));
+		}
+	}
+
+	#[test]
+	fn average_as() {
+		macro_rules! assert_average_as {
+			($sma_type:ident) => {
+				let mut sma = $sma_type::<u32, u32, 3>::new();
+				assert_eq!(sma.get_average_as::<f64>(), 0.0);
+
+				sma.add_samples(&[1, 2]);
+				assert_eq!(sma.get_average(), 1);
+				assert_eq!(sma.get_average_as::<f64>(), 1.5);
+
+				sma.add_samples(&[6, 3]);
+				assert_eq!(sma.get_average_as::<f64>(), 11.0 / 3.0);
+				assert_eq!(sma.get_average_as::<u64>(), 3);
+			};
+		}
+
+		assert_average_as!(NoSumSMA);
+		assert_average_as!(SingleSumSMA);
+		assert_average_as!(SumTreeSMA);
+
+		let mut sma = KahanSumSMA::<f32, f32, 2>::new();
+		sma.add_samples(&[0.25, 0.5]);
+		assert_eq!(sma.get_average_as::<f64>(), 0.375);
+	}
+
+	#[test]
+	fn no_sum_drain() {
+		let mut sma = NoSumSMA::<f32, f32, 4>::new();
+		assert_eq!(sma.drain(), (0.0, vec![]));
+
+		sma.add_samples(&[0.1, 0.7, 0.3, 0.9, 0.4, 0.8]);
+		let average = sma.get_average();
+		assert_eq!(sma.drain(), (average, vec![0.3, 0.9, 0.4, 0.8]));
+		assert!(sma.is_empty());
+		assert_eq!(sma.get_average(), 0.0);
+
+		sma.add_sample(2.0);
+		assert_eq!(sma.drain(), (2.0, vec![2.0]));
+		assert_eq!(sma.get_num_samples(), 0);
+	}
+
+	#[test]
+	fn new_nonzero() {
+		let mut no_sum_sma = NoSumSMA::<u32, u32, 2>::new_nonzero();
+		let mut single_sum_sma = SingleSumSMA::<u32, u32, 2>::new_nonzero();
+		let mut sum_tree_sma = SumTreeSMA::<u32, u32, 2>::new_nonzero();
+
+		no_sum_sma.add_samples(&[1, 2, 3]);
+		single_sum_sma.add_samples(&[1, 2, 3]);
+		sum_tree_sma.add_samples(&[1, 2, 3]);
+		assert_eq!(no_sum_sma.get_average(), 2);
+		assert_eq!(single_sum_sma.get_average(), 2);
+		assert_eq!(sum_tree_sma.get_average(), 2);
+	}
+
+	#[test]
+	fn merge_window() {
+		macro_rules! assert_merge_window {
+			($sma_type:ident) => {
+				let samples = [0.5, 1.25, 3.0, 2.5, 4.75, 0.25, 1.0];
+
+				let mut a = $sma_type::<f64, f64, 6>::new();
+				let mut b = $sma_type::<f64, f64, 6>::new();
+				a.add_samples(&samples[..3]);
+				b.add_samples(&samples[3..]);
+				a.merge_window(&b);
+
+				let mut sequential = $sma_type::<f64, f64, 6>::new();
+				sequential.add_samples(&samples);
+				assert_eq!(a, sequential);
+				assert_eq!(a.get_average(), sequential.get_average());
+				assert_eq!(a.get_num_samples(), 6);
+				assert_eq!(b.get_num_samples(), 4);
+			};
+		}
+
+		assert_merge_window!(NoSumSMA);
+		assert_merge_window!(SingleSumSMA);
+		assert_merge_window!(SumTreeSMA);
+	}
+
+	#[test]
+	fn memory_footprint() {
+		use std::mem::size_of;
+
+		let sma = SumTreeSMA::<f32, f32, 8>::new();
+		assert_eq!(sma.get_tree_node_count(), 15);
+		// The sum tree also allocates a node that is not part of the tree, to simplify indexing
+		assert_eq!(
+			sma.get_memory_footprint(),
+			size_of::<SumTreeSMA<f32, f32, 8>>()
+				+ (sma.get_tree_node_count() + 1) * size_of::<f32>()
+		);
+
+		let sma = NoSumSMA::<f64, f64, 8>::new();
+		assert!(sma.get_memory_footprint() >= 8 * size_of::<f64>());
+		assert_eq!(
+			sma.get_memory_footprint(),
+			size_of::<NoSumSMA<f64, f64, 8>>()
+		);
+
+		let sma = SingleSumSMA::<u16, u16, 8>::new();
+		assert!(sma.get_memory_footprint() >= 9 * size_of::<u16>());
+	}
+
+	#[test]
+	fn rounded_average() {
+		use crate::RoundingMode::*;
+
+		macro_rules! assert_rounded_average {
+			($sma_type:ident) => {
+				let mut sma = $sma_type::<i32, i32, 3>::new();
+				assert_eq!(sma.get_rounded_average(HalfUp), 0);
+
+				sma.add_samples(&[1, 2]);
+				assert_eq!(sma.get_rounded_average(Truncate), 1);
+				assert_eq!(sma.get_rounded_average(HalfUp), 2);
+				assert_eq!(sma.get_rounded_average(HalfToEven), 2);
+
+				sma.add_sample(2);
+				assert_eq!(sma.get_rounded_average(Truncate), 1);
+				assert_eq!(sma.get_rounded_average(HalfUp), 2);
+				assert_eq!(sma.get_rounded_average(HalfToEven), 2);
+
+				let mut sma = $sma_type::<i32, i32, 2>::new();
+				sma.add_samples(&[-1, -2]);
+				assert_eq!(sma.get_rounded_average(Truncate), -1);
+				assert_eq!(sma.get_rounded_average(HalfUp), -1);
+				assert_eq!(sma.get_rounded_average(HalfToEven), -2);
+
+				sma.add_sample(-3);
+				assert_eq!(sma.get_rounded_average(Truncate), -2);
+				assert_eq!(sma.get_rounded_average(HalfUp), -2);
+				assert_eq!(sma.get_rounded_average(HalfToEven), -2);
+			};
+		}
+
+		assert_rounded_average!(NoSumSMA);
+		assert_rounded_average!(SingleSumSMA);
+		assert_rounded_average!(SumTreeSMA);
+	}
+
+	#[test]
+	fn retain() {
+		macro_rules! assert_retain {
+			($sma_type:ident) => {
+				let mut sma = $sma_type::<u32, u32, 3>::new();
+				sma.add_samples(&[7, 1000, 5]);
+				assert_eq!(sma.get_average(), 337);
+
+				sma.retain(|sample| *sample < 100);
+				assert_eq!(sma.get_num_samples(), 2);
+				assert_eq!(sma.get_average(), 6);
+				assert!(!sma.is_full());
+				assert_eq!(
+					sma.get_sample_window_iter().collect::<Vec<&u32>>(),
+					vec![&7, &5]
+				);
+
+				sma.add_sample(9);
+				assert!(sma.is_full());
+				assert_eq!(sma.get_average(), 7);
+
+				sma.add_sample(1);
+				assert_eq!(sma.get_average(), 5);
+
+				// The sample window wraps around the end of its storage
+				let mut sma = $sma_type::<u32, u32, 5>::new();
+				sma.add_samples(&[1, 2, 3, 4, 5, 6, 7, 8]);
+				sma.retain(|sample| sample % 2 == 0);
+				assert_eq!(
+					sma.get_sample_window_iter().collect::<Vec<&u32>>(),
+					vec![&4, &6, &8]
+				);
+				assert_eq!(sma.get_average(), 6);
+
+				sma.add_samples(&[10, 12, 14]);
+				assert_eq!(
+					sma.get_sample_window_iter().collect::<Vec<&u32>>(),
+					vec![&6, &8, &10, &12, &14]
+				);
+				assert_eq!(sma.get_average(), 10);
+
+				sma.retain(|_| false);
+				assert!(sma.is_empty());
+				assert_eq!(sma.get_average(), 0);
+			};
+		}
+
+		assert_retain!(NoSumSMA);
+		assert_retain!(SingleSumSMA);
+		assert_retain!(SumTreeSMA);
+	}
+
+	#[test]
+	fn average_of_last() {
+		for sma in &mut get_sma_impls!(u32, 5, new) {
+			assert_eq!(sma.get_average_of_last(2), None);
+
+			sma.add_samples(&[100, 6, 2, 8, 4, 10]);
+			assert_eq!(sma.get_average_of_last(0), None);
+			assert_eq!(sma.get_average_of_last(1), Some(10));
+			assert_eq!(sma.get_average_of_last(2), Some(7));
+			assert_eq!(sma.get_average_of_last(3), Some(7));
+			assert_eq!(sma.get_average_of_last(5), Some(6));
+			assert_eq!(sma.get_average_of_last(6), Some(6));
+			assert_eq!(sma.get_average_of_last(usize::MAX), Some(6));
+		}
+
+		for sma in &mut get_sma_impls!(u32, 5, new) {
+			sma.add_samples(&[3, 5]);
+			assert_eq!(sma.get_average_of_last(1), Some(5));
+			assert_eq!(sma.get_average_of_last(2), Some(4));
+			assert_eq!(sma.get_average_of_last(4), Some(4));
+		}
+	}
+
+	#[test]
+	fn average_of_last_wrapping() {
+		// Cover every position of the most recent sample within the wrapping sample window
+		let samples: Vec<u32> = (1..=20).map(|sample| sample * 7 % 11).collect();
+		for sma in &mut get_sma_impls!(u32, 6, new) {
+			for (idx, sample) in samples.iter().enumerate() {
+				sma.add_sample(*sample);
+
+				let window = &samples[(idx + 1).saturating_sub(6)..=idx];
+				for num_samples in 1..=window.len() {
+					let last = &window[window.len() - num_samples..];
+					assert_eq!(
+						sma.get_average_of_last(num_samples),
+						Some(last.iter().sum::<u32>() / num_samples as u32)
+					);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn from_fn() {
+		macro_rules! assert_from_fn {
+			($sma_type:ident) => {
+				let sma = $sma_type::<f32, f32, 8>::from_fn(|idx| idx as f32);
+				assert!(sma.is_full());
+				assert_eq!(sma.get_average(), 3.5);
+				assert_eq!(sma.get_oldest_sample(), Some(0.0));
+				assert_eq!(sma.get_most_recent_sample(), Some(7.0));
+
+				let sma = $sma_type::<u32, u32, 0>::from_fn(|_| unreachable!());
+				assert!(sma.is_empty());
+			};
+		}
+
+		assert_from_fn!(NoSumSMA);
+		assert_from_fn!(SingleSumSMA);
+		assert_from_fn!(SumTreeSMA);
+	}
+
+	#[test]
+	fn for_each_sample() {
+		let mut sma = SingleSumSMA::<u32, u32, 3>::new();
+		let mut samples = Vec::new();
+		sma.for_each_sample(|sample| samples.push(*sample));
+		assert!(samples.is_empty());
+
+		for sample in 1..=5 {
+			sma.add_sample(sample);
+		}
+		sma.for_each_sample(|sample| samples.push(*sample));
+		assert_eq!(samples, vec![3, 4, 5]);
+	}
+
+	#[test]
+	fn peek_would_evict() {
+		for sma in &mut get_sma_impls!(u32, 3, new) {
+			assert_eq!(sma.peek_would_evict(), None);
+
+			sma.add_sample(1);
+			sma.add_sample(2);
+			assert_eq!(sma.get_oldest_sample(), Some(1));
+			assert_eq!(sma.peek_would_evict(), None);
+
+			sma.add_sample(3);
+			assert_eq!(sma.peek_would_evict(), Some(1));
+
+			sma.add_sample(4);
+			assert_eq!(sma.peek_would_evict(), Some(2));
+		}
+
+		for sma in &mut get_sma_impls!(u32, 0, new) {
+			sma.add_sample(1);
+			assert_eq!(sma.peek_would_evict(), None);
+		}
+	}
+
+	#[test]
+	fn default_get_oldest_sample() {
+		// An implementation that, like those written before get_oldest_sample existed, does not
+		// provide it
+		struct Wrapper(SumTreeSMA<u32, u32, 3>);
+
+		impl SMA<u32, u32, 3> for Wrapper {
+			fn add_sample(&mut self, new_sample: u32) {
+				self.0.add_sample(new_sample);
+			}
+			fn get_average(&self) -> u32 {
+				self.0.get_average()
+			}
+			fn get_most_recent_sample(&self) -> Option<u32> {
+				self.0.get_most_recent_sample()
+			}
+			fn get_num_samples(&self) -> usize {
+				self.0.get_num_samples()
+			}
+			fn get_sample_window_size(&self) -> usize {
+				self.0.get_sample_window_size()
+			}
+			fn get_sample_window_iter(&self) -> crate::Iter<'_, u32, 3> {
+				self.0.get_sample_window_iter()
+			}
+		}
+
+		let mut sma = Wrapper(SumTreeSMA::new());
+		assert_eq!(sma.get_oldest_sample(), None);
+
+		for sample in 1..=5 {
+			sma.add_sample(sample);
+			assert_eq!(sma.get_oldest_sample(), sma.0.get_oldest_sample());
+		}
+		assert_eq!(sma.get_oldest_sample(), Some(3));
+	}
+
+	#[test]
+	fn builder() {
+		let builder = MovingAverageBuilder::new().window::<3>().divisor::<u32>();
+
+		let mut no_sum_sma = builder.no_sum();
+		let mut single_sum_sma = builder.single_sum();
+		let mut sum_tree_sma = builder.sum_tree();
+		for sample in [1, 2, 3, 6] {
+			no_sum_sma.add_sample(sample);
+			single_sum_sma.add_sample(sample);
+			sum_tree_sma.add_sample(sample);
+		}
+
+		assert_eq!(no_sum_sma.get_average(), 3);
+		assert_eq!(single_sum_sma.get_average(), 3);
+		assert_eq!(sum_tree_sma.get_average(), 3);
+		assert_eq!(sum_tree_sma.get_sample_window_size(), 3);
+
+		let mut sma = MovingAverageBuilder::new()
+			.divisor::<f32>()
+			.window::<2>()
+			.single_sum::<f32>();
+		sma.add_sample(1.0);
+		sma.add_sample(2.0);
+		assert_eq!(sma.get_average(), 1.5);
+	}
+
+	#[test]
+	fn builder_from_zero() {
+		use std::time::Duration;
+
+		let builder = MovingAverageBuilder::new().window::<3>().divisor::<u32>();
+
+		let mut no_sum_sma = builder.no_sum_from_zero(Duration::ZERO);
+		let mut single_sum_sma = builder.single_sum_from_zero(Duration::ZERO);
+		let mut sum_tree_sma = builder.sum_tree_from_zero(Duration::ZERO);
+		for millis in [1, 2, 3, 6] {
+			no_sum_sma.add_sample(Duration::from_millis(millis));
+			single_sum_sma.add_sample(Duration::from_millis(millis));
+			sum_tree_sma.add_sample(Duration::from_millis(millis));
+		}
+
+		assert_eq!(no_sum_sma.get_average(), Duration::from_millis(11) / 3);
+		assert_eq!(single_sum_sma.get_average(), Duration::from_millis(11) / 3);
+		assert_eq!(sum_tree_sma.get_average(), Duration::from_millis(11) / 3);
+		assert_eq!(sum_tree_sma.get_sample_window_size(), 3);
+	}
+
+	#[test]
+	fn add_assign() {
+		macro_rules! assert_add_assign {
+			($sma_type:ident) => {
+				let mut sma = $sma_type::<u32, u32, 3>::new();
+				let mut reference_sma = $sma_type::<u32, u32, 3>::new();
+				for sample in [4, 8, 3, 7, 11] {
+					sma += sample;
+					reference_sma.add_sample(sample);
+				}
+				assert_eq!(sma, reference_sma);
+				assert_eq!(sma.get_average(), 7);
+			};
+		}
+
+		assert_add_assign!(NoSumSMA);
+		assert_add_assign!(SingleSumSMA);
+		assert_add_assign!(SumTreeSMA);
+	}
+
+	#[test]
+	fn window_size_const() {
+		let window: [u32; NoSumSMA::<u32, u32, 4>::WINDOW_SIZE] = [0; 4];
+		assert_eq!(window.len(), 4);
+		assert_eq!(SingleSumSMA::<u32, u32, 5>::WINDOW_SIZE, 5);
+		assert_eq!(SumTreeSMA::<u32, u32, 6>::WINDOW_SIZE, 6);
+	}
+
+	#[test]
+	fn checked_add_sample() {
+		macro_rules! assert_checked_add_sample {
+			($sma_type:ident) => {
+				let mut sma = $sma_type::<u8, u8, 3>::new();
+				assert_eq!(sma.checked_add_sample(100), Ok(None));
+				assert_eq!(sma.checked_add_sample(100), Ok(None));
+				assert_eq!(sma.checked_add_sample(55), Ok(None));
+				assert_eq!(sma.get_average(), 85);
+
+				// The resulting sum would be 256
+				let error = sma.checked_add_sample(101).unwrap_err();
+				assert_eq!(error.sample_type_name(), "u8");
+				let samples: Vec<u8> = sma.get_sample_window_iter().cloned().collect();
+				assert_eq!(samples, vec![100, 100, 55]);
+				assert_eq!(sma.get_average(), 85);
+
+				// The resulting sum is 255
+				assert_eq!(sma.checked_add_sample(100), Ok(Some(100)));
+				assert_eq!(sma.get_average(), 85);
+				assert_eq!(sma.get_most_recent_sample(), Some(100));
+				assert_eq!(sma.checked_add_sample(0), Ok(Some(100)));
+				assert_eq!(sma.get_average(), 51);
+			};
+		}
+
+		assert_checked_add_sample!(SingleSumSMA);
+		assert_checked_add_sample!(SumTreeSMA);
+	}
+
+	#[test]
+	fn reset_to() {
+		macro_rules! assert_reset_to {
+			($sma_type:ident) => {
+				let mut sma = $sma_type::<f64, f64, 4>::new();
+				sma.add_samples(&[0.1, 0.7, 0.3, 0.9, 0.4, 0.8, 0.2]);
+
+				sma.reset_to(2.5);
+				assert_eq!(sma.get_num_samples(), 4);
+				assert!(sma.is_full());
+				assert_eq!(sma.get_average(), 2.5);
+				assert_eq!(sma.peek_would_evict(), Some(2.5));
+
+				sma.add_sample(6.5);
+				assert_eq!(sma.get_average(), 3.5);
+			};
+		}
+
+		assert_reset_to!(NoSumSMA);
+		assert_reset_to!(SingleSumSMA);
+		assert_reset_to!(SumTreeSMA);
+	}
+
+	#[test]
+	fn add_and_get_average() {
+		let samples: Vec<f64> = (0..40).map(|idx| f64::from(idx).sin() * 10.0).collect();
+		let mut reference_smas = get_sma_impls!(f64, 7, new);
+		for (sma, reference_sma) in get_sma_impls!(f64, 7, new)
+			.iter_mut()
+			.zip(reference_smas.iter_mut())
+		{
+			for sample in &samples {
+				reference_sma.add_sample(*sample);
+				assert_eq!(
+					sma.add_and_get_average(*sample),
+					reference_sma.get_average()
+				);
+				assert_eq!(sma.get_average(), reference_sma.get_average());
+			}
+		}
+
+		for sma in &mut get_sma_impls!(u32, 0, new) {
+			assert_eq!(sma.add_and_get_average(5), 0);
+		}
+	}
+
+	#[test]
+	fn no_sum_add_and_get_average() {
+		use rand::{distributions::Uniform, rngs::SmallRng, Rng, SeedableRng};
+
+		let samples: Vec<f32> = SmallRng::seed_from_u64(0xC0FFEE)
+			.sample_iter(&Uniform::from(-1000.0..1000.0))
+			.take(1000)
+			.collect();
+
+		let mut sma = NoSumSMA::<_, f32, 100>::new();
+		let mut reference_sma = NoSumSMA::<_, f32, 100>::new();
+		for sample in samples {
+			reference_sma.add_sample(sample);
+			assert_eq!(sma.add_and_get_average(sample), reference_sma.get_average());
+			assert_eq!(sma.get_generation(), reference_sma.get_generation());
+		}
+		assert!(sma == reference_sma);
+	}
+
+	#[test]
+	fn scan_averages() {
+		let samples = [4, 8, 3, 7, 11, 0];
+		for sma in &mut get_sma_impls!(u32, 3, new) {
+			assert_eq!(sma.scan_averages(&samples), vec![4, 6, 5, 6, 7, 6]);
+			assert_eq!(sma.get_average(), 6);
+			assert!(sma.scan_averages(&[]).is_empty());
+		}
+
+		for (sma, mut reference_sma) in get_sma_impls!(f64, 4, new)
+			.iter_mut()
+			.zip(get_sma_impls!(f64, 4, new))
+		{
+			let samples: Vec<f64> = (0..20).map(|idx| f64::from(idx).cos()).collect();
+			let averages: Vec<f64> = samples
+				.iter()
+				.map(|sample| {
+					reference_sma.add_sample(*sample);
+					reference_sma.get_average()
+				})
+				.collect();
+			assert_eq!(sma.scan_averages(&samples), averages);
+		}
+	}
+
+	#[test]
+	fn into_inner() {
+		macro_rules! assert_into_inner {
+			($sma_type:ident) => {
+				let mut sma = $sma_type::<u32, u32, 3>::new();
+				sma.add_samples(&[1, 2]);
+				assert_eq!(sma.clone().into_inner(), vec![1, 2]);
+
+				sma.add_samples(&[3, 4, 5, 6, 7]);
+				assert_eq!(sma.clone().into_inner(), vec![5, 6, 7]);
+				sma.add_sample(8);
+				assert_eq!(sma.into_inner(), vec![6, 7, 8]);
+
+				assert!($sma_type::<u32, u32, 0>::new().into_inner().is_empty());
+			};
+		}
+
+		assert_into_inner!(NoSumSMA);
+		assert_into_inner!(SingleSumSMA);
+		assert_into_inner!(SumTreeSMA);
+	}
+
+	#[test]
+	fn shift_out() {
+		macro_rules! assert_shift_out {
+			($sma_type:ident) => {
+				let mut sma = $sma_type::<u32, u32, 4>::new();
+				sma.add_samples(&[1, 2, 3, 4, 5, 6]);
+				assert_eq!(sma.get_average(), 4);
+
+				assert_eq!(sma.shift_out(), Some(3));
+				assert_eq!(sma.get_num_samples(), 3);
+				assert_eq!(sma.get_oldest_sample(), Some(4));
+				assert_eq!(sma.get_average(), 5);
+				assert!(sma.get_sample_window_iter().eq(&[4, 5, 6]));
+
+				// The sample window fills up again, and the oldest sample is evicted once it's full
+				sma.add_samples(&[7, 8]);
+				assert_eq!(sma.get_average(), 6);
+				assert!(sma.get_sample_window_iter().eq(&[5, 6, 7, 8]));
+				assert_eq!(sma.clone().into_inner(), vec![5, 6, 7, 8]);
+
+				assert_eq!(sma.shift_out(), Some(5));
+				assert_eq!(sma.shift_out(), Some(6));
+				assert_eq!(sma.clone().into_inner(), vec![7, 8]);
+				assert_eq!(sma.shift_out(), Some(7));
+				assert_eq!(sma.shift_out(), Some(8));
+				assert_eq!(sma.shift_out(), None);
+				assert!(sma.is_empty());
+				assert_eq!(sma.get_average(), 0);
+
+				sma.add_sample(9);
+				assert_eq!(sma.get_average(), 9);
+
+				assert_eq!($sma_type::<u32, u32, 0>::new().shift_out(), None);
+			};
+		}
+
+		assert_shift_out!(NoSumSMA);
+		assert_shift_out!(SingleSumSMA);
+		assert_shift_out!(SumTreeSMA);
+	}
+
+	#[test]
+	fn generation() {
+		macro_rules! assert_generation {
+			($sma_type:ident) => {
+				let mut sma = $sma_type::<f64, f64, 3>::new();
+				assert_eq!(sma.get_generation(), 0);
+
+				sma.add_sample(1.0);
+				sma.add_sample(2.0);
+				let generation = sma.get_generation();
+				assert!(0 < generation);
+
+				// Reading leaves the generation unchanged
+				sma.get_average();
+				sma.get_variance();
+				sma.get_sample_window_iter().count();
+				assert_eq!(sma.clone().get_generation(), generation);
+				assert_eq!(sma.get_generation(), generation);
+
+				let mut last_generation = generation;
+				let mut assert_advanced = |sma: &$sma_type<f64, f64, 3>| {
+					assert!(last_generation < sma.get_generation());
+					last_generation = sma.get_generation();
+				};
+
+				sma.add_samples(&[3.0, 4.0]);
+				assert_advanced(&sma);
+				sma.shift_out();
+				assert_advanced(&sma);
+				sma.retain(|sample| *sample < 4.0);
+				assert_advanced(&sma);
+				sma.restore(sma.snapshot());
+				assert_advanced(&sma);
+				sma.reset_to(5.0);
+				assert_advanced(&sma);
+
+				// The generation is not part of the state that is compared
+				let mut other_sma = $sma_type::<f64, f64, 3>::new();
+				other_sma.add_samples(&[5.0, 5.0, 5.0]);
+				assert_eq!(sma, other_sma);
+
+				// A zero-sized sample window is never modified
+				let mut sma = $sma_type::<f64, f64, 0>::new();
+				sma.add_sample(1.0);
+				assert_eq!(sma.get_generation(), 0);
+			};
+		}
+
+		assert_generation!(NoSumSMA);
+		assert_generation!(SingleSumSMA);
+		assert_generation!(SumTreeSMA);
+	}
+
+	#[test]
+	fn average_if_ready() {
+		macro_rules! assert_average_if_ready {
+			($sma_type:ident) => {
+				let sma = $sma_type::<u32, u32, 5>::new();
+				assert_eq!(sma.get_min_samples(), 0);
+				assert_eq!(sma.get_average_if_ready(), Some(0));
+
+				let mut sma = $sma_type::<u32, u32, 5>::new().with_min_samples(3);
+				assert_eq!(sma.get_min_samples(), 3);
+				assert_eq!(sma.get_average_if_ready(), None);
+				sma.add_sample(2);
+				assert_eq!(sma.get_average_if_ready(), None);
+				sma.add_sample(4);
+				assert_eq!(sma.get_average_if_ready(), None);
+				sma.add_sample(6);
+				assert_eq!(sma.get_average_if_ready(), Some(4));
+				sma.add_samples(&[8, 10, 12]);
+				assert_eq!(sma.get_average_if_ready(), Some(8));
+
+				sma.shift_out();
+				sma.shift_out();
+				assert_eq!(sma.get_average_if_ready(), Some(10));
+				sma.shift_out();
+				assert_eq!(sma.get_average_if_ready(), None);
+
+				let mut sma = $sma_type::<u32, u32, 2>::new().with_min_samples(2);
+				sma.add_sample(1);
+				assert_eq!(sma.get_average_if_ready(), None);
+				assert_eq!(sma.get_average_when_full(), None);
+				sma.add_sample(3);
+				assert_eq!(sma.get_average_if_ready(), Some(2));
+			};
+		}
+
+		assert_average_if_ready!(NoSumSMA);
+		assert_average_if_ready!(SingleSumSMA);
+		assert_average_if_ready!(SumTreeSMA);
+	}
+
+	#[test]
+	#[should_panic(expected = "min_samples must not exceed WINDOW_SIZE")]
+	fn min_samples_exceeding_window_size() {
+		SingleSumSMA::<u32, u32, 3>::new().with_min_samples(4);
+	}
+
+	#[test]
+	#[cfg(feature = "serde")]
+	fn sum_tree_serde() {
+		let mut sma = SumTreeSMA::<u64, u64, 12>::new();
+		sma.add_samples(&(1000..1020).collect::<Vec<u64>>());
+
+		let serialized = serde_json::to_string(&sma).unwrap();
+		assert_eq!(
+			serialized,
+			format!("{:?}", (1008..1020).collect::<Vec<u64>>()).replace(' ', "")
+		);
+
+		// Storing all nodes of the sum tree, i.e. the leaf nodes and the sums of their subtrees
+		let mut level: Vec<u64> = sma.get_sample_window_iter().cloned().collect();
+		level.resize(12_usize.next_power_of_two(), 0);
+		let mut nodes = level.clone();
+		while 1 < level.len() {
+			level = level.chunks(2).map(|pair| pair[0] + pair[1]).collect();
+			nodes.extend(&level);
+		}
+		assert_eq!(nodes.len(), sma.get_tree_node_count());
+		assert!(serialized.len() * 2 < serde_json::to_string(&nodes).unwrap().len());
+
+		let deserialized: SumTreeSMA<u64, u64, 12> = serde_json::from_str(&serialized).unwrap();
+		assert_eq!(deserialized, sma);
+		assert_eq!(deserialized.get_average(), sma.get_average());
+		assert_eq!(
+			deserialized.get_average_of_last(5),
+			sma.get_average_of_last(5)
+		);
+
+		let mut partial_sma = SumTreeSMA::<f64, f64, 4>::new();
+		partial_sma.add_samples(&[1.5, 2.5]);
+		let round_tripped: SumTreeSMA<f64, f64, 4> =
+			serde_json::from_str(&serde_json::to_string(&partial_sma).unwrap()).unwrap();
+		assert_eq!(round_tripped, partial_sma);
+		assert_eq!(round_tripped.get_average(), 2.0);
+
+		let error = serde_json::from_str::<SumTreeSMA<u64, u64, 2>>("[1,2,3]").unwrap_err();
+		assert!(error.to_string().contains("at most 2 samples"));
+	}
+
+	#[test]
+	fn fill_state() {
+		for sma in &mut get_sma_impls!(u32, 2, new) {
+			assert!(sma.is_empty());
+			assert!(!sma.is_full());
+			assert_eq!(sma.fill_ratio(), 0.0);
+
+			sma.add_sample(1);
+			assert!(!sma.is_empty());
+			assert!(!sma.is_full());
+			assert_eq!(sma.fill_ratio(), 0.5);
+
+			sma.add_sample(2);
+			assert!(!sma.is_empty());
+			assert!(sma.is_full());
+			assert_eq!(sma.fill_ratio(), 1.0);
+
+			sma.add_sample(3);
+			assert!(sma.is_full());
+			assert_eq!(sma.fill_ratio(), 1.0);
+		}
+	}
+
+	#[test]
+	fn fill_state_zero_sized() {
+		for sma in &mut get_sma_impls!(u32, 0, new) {
+			assert!(sma.is_empty());
+			assert!(sma.is_full());
+			assert_eq!(sma.fill_ratio(), 1.0);
+
+			sma.add_sample(1);
+			assert!(sma.is_empty());
+			assert!(sma.is_full());
+			assert_eq!(sma.fill_ratio(), 1.0);
+		}
+	}
+
+	#[test]
+	fn average_when_full() {
+		for sma in &mut get_sma_impls!(u32, 3, new) {
+			assert_eq!(sma.get_average_when_full(), None);
+
+			sma.add_sample(3);
+			assert_eq!(sma.get_average_when_full(), None);
+
+			sma.add_sample(6);
+			assert_eq!(sma.get_average_when_full(), None);
+
+			sma.add_sample(9);
+			assert_eq!(sma.get_average_when_full(), Some(6));
+
+			sma.add_sample(12);
+			assert_eq!(sma.get_average_when_full(), Some(9));
+		}
+
+		for sma in &mut get_sma_impls!(u32, 0, new) {
+			assert_eq!(sma.get_average_when_full(), None);
+
+			sma.add_sample(3);
+			assert_eq!(sma.get_average_when_full(), None);
+		}
+	}
+
+	#[test]
+	fn variance_and_std_dev() {
+		for sma in &mut get_sma_impls!(f64, 8, new) {
+			assert_eq!(sma.get_variance(), 0.0);
+			assert_eq!(sma.get_std_dev(), 0.0);
+
+			for sample in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+				sma.add_sample(sample);
+			}
+			assert_eq!(sma.get_average(), 5.0);
+			assert_eq!(sma.get_variance(), 4.0);
+			assert_eq!(sma.get_std_dev(), 2.0);
+
+			// Window is now [4, 4, 4, 5, 5, 7, 9, 2]
+			sma.add_sample(2.0);
+			assert_eq!(sma.get_average(), 5.0);
+			assert_eq!(sma.get_variance(), 4.0);
+
+			// Window is now [4, 4, 5, 5, 7, 9, 2, 12]
+			sma.add_sample(12.0);
+			assert_eq!(sma.get_average(), 6.0);
+			assert_eq!(sma.get_variance(), 9.0);
+			assert_eq!(sma.get_std_dev(), 3.0);
+		}
+	}
+
+	#[test]
+	fn rms() {
+		for sma in &mut get_sma_impls!(f64, 12, new) {
+			assert_eq!(sma.get_rms(), 0.0);
+
+			// Over a whole number of periods, the RMS of a sine wave is its amplitude divided by
+			// sqrt(2), regardless of its phase
+			for idx in 0..30 {
+				sma.add_sample(3.0 * (std::f64::consts::PI * (idx as f64 / 6.0 + 0.1)).sin());
+				if 12 <= idx + 1 {
+					assert!((sma.get_rms() - 3.0 / 2.0_f64.sqrt()).abs() < 1e-12);
+				}
+			}
+
+			sma.add_samples(&[-2.0; 12]);
+			assert_eq!(sma.get_rms(), 2.0);
+		}
+	}
+
+	#[test]
+	fn i32_variance() {
+		for sma in &mut get_sma_impls!(i32, 3, new) {
+			sma.add_sample(1);
+			sma.add_sample(3);
+			sma.add_sample(5);
+			// Squared deviations from 3 are [4, 0, 4], so the variance is 8 / 3 = 2
+			assert_eq!(sma.get_variance(), 2);
+		}
+	}
+
+	#[test]
+	fn u32_variance() {
+		for sma in &mut get_sma_impls!(u32, 4, new) {
+			assert_eq!(sma.get_variance(), 0);
+
+			sma.add_samples(&[1, 3, 5, 8]);
+			// Squared deviations from 4 are [9, 1, 1, 16], so the variance is 27 / 4 = 6
+			assert_eq!(sma.get_average(), 4);
+			assert_eq!(sma.get_variance(), 6);
+
+			sma.add_samples(&[7; 4]);
+			assert_eq!(sma.get_variance(), 0);
+		}
+	}
+
+	#[test]
+	fn saturating_sum_u32_samples() {
+		let mut sma = SaturatingSumSMA::<_, u32, 3>::new();
+
+		sma.add_sample(u32::MAX - 2);
+		sma.add_sample(2);
+		assert!(!sma.is_saturated());
+		assert_eq!(sma.get_average(), u32::MAX / 2);
+
+		// The sum no longer fits in a u32, so it is clamped to u32::MAX
+		sma.add_sample(u32::MAX);
+		assert!(sma.is_saturated());
+		assert_eq!(sma.get_average(), u32::MAX / 3);
+
+		sma.add_sample(u32::MAX);
+		assert!(sma.is_saturated());
+		assert_eq!(sma.get_average(), u32::MAX / 3);
+
+		// Once the saturating samples have left the window, the sum is exact again
+		sma.add_sample(6);
+		assert!(sma.is_saturated());
+		sma.add_sample(9);
+		assert!(sma.is_saturated());
+		sma.add_sample(12);
+		assert!(!sma.is_saturated());
+		assert_eq!(sma.get_average(), 9);
+	}
+
+	#[test]
+	fn saturating_sum_i8_samples() {
+		let mut sma = SaturatingSumSMA::<_, i8, 2>::new();
+
+		sma.add_sample(-100);
+		sma.add_sample(-100);
+		assert!(sma.is_saturated());
+		assert_eq!(sma.get_average(), -64);
+
+		sma.add_sample(100);
+		assert!(!sma.is_saturated());
+		assert_eq!(sma.get_average(), 0);
+	}
+
+	#[test]
+	fn saturating_sum_i8_mixed_sign_samples() {
+		let mut sma = SaturatingSumSMA::<_, i8, 3>::new();
+
+		// Summing the samples in order would clip at 100 + 100, even though the sum fits in an i8
+		sma.add_sample(-100);
+		sma.add_sample(100);
+		sma.add_sample(100);
+		sma.add_sample(-100);
+		assert!(!sma.is_saturated());
+		assert_eq!(sma.get_average(), 33);
+
+		// [100, -100, 120] sums to 120, which fits, although 100 + 120 does not
+		sma.add_sample(120);
+		assert!(!sma.is_saturated());
+		assert_eq!(sma.get_average(), 40);
+
+		// [-100, 120, 120] sums to 140, which is clamped to 127
+		sma.add_sample(120);
+		assert!(sma.is_saturated());
+		assert_eq!(sma.get_average(), 42);
+
+		// [120, 120, -128] sums to 112
+		sma.add_sample(-128);
+		assert!(!sma.is_saturated());
+		assert_eq!(sma.get_average(), 37);
+	}
+
+	#[test]
+	fn widening_sum_u32_samples() {
+		let mut sma = WideningSumSMA::<u32, u64, u64, 1000>::new();
+		for _ in 0..1500 {
+			sma.add_sample(u32::MAX);
+		}
+		assert_eq!(sma.get_average(), u32::MAX);
+		assert_eq!(sma.get_wide_sum(), 1000 * u32::MAX as u64);
+
+		for _ in 0..500 {
+			sma.add_sample(1);
+		}
+		assert_eq!(sma.get_wide_average(), (500 * u32::MAX as u64 + 500) / 1000);
+		assert_eq!(sma.get_average(), u32::MAX / 2 + 1);
+
+		let mut sma = WideningSumSMA::<i8, i16, i16, 3>::new();
+		sma.add_samples(&[i8::MIN, i8::MIN, i8::MAX, i8::MIN]);
+		assert_eq!(sma.get_average(), -43);
+	}
+
+	#[test]
+	fn dynamic_f32_samples() {
+		let window_size = "3".parse().unwrap();
+		let mut sma = DynamicSMA::<_, f32>::new(window_size);
+
+		assert_eq!(sma.get_average(), 0.0);
+		assert_eq!(sma.get_num_samples(), 0);
+		assert_eq!(sma.get_sample_window_size(), 3);
+
+		sma.add_sample(4.0);
+		assert_eq!(sma.get_average(), 4.0);
+		assert_eq!(sma.get_num_samples(), 1);
+
+		sma.add_sample(8.0);
+		assert_eq!(sma.get_average(), 6.0);
+		assert_eq!(sma.get_num_samples(), 2);
+
+		sma.add_sample(3.0);
+		assert_eq!(sma.get_average(), 5.0);
+		assert_eq!(sma.get_num_samples(), 3);
+
+		// Here we reach window_size and start to pop old samples
+
+		sma.add_sample(7.0);
+		assert_eq!(sma.get_average(), 6.0);
+		assert_eq!(sma.get_num_samples(), 3);
+
+		sma.add_sample(11.0);
+		assert_eq!(sma.get_average(), 7.0);
+		assert_eq!(sma.get_num_samples(), 3);
+
+		sma.add_sample(0.0);
+		assert_eq!(sma.get_average(), 6.0);
+		assert_eq!(sma.get_num_samples(), 3);
+
+		sma.add_sample(-23.0);
+		assert_eq!(sma.get_average(), -4.0);
+		assert_eq!(sma.get_num_samples(), 3);
+		assert_eq!(sma.get_most_recent_sample(), Some(-23.0));
+		assert_eq!(sma.get_oldest_sample(), Some(11.0));
+		assert_eq!(
+			sma.get_sample_window_iter().collect::<Vec<&f32>>(),
+			vec![&11.0, &0.0, &-23.0]
+		);
+	}
+
+	#[test]
+	fn dynamic_resize_window() {
+		let mut sma = DynamicSMA::<u32, u32>::new(4);
+		for sample in [2, 4, 6, 8] {
+			sma.add_sample(sample);
+		}
+
+		sma.resize_window(2);
+		assert_eq!(sma.get_sample_window_size(), 2);
+		assert_eq!(sma.get_num_samples(), 2);
+		assert_eq!(sma.get_average(), 7);
+		assert_eq!(sma.get_oldest_sample(), Some(6));
+
+		sma.add_sample(10);
+		assert_eq!(sma.get_average(), 9);
+
+		sma.resize_window(4);
+		assert_eq!(sma.get_sample_window_size(), 4);
+		assert_eq!(sma.get_num_samples(), 2);
+		assert_eq!(sma.get_average(), 9);
+
+		for sample in [12, 14, 16] {
+			sma.add_sample(sample);
+		}
+		assert_eq!(sma.get_num_samples(), 4);
+		assert_eq!(sma.get_average(), 13);
+
+		sma.resize_window(0);
+		assert_eq!(sma.get_num_samples(), 0);
+		assert_eq!(sma.get_average(), 0);
+	}
+
+	#[test]
+	fn dynamic_for_each_sample() {
+		let mut sma = DynamicSMA::<u32, u32>::new(3);
+		for sample in 1..=5 {
+			sma.add_sample(sample);
+		}
+
+		let mut samples = Vec::new();
+		sma.for_each_sample(|sample| samples.push(*sample));
+		assert_eq!(samples, vec![3, 4, 5]);
+	}
+
+	#[test]
+	fn dynamic_edge_case_zero_sized() {
+		let mut sma = DynamicSMA::<u32, u32>::new(0);
+		assert_eq!(sma.get_average(), 0);
+		assert_eq!(sma.get_num_samples(), 0);
+
+		sma.add_sample(16);
+		assert_eq!(sma.get_average(), 0);
+		assert_eq!(sma.get_num_samples(), 0);
+		assert_eq!(sma.get_most_recent_sample(), None);
+	}
+
+	#[test]
+	fn f32_random_samples_max_algorithm_diffs() {
+		use rand::{distributions::Uniform, rngs::SmallRng, Rng, SeedableRng};
+		use rayon::prelude::*;
+
+		const WINDOW_SIZE: usize = 10;
+		const VALUE_RANGES: [(usize, usize); 6] = [
+			(0, 10),
+			(10, 100),
+			(100, 1000),
+			(1000, 10000),
+			(10000, 100000),
+			(100000, 1000000),
+		];
+
+		let seeds: Vec<u64> = SmallRng::seed_from_u64(0xCAFEBABE)
+			.sample_iter(&Uniform::from(0..u64::MAX))
+			.take(100)
+			.collect();
+
+		let averages_array_vec: Vec<[[f32; 3]; VALUE_RANGES.len()]> = seeds
+			.par_iter()
+			.map(|seed| {
+				let random_values: Vec<f32> = SmallRng::seed_from_u64(*seed)
+					.sample_iter(&Uniform::from(-100.0..100.0))
+					.take(1000000)
+					.collect();
+
+				let mut single_sum_sma = SingleSumSMA::<_, f32, WINDOW_SIZE>::new();
+				let mut sum_tree_sma = SumTreeSMA::<_, f32, WINDOW_SIZE>::new();
+				let mut no_sum_sma = NoSumSMA::<_, f32, WINDOW_SIZE>::new();
+
+				VALUE_RANGES.map(|value_range| {
+					for random_value in &random_values[value_range.0..value_range.1] {
+						single_sum_sma.add_sample(*random_value);
+						sum_tree_sma.add_sample(*random_value);
+						no_sum_sma.add_sample(*random_value);
+					}
+					[
+						single_sum_sma.get_average(),
+						sum_tree_sma.get_average(),
+						no_sum_sma.get_average(),
+					]
+				})
+			})
+			.collect();
+
+		let mut maximum_absolute_diffs_array = [[0.0f32; VALUE_RANGES.len()]; 2];
+
+		for averages_array in averages_array_vec {
+			for (idx, averages) in averages_array.iter().enumerate() {
+				for i in 0..2 {
+					let abs_diff = (averages[i] - averages[2]).abs();
+					if maximum_absolute_diffs_array[i][idx] < abs_diff {
+						maximum_absolute_diffs_array[i][idx] = abs_diff;
+					}
+				}
+			}
+		}
+
+		let [single_sum_maximum_absolute_diff, sum_tree_maximum_absolute_diff]: [f32; 2] =
+			maximum_absolute_diffs_array.map(|maximum_absolute_diffs| {
+				*maximum_absolute_diffs
+					.iter()
+					.max_by(|a, b| a.abs().partial_cmp(&b.abs()).unwrap())
+					.unwrap()
+			});
+
+		assert!(single_sum_maximum_absolute_diff < 0.002);
+		assert!(sum_tree_maximum_absolute_diff < 0.000006);
+	}
+
+	#[test]
+	fn f32_random_samples_kahan_sum_diffs() {
+		use rand::{distributions::Uniform, rngs::SmallRng, Rng, SeedableRng};
+		use rayon::prelude::*;
+
+		const WINDOW_SIZE: usize = 10;
+		const NUM_SAMPLES: usize = 100000;
+
+		let seeds: Vec<u64> = SmallRng::seed_from_u64(0xCAFEBABE)
+			.sample_iter(&Uniform::from(0..u64::MAX))
+			.take(20)
+			.collect();
+
+		let maximum_absolute_diffs: Vec<[f32; 2]> = seeds
+			.par_iter()
+			.map(|seed| {
+				let mut single_sum_sma = SingleSumSMA::<_, f32, WINDOW_SIZE>::new();
+				let mut kahan_sum_sma = KahanSumSMA::<_, f32, WINDOW_SIZE>::new();
+				let mut no_sum_sma = NoSumSMA::<_, f32, WINDOW_SIZE>::new();
+
+				let mut maximum_absolute_diffs = [0.0f32; 2];
+
+				for random_value in SmallRng::seed_from_u64(*seed)
+					.sample_iter(&Uniform::from(-100.0..100.0))
+					.take(NUM_SAMPLES)
+				{
+					single_sum_sma.add_sample(random_value);
+					kahan_sum_sma.add_sample(random_value);
+					no_sum_sma.add_sample(random_value);
+				}
+
+				let no_sum_average = no_sum_sma.get_average();
+				for (i, average) in [single_sum_sma.get_average(), kahan_sum_sma.get_average()]
+					.iter()
+					.enumerate()
+				{
+					maximum_absolute_diffs[i] =
+						maximum_absolute_diffs[i].max((average - no_sum_average).abs());
+				}
+				maximum_absolute_diffs
+			})
+			.collect();
+
+		let [single_sum_maximum_absolute_diff, kahan_sum_maximum_absolute_diff] =
+			maximum_absolute_diffs
+				.iter()
+				.fold([0.0f32; 2], |acc, diffs| {
+					[acc[0].max(diffs[0]), acc[1].max(diffs[1])]
+				});
+
+		assert!(kahan_sum_maximum_absolute_diff * 10.0 < single_sum_maximum_absolute_diff);
+	}
+}
+
+#[cfg(all(test, not(feature = "num-traits")))]
+mod tests {
+	use crate::{DynamicSMA, NoSumSMA, SingleSumSMA, SumTreeSMA, SMA};
+
+	#[test]
+	fn from_zero_without_num_traits() {
+		let mut no_sum_sma = NoSumSMA::<f32, f32, 2>::from_zero(0.0);
+		let mut single_sum_sma = SingleSumSMA::<u32, u32, 2>::from_zero(0);
+		let mut sum_tree_sma = SumTreeSMA::<i64, i64, 2>::from_zero(0);
+		let mut dynamic_sma = DynamicSMA::<f64, f64>::from_zero(0.0, 2);
+
+		for sample in [1, 2, 3] {
+			no_sum_sma.add_sample(sample as f32);
+			single_sum_sma.add_sample(sample as u32);
+			sum_tree_sma.add_sample(sample as i64);
+			dynamic_sma.add_sample(sample as f64);
+		}
+
+		assert_eq!(no_sum_sma.get_average(), 2.5);
+		assert_eq!(single_sum_sma.get_average(), 2);
+		assert_eq!(sum_tree_sma.get_average(), 2);
+		assert_eq!(dynamic_sma.get_average(), 2.5);
 	}
 }
@@ -0,0 +1,99 @@
+use crate::{NoSumSMA, SingleSumSMA, SumTreeSMA};
+use num_traits::Zero;
+use std::marker::PhantomData;
+
+/// A builder for the SMA implementations, which lets the sample window size and `Divisor` type be
+/// stated by name, rather than as positional type parameters. The `Sample` type is inferred from
+/// the samples that are later added.
+///
+/// ```
+/// # use simple_moving_average::{MovingAverageBuilder, SMA};
+/// let mut ma = MovingAverageBuilder::new()
+///     .window::<3>()
+///     .divisor::<f32>()
+///     .sum_tree();
+/// ma.add_sample(1.0);
+/// ma.add_sample(2.0);
+/// assert_eq!(ma.get_average(), 1.5);
+/// ```
+///
+/// Since the sample window size is a const generic parameter of the SMA implementations, it is
+/// given as a const generic argument, `window::<3>()`, rather than as a runtime value, i.e. not as
+/// `window(3)`. Use [DynamicSMA](crate::DynamicSMA) if the sample window size is only known at
+/// runtime. The `Divisor` type must be set using [divisor](MovingAverageBuilder::divisor) before
+/// the built SMA can be used.
+///
+/// The [no_sum](MovingAverageBuilder::no_sum), [single_sum](MovingAverageBuilder::single_sum) and
+/// [sum_tree](MovingAverageBuilder::sum_tree) terminal methods are only available for `Sample`
+/// types that implement [num_traits::Zero]. For other `Sample` types, use their `_from_zero`
+/// counterparts, which take the zero sample as argument.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MovingAverageBuilder<Divisor = (), const WINDOW_SIZE: usize = 0> {
+	_marker: PhantomData<Divisor>,
+}
+
+impl MovingAverageBuilder {
+	/// Constructs a new [MovingAverageBuilder].
+	pub fn new() -> Self {
+		Self {
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<Divisor, const WINDOW_SIZE: usize> MovingAverageBuilder<Divisor, WINDOW_SIZE> {
+	/// Sets the sample window size.
+	pub fn window<const NEW_WINDOW_SIZE: usize>(
+		self,
+	) -> MovingAverageBuilder<Divisor, NEW_WINDOW_SIZE> {
+		MovingAverageBuilder {
+			_marker: PhantomData,
+		}
+	}
+
+	/// Sets the `Divisor` type, which the sum of the samples is divided by to get their average.
+	pub fn divisor<NewDivisor>(self) -> MovingAverageBuilder<NewDivisor, WINDOW_SIZE> {
+		MovingAverageBuilder {
+			_marker: PhantomData,
+		}
+	}
+
+	/// Builds a [NoSumSMA]. See [NoSumSMA::new].
+	pub fn no_sum<Sample: Copy + Zero>(self) -> NoSumSMA<Sample, Divisor, WINDOW_SIZE> {
+		NoSumSMA::new()
+	}
+
+	/// Builds a [SingleSumSMA]. See [SingleSumSMA::new].
+	pub fn single_sum<Sample: Copy + Zero>(self) -> SingleSumSMA<Sample, Divisor, WINDOW_SIZE> {
+		SingleSumSMA::new()
+	}
+
+	/// Builds a [SumTreeSMA]. See [SumTreeSMA::new].
+	pub fn sum_tree<Sample: Copy + Zero>(self) -> SumTreeSMA<Sample, Divisor, WINDOW_SIZE> {
+		SumTreeSMA::new()
+	}
+
+	/// Builds a [NoSumSMA] from the given `zero` sample. See [NoSumSMA::from_zero].
+	pub fn no_sum_from_zero<Sample: Copy>(
+		self,
+		zero: Sample,
+	) -> NoSumSMA<Sample, Divisor, WINDOW_SIZE> {
+		NoSumSMA::from_zero(zero)
+	}
+
+	/// Builds a [SingleSumSMA] from the given `zero` sample. See [SingleSumSMA::from_zero].
+	pub fn single_sum_from_zero<Sample: Copy>(
+		self,
+		zero: Sample,
+	) -> SingleSumSMA<Sample, Divisor, WINDOW_SIZE> {
+		SingleSumSMA::from_zero(zero)
+	}
+
+	/// Builds a [SumTreeSMA] from the given `zero` sample. See [SumTreeSMA::from_zero].
+	pub fn sum_tree_from_zero<Sample: Copy>(
+		self,
+		zero: Sample,
+	) -> SumTreeSMA<Sample, Divisor, WINDOW_SIZE> {
+		SumTreeSMA::from_zero(zero)
+	}
+}
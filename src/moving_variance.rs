@@ -0,0 +1,224 @@
+use crate::{common::cast_to_divisor_type, ring_buffer::RingBuffer, DivisorFromCount};
+use num_traits::Float;
+
+/// Keeps track of the mean and variance of the samples in a sliding sample window of size
+/// `WINDOW_SIZE`, using [Welford's online algorithm](https://en.wikipedia.org/wiki/Algorithms_for_calculating_variance#Welford's_online_algorithm).
+///
+/// When a sample is added to a full sample window, the oldest sample is unwound from the running
+/// mean and sum of squared deviations in the same step as the new sample is added. Reading the
+/// mean, variance and standard deviation are `O(1)` operations. The variance is the population
+/// variance, i.e. the sum of squared deviations divided by the number of samples, consistent with
+/// [SMA::get_variance](crate::SMA::get_variance).
+///
+/// Unwinding samples accumulates floating point rounding errors, which is especially problematic
+/// when the variance is small compared to the mean, as the sum of squared deviations may then be
+/// dominated by the rounding error, or even become negative. To bound this error, the mean and sum
+/// of squared deviations are recalculated from the samples in the sample window every
+/// `WINDOW_SIZE` added samples, which makes adding a sample an amortized `O(1)` operation. The sum
+/// of squared deviations is also clamped to zero in between. Only floating point `Sample` types
+/// are supported.
+#[derive(Clone, Copy, Debug)]
+pub struct MovingVariance<Sample, const WINDOW_SIZE: usize> {
+	samples: RingBuffer<Sample, WINDOW_SIZE>,
+	mean: Sample,
+	// Sum of the squared deviations from the mean, M2 in Welford's algorithm
+	squared_deviation_sum: Sample,
+	num_samples_since_recalculation: usize,
+}
+
+impl<Sample, const WINDOW_SIZE: usize> MovingVariance<Sample, WINDOW_SIZE>
+where
+	Sample: Float + DivisorFromCount,
+{
+	/// Constructs a new [MovingVariance] with window size `WINDOW_SIZE`.
+	pub fn new() -> Self {
+		Self {
+			samples: RingBuffer::new(Sample::zero()),
+			mean: Sample::zero(),
+			squared_deviation_sum: Sample::zero(),
+			num_samples_since_recalculation: 0,
+		}
+	}
+
+	/// Adds a sample to the series of samples. If the sample window is full, this will cause the
+	/// oldest sample to be dropped, i.e. no longer contribute to the mean and variance.
+	pub fn add_sample(&mut self, new_sample: Sample) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+
+		match self.samples.shift(new_sample) {
+			Some(shifted_sample) => {
+				let mean = self.mean
+					+ (new_sample - shifted_sample) / cast_to_divisor_type::<Sample>(WINDOW_SIZE);
+				self.squared_deviation_sum = (self.squared_deviation_sum
+					+ (new_sample - shifted_sample)
+						* (new_sample - mean + shifted_sample - self.mean))
+					.max(Sample::zero());
+				self.mean = mean;
+			}
+			None => {
+				let deviation = new_sample - self.mean;
+				self.mean =
+					self.mean + deviation / cast_to_divisor_type::<Sample>(self.samples.len());
+				self.squared_deviation_sum =
+					self.squared_deviation_sum + deviation * (new_sample - self.mean);
+			}
+		}
+
+		self.num_samples_since_recalculation += 1;
+		if self.num_samples_since_recalculation == WINDOW_SIZE {
+			self.recalculate();
+		}
+	}
+
+	/// Returns the mean of the samples in the sample window, or zero if it is empty.
+	pub fn get_mean(&self) -> Sample {
+		self.mean
+	}
+
+	/// Returns the population variance of the samples in the sample window, or zero if it is empty.
+	pub fn get_variance(&self) -> Sample {
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return Sample::zero();
+		}
+
+		self.squared_deviation_sum / cast_to_divisor_type::<Sample>(num_samples)
+	}
+
+	/// Returns the population standard deviation of the samples in the sample window, i.e. the
+	/// square root of [get_variance](MovingVariance::get_variance).
+	pub fn get_std_dev(&self) -> Sample {
+		self.get_variance().sqrt()
+	}
+
+	/// Returns the total number of samples currently in the in the sample window. This value never
+	/// exceeds the sample window size.
+	pub fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	/// Returns the maximum number of samples that fit in the sample window.
+	pub fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+
+	// Two-pass calculation, which does not suffer from the rounding errors of the unwinding updates
+	fn recalculate(&mut self) {
+		let num_samples = cast_to_divisor_type::<Sample>(self.samples.len());
+
+		self.mean = self
+			.samples
+			.iter()
+			.fold(Sample::zero(), |sum, sample| sum + *sample)
+			/ num_samples;
+		self.squared_deviation_sum = self.samples.iter().fold(Sample::zero(), |sum, sample| {
+			sum + (*sample - self.mean) * (*sample - self.mean)
+		});
+		self.num_samples_since_recalculation = 0;
+	}
+}
+
+impl<Sample, const WINDOW_SIZE: usize> Default for MovingVariance<Sample, WINDOW_SIZE>
+where
+	Sample: Float + DivisorFromCount,
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn brute_force_variance(window: &[f64]) -> (f64, f64) {
+		let mean = window.iter().sum::<f64>() / window.len() as f64;
+		let variance = window
+			.iter()
+			.map(|sample| (sample - mean) * (sample - mean))
+			.sum::<f64>()
+			/ window.len() as f64;
+		(mean, variance)
+	}
+
+	fn assert_matches_brute_force<const WINDOW_SIZE: usize>(samples: &[f64]) {
+		let mut moving_variance = MovingVariance::<f64, WINDOW_SIZE>::new();
+
+		for (idx, sample) in samples.iter().enumerate() {
+			moving_variance.add_sample(*sample);
+
+			let window = &samples[(idx + 1).saturating_sub(WINDOW_SIZE)..=idx];
+			let (mean, variance) = brute_force_variance(window);
+			assert!((moving_variance.get_mean() - mean).abs() < 1e-9);
+			assert!((moving_variance.get_variance() - variance).abs() < 1e-9);
+			assert!((moving_variance.get_std_dev() - variance.sqrt()).abs() < 1e-6);
+		}
+	}
+
+	#[test]
+	fn basics() {
+		let mut moving_variance = MovingVariance::<f64, 3>::new();
+		assert_eq!(moving_variance.get_mean(), 0.0);
+		assert_eq!(moving_variance.get_variance(), 0.0);
+
+		moving_variance.add_sample(2.0);
+		assert_eq!(moving_variance.get_variance(), 0.0);
+
+		moving_variance.add_sample(4.0);
+		assert_eq!(moving_variance.get_mean(), 3.0);
+		assert_eq!(moving_variance.get_variance(), 1.0);
+		assert_eq!(moving_variance.get_std_dev(), 1.0);
+
+		// Window: [4, 6, 8]
+		moving_variance.add_sample(6.0);
+		moving_variance.add_sample(8.0);
+		assert_eq!(moving_variance.get_mean(), 6.0);
+		assert!((moving_variance.get_variance() - 8.0 / 3.0).abs() < 1e-12);
+		assert_eq!(moving_variance.get_num_samples(), 3);
+	}
+
+	#[test]
+	fn random_samples() {
+		use rand::{distributions::Uniform, rngs::SmallRng, Rng, SeedableRng};
+
+		let samples: Vec<f64> = SmallRng::seed_from_u64(0xCAFEBABE)
+			.sample_iter(&Uniform::from(-100.0..100.0))
+			.take(1000)
+			.collect();
+
+		assert_matches_brute_force::<1>(&samples);
+		assert_matches_brute_force::<2>(&samples);
+		assert_matches_brute_force::<7>(&samples);
+		assert_matches_brute_force::<64>(&samples);
+	}
+
+	#[test]
+	fn large_offset() {
+		// A small variance around a large mean is prone to catastrophic cancellation
+		let samples: Vec<f64> = (0..1000)
+			.map(|idx| 1e9 + f64::from(idx % 7) * 0.001)
+			.collect();
+
+		let mut moving_variance = MovingVariance::<f64, 10>::new();
+		for (idx, sample) in samples.iter().enumerate() {
+			moving_variance.add_sample(*sample);
+
+			let window = &samples[(idx + 1).saturating_sub(10)..=idx];
+			let (_, variance) = brute_force_variance(window);
+			assert!(moving_variance.get_variance() >= 0.0);
+			assert!((moving_variance.get_variance() - variance).abs() < 1e-6);
+		}
+	}
+
+	#[test]
+	fn edge_case_zero_sized() {
+		let mut moving_variance = MovingVariance::<f32, 0>::new();
+		moving_variance.add_sample(1.0);
+		assert_eq!(moving_variance.get_mean(), 0.0);
+		assert_eq!(moving_variance.get_variance(), 0.0);
+		assert_eq!(moving_variance.get_num_samples(), 0);
+	}
+}
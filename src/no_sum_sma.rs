@@ -1,16 +1,39 @@
 use super::SMA;
-use crate::{common::cast_to_divisor_type, ring_buffer::RingBuffer, Iter};
-use num_traits::{FromPrimitive, Zero};
+#[cfg(feature = "num-traits")]
+use crate::WindowSizeError;
+use crate::{
+	common::{cast_to_divisor_type, pairwise_sum},
+	ring_buffer::RingBuffer,
+	DivisorFromCount, IntoIter, Iter, Snapshot,
+};
+#[cfg(feature = "num-traits")]
+use num_traits::{NumCast, ToPrimitive, Zero};
 use std::{
+	any::type_name,
+	fmt,
+	hash::{Hash, Hasher},
 	marker::{self, PhantomData},
+	mem::size_of,
 	ops::{AddAssign, Div},
 };
+#[cfg(feature = "num-traits")]
+use std::{convert::TryFrom, iter::FromIterator};
 
-/// An SMA implementation that does not cache any intermediate sample sum.
-#[derive(Clone, Copy, Debug)]
+/// An SMA implementation that does not cache any intermediate sample sum. The samples are summed
+/// using [pairwise summation](https://en.wikipedia.org/wiki/Pairwise_summation), which keeps the
+/// floating point rounding error low, even for large sample windows.
+///
+/// Every call to [get_average](SMA::get_average) sums the whole sample window. If the average is
+/// read much more often than samples are added, consider wrapping the [NoSumSMA] in a
+/// [CachedSMA](crate::CachedSMA), which memoizes the average until the sample window next changes.
+#[derive(Clone, Copy)]
 pub struct NoSumSMA<Sample, Divisor, const WINDOW_SIZE: usize> {
 	samples: RingBuffer<Sample, WINDOW_SIZE>,
 	zero: Sample,
+	// Incremented whenever the sample window is modified
+	generation: u64,
+	// The number of samples that get_average_if_ready requires
+	min_samples: usize,
 	_marker: marker::PhantomData<Divisor>,
 }
 
@@ -18,37 +41,41 @@ impl<Sample, Divisor, const WINDOW_SIZE: usize> SMA<Sample, Divisor, WINDOW_SIZE
 	for NoSumSMA<Sample, Divisor, WINDOW_SIZE>
 where
 	Sample: Copy + AddAssign + Div<Divisor, Output = Sample>,
-	Divisor: FromPrimitive,
+	Divisor: DivisorFromCount,
 {
 	fn add_sample(&mut self, new_sample: Sample) {
 		if WINDOW_SIZE == 0 {
 			return;
 		}
 		self.samples.push_front(new_sample);
+		self.generation += 1;
 	}
 
-	fn get_average(&self) -> Sample {
-		let num_samples = self.samples.len();
-
-		if num_samples == 0 {
-			return self.zero;
+	// Shifts the new sample into the sample window, and then sums the window once, in the same order
+	// as get_average does, so that the result is identical to that of a separate get_average call
+	fn add_and_get_average(&mut self, new_sample: Sample) -> Sample {
+		if WINDOW_SIZE != 0 {
+			self.samples.shift(new_sample);
+			self.generation += 1;
 		}
 
-		let sum = {
-			let mut sum = self.zero;
-			for sample in self.samples.iter() {
-				sum += *sample;
-			}
-			sum
-		};
+		let (older_samples, newer_samples) = self.samples.as_slices();
+		self.calculate_average(older_samples, newer_samples)
+	}
 
-		sum / cast_to_divisor_type(num_samples)
+	fn get_average(&self) -> Sample {
+		let (older_samples, newer_samples) = self.samples.as_slices();
+		self.calculate_average(older_samples, newer_samples)
 	}
 
 	fn get_most_recent_sample(&self) -> Option<Sample> {
 		self.samples.front().cloned()
 	}
 
+	fn get_oldest_sample(&self) -> Option<Sample> {
+		self.samples.back().cloned()
+	}
+
 	fn get_num_samples(&self) -> usize {
 		self.samples.len()
 	}
@@ -57,14 +84,17 @@ where
 		WINDOW_SIZE
 	}
 
-	fn get_sample_window_iter(&self) -> Iter<Sample, WINDOW_SIZE> {
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
 		self.samples.iter()
 	}
 }
 
+#[cfg(feature = "num-traits")]
 impl<Sample: Copy + Zero, Divisor, const WINDOW_SIZE: usize>
 	NoSumSMA<Sample, Divisor, WINDOW_SIZE>
 {
+	const NONZERO_WINDOW_SIZE_CHECK: () = assert!(0 < WINDOW_SIZE, "WINDOW_SIZE must be non-zero");
+
 	/// Constructs a new [NoSumSMA] with window size `WINDOW_SIZE`. This constructor is
 	/// only available for `Sample` types that implement [num_traits::Zero]. If the `Sample` type
 	/// does not, use the [from_zero](NoSumSMA::from_zero) constructor instead.
@@ -75,20 +105,427 @@ impl<Sample: Copy + Zero, Divisor, const WINDOW_SIZE: usize>
 		Self {
 			samples: RingBuffer::new(Sample::zero()),
 			zero: Sample::zero(),
+			generation: 0,
+			min_samples: 0,
 			_marker: PhantomData,
 		}
 	}
+
+	/// Constructs a new [NoSumSMA] with window size `WINDOW_SIZE`, just like [new](NoSumSMA::new),
+	/// except that a `WINDOW_SIZE` of zero fails to compile, rather than resulting in an SMA that
+	/// ignores all samples.
+	///
+	/// ```compile_fail
+	/// # use simple_moving_average::NoSumSMA;
+	/// let sma = NoSumSMA::<f32, f32, 0>::new_nonzero();
+	/// ```
+	pub fn new_nonzero() -> Self {
+		let () = Self::NONZERO_WINDOW_SIZE_CHECK;
+
+		Self::new()
+	}
+}
+
+#[cfg(feature = "num-traits")]
+impl<Sample: Copy + Zero, Divisor, const WINDOW_SIZE: usize> Default
+	for NoSumSMA<Sample, Divisor, WINDOW_SIZE>
+{
+	fn default() -> Self {
+		Self::new()
+	}
 }
 
 impl<Sample: Copy, Divisor, const WINDOW_SIZE: usize> NoSumSMA<Sample, Divisor, WINDOW_SIZE> {
+	/// The sample window size, as a constant that can be used in const contexts, e.g. to size an
+	/// array. Equal to [get_sample_window_size](SMA::get_sample_window_size).
+	pub const WINDOW_SIZE: usize = WINDOW_SIZE;
+
 	/// Constructs a new [NoSumSMA] with window size `WINDOW_SIZE` from the given
 	/// `zero` sample. If the `Sample` type implements [num_traits::Zero], the
 	/// [new](NoSumSMA::new) constructor might be preferable to this.
-	pub fn from_zero(zero: Sample) -> Self {
+	///
+	/// This constructor is a `const fn`, so it can be used to construct a [NoSumSMA] in a `const`
+	/// or `static` item, without any heap allocation.
+	pub const fn from_zero(zero: Sample) -> Self {
 		Self {
 			samples: RingBuffer::new(zero),
 			zero,
+			generation: 0,
+			min_samples: 0,
 			_marker: PhantomData,
 		}
 	}
+
+	/// Consumes the [NoSumSMA], returning the samples in the sample window, oldest first.
+	pub fn into_inner(self) -> Vec<Sample> {
+		let (older_samples, newer_samples) = self.samples.as_slices();
+		[older_samples, newer_samples].concat()
+	}
+
+	/// Returns the number of bytes of memory used by this instance. Since a [NoSumSMA] is
+	/// completely backed by an array, this is its size on the stack.
+	pub fn get_memory_footprint(&self) -> usize {
+		size_of::<Self>()
+	}
+
+	/// Returns the generation of the sample window, a counter that is incremented whenever it is
+	/// modified, e.g. by adding a sample. A reader that polls the average, e.g. through a
+	/// [SharedSMA](crate::SharedSMA), can compare the generation with the one it last saw to find
+	/// out whether anything has changed, without recalculating or comparing anything. Reading never
+	/// changes the generation.
+	pub fn get_generation(&self) -> u64 {
+		self.generation
+	}
+
+	/// Returns a [Snapshot] of the samples in the sample window, which can later be restored using
+	/// [restore](NoSumSMA::restore).
+	pub fn snapshot(&self) -> Snapshot<Sample> {
+		Snapshot::new(self.samples.iter().cloned().collect(), None)
+	}
+
+	/// Replaces the samples in the sample window with those of `snapshot`. If the snapshot contains
+	/// more samples than fit in the sample window, only the most recent ones are kept.
+	pub fn restore(&mut self, snapshot: Snapshot<Sample>) {
+		let (samples, _) = snapshot.into_parts();
+
+		self.samples.clear();
+		for sample in &samples[samples.len().saturating_sub(WINDOW_SIZE)..] {
+			self.samples.push_front(*sample);
+		}
+		self.generation += 1;
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> NoSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + AddAssign + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	/// Constructs a new [NoSumSMA] with window size `WINDOW_SIZE`, whose sample window is completely
+	/// filled with the samples returned by `f(0)` (the oldest) through `f(WINDOW_SIZE - 1)` (the
+	/// most recent), like [std::array::from_fn].
+	#[cfg(feature = "num-traits")]
+	pub fn from_fn<F: FnMut(usize) -> Sample>(mut f: F) -> Self
+	where
+		Sample: Zero,
+	{
+		let mut sma = Self::new();
+		for idx in 0..WINDOW_SIZE {
+			sma.add_sample(f(idx));
+		}
+		sma
+	}
+
+	/// Constructs a new [NoSumSMA] with window size `WINDOW_SIZE`, whose sample window is completely
+	/// filled with `value`. This constructor is only available for `Sample` types that implement
+	/// [num_traits::Zero]. If the `Sample` type does not, use the
+	/// [with_initial_from_zero](NoSumSMA::with_initial_from_zero) constructor instead.
+	#[cfg(feature = "num-traits")]
+	pub fn with_initial(value: Sample) -> Self
+	where
+		Sample: Zero,
+	{
+		Self::with_initial_from_zero(Sample::zero(), value)
+	}
+
+	/// Adds the samples in the sample window of `other` to `self`, oldest first, as if they had been
+	/// added one by one. Only the last `WINDOW_SIZE` of the combined samples remain in the sample
+	/// window.
+	pub fn merge_window(&mut self, other: &Self) {
+		for sample in other.samples.iter() {
+			self.add_sample(*sample);
+		}
+	}
+
+	/// Sets the number of samples that the sample window must contain for
+	/// [get_average_if_ready](NoSumSMA::get_average_if_ready) to return an average, which lets a
+	/// caller trade responsiveness for stability, rather than waiting for the sample window to be
+	/// full. Defaults to zero, i.e. no threshold.
+	///
+	/// # Panics
+	///
+	/// Panics if `min_samples` exceeds `WINDOW_SIZE`.
+	pub fn with_min_samples(mut self, min_samples: usize) -> Self {
+		assert!(
+			min_samples <= WINDOW_SIZE,
+			"min_samples must not exceed WINDOW_SIZE"
+		);
+		self.min_samples = min_samples;
+		self
+	}
+
+	/// Returns the number of samples that the sample window must contain for
+	/// [get_average_if_ready](NoSumSMA::get_average_if_ready) to return an average.
+	pub fn get_min_samples(&self) -> usize {
+		self.min_samples
+	}
+
+	/// Returns the simple moving average value of all the samples in the sample window, but only
+	/// once it contains at least [get_min_samples](NoSumSMA::get_min_samples) samples. Before that,
+	/// `None` is returned.
+	pub fn get_average_if_ready(&self) -> Option<Sample> {
+		if self.get_num_samples() < self.min_samples {
+			return None;
+		}
+
+		Some(self.get_average())
+	}
+
+	/// Returns the simple moving average value of all the samples in the sample window, like
+	/// [get_average](SMA::get_average), but accumulates the sum of the samples in the `Acc` type,
+	/// e.g. [f64] for [f32] samples or [u64] for [u32] samples. Each sample is widened to `Acc`
+	/// before being added to the sum, which is then divided by the number of samples and narrowed
+	/// back to the `Sample` type. This prevents the sum from overflowing and reduces its rounding
+	/// error, without allocating or caching anything.
+	///
+	/// # Panics
+	///
+	/// Panics if the average can't be narrowed to the `Sample` type, which never happens for
+	/// integer types.
+	#[cfg(feature = "num-traits")]
+	pub fn get_average_with_acc<Acc>(&self) -> Sample
+	where
+		Sample: NumCast,
+		Acc: Copy + From<Sample> + AddAssign + Div<Output = Acc> + DivisorFromCount + ToPrimitive,
+	{
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return self.zero;
+		}
+
+		let mut sum = Acc::from(self.zero);
+		for sample in self.samples.iter() {
+			sum += Acc::from(*sample);
+		}
+
+		<Sample as NumCast>::from(sum / cast_to_divisor_type(num_samples))
+			.unwrap_or_else(|| panic!("Failed to narrow the average to the sample type"))
+	}
+
+	/// Removes the samples for which `predicate` returns `false` from the sample window, e.g.
+	/// samples that turned out to be invalid, keeping the order of the remaining samples.
+	///
+	/// This temporarily breaks the invariant that the sample window holds the last `WINDOW_SIZE`
+	/// samples, until enough new samples have been added to fill it up again.
+	pub fn retain<F: FnMut(&Sample) -> bool>(&mut self, predicate: F) {
+		self.samples.retain(predicate);
+		self.generation += 1;
+	}
+
+	/// Removes the oldest sample from the sample window, without adding a new sample. Returns the
+	/// removed sample, or `None` if the sample window is empty.
+	///
+	/// This deliberately breaks the invariant that the sample window holds the last `WINDOW_SIZE`
+	/// samples, until enough new samples have been added to fill it up again.
+	pub fn shift_out(&mut self) -> Option<Sample> {
+		let shifted_sample = self.samples.pop_back()?;
+		self.generation += 1;
+		Some(shifted_sample)
+	}
+
+	/// Empties the sample window, dropping all samples that were in it, so that the average becomes
+	/// zero.
+	pub fn clear(&mut self) {
+		self.samples.clear();
+		self.generation += 1;
+	}
+
+	/// Completely fills the sample window with `value`, dropping all samples that were in it, so
+	/// that the average becomes `value`. Unlike emptying the sample window, this resets it to a
+	/// known steady state, e.g. after a regime change.
+	pub fn reset_to(&mut self, value: Sample) {
+		self.samples.clear();
+		for _ in 0..WINDOW_SIZE {
+			self.samples.push_front(value);
+		}
+		self.generation += 1;
+	}
+
+	/// Empties the sample window, returning the average of the samples that were in it, along with
+	/// the samples themselves, oldest first.
+	///
+	/// The samples are popped off the sample window in a single pass, after which their average is
+	/// calculated from the returned samples, the same way as by [get_average](SMA::get_average).
+	pub fn drain(&mut self) -> (Sample, Vec<Sample>) {
+		let mut samples = Vec::with_capacity(self.samples.len());
+		while let Some(sample) = self.samples.pop_back() {
+			samples.push(sample);
+		}
+		self.generation += 1;
+
+		(self.calculate_average(&samples, &[]), samples)
+	}
+
+	// The sample window is given as two slices, e.g. split where it wraps around the end of the
+	// backing array, but the average only depends on the order of the samples, not on the split
+	fn calculate_average(&self, older_samples: &[Sample], newer_samples: &[Sample]) -> Sample {
+		let num_samples = older_samples.len() + newer_samples.len();
+
+		if num_samples == 0 {
+			return self.zero;
+		}
+
+		pairwise_sum(&self.zero, older_samples, newer_samples) / cast_to_divisor_type(num_samples)
+	}
+
+	/// Constructs a new [NoSumSMA] with window size `WINDOW_SIZE` from the given `zero` sample, whose
+	/// sample window is completely filled with `value`.
+	pub fn with_initial_from_zero(zero: Sample, value: Sample) -> Self {
+		let mut sma = Self::from_zero(zero);
+		for _ in 0..WINDOW_SIZE {
+			sma.add_sample(value);
+		}
+		sma
+	}
+}
+
+#[cfg(feature = "num-traits")]
+impl<Sample, Divisor, const WINDOW_SIZE: usize> FromIterator<Sample>
+	for NoSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Zero + AddAssign + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	/// Constructs a new [NoSumSMA] and adds all samples yielded by `iter` to it. Only the last
+	/// `WINDOW_SIZE` samples will end up in the sample window.
+	fn from_iter<I: IntoIterator<Item = Sample>>(iter: I) -> Self {
+		let mut sma = Self::new();
+		sma.extend(iter);
+		sma
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> Extend<Sample>
+	for NoSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + AddAssign + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	fn extend<I: IntoIterator<Item = Sample>>(&mut self, iter: I) {
+		for sample in iter {
+			self.add_sample(sample);
+		}
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> AddAssign<Sample>
+	for NoSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + AddAssign + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	/// Adds a sample to the series of samples, like [add_sample](SMA::add_sample).
+	fn add_assign(&mut self, new_sample: Sample) {
+		self.add_sample(new_sample);
+	}
+}
+
+#[cfg(feature = "num-traits")]
+impl<Sample, Divisor, const WINDOW_SIZE: usize> From<[Sample; WINDOW_SIZE]>
+	for NoSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Zero + AddAssign + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	/// Constructs a new [NoSumSMA] whose sample window is completely filled with `samples`, the last
+	/// sample being the most recent one.
+	fn from(samples: [Sample; WINDOW_SIZE]) -> Self {
+		let mut sma = Self::new();
+		sma.add_samples(&samples);
+		sma
+	}
+}
+
+#[cfg(feature = "num-traits")]
+impl<Sample, Divisor, const WINDOW_SIZE: usize> TryFrom<&[Sample]>
+	for NoSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Zero + AddAssign + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	type Error = WindowSizeError;
+
+	/// Constructs a new [NoSumSMA] whose sample window contains `samples`, the last sample being the
+	/// most recent one. Fails if there are more samples than fit in the sample window, rather than
+	/// silently dropping the oldest ones.
+	fn try_from(samples: &[Sample]) -> Result<Self, Self::Error> {
+		if WINDOW_SIZE < samples.len() {
+			return Err(WindowSizeError::new(samples.len(), WINDOW_SIZE));
+		}
+
+		let mut sma = Self::new();
+		sma.add_samples(samples);
+		Ok(sma)
+	}
+}
+
+impl<Sample: Copy, Divisor, const WINDOW_SIZE: usize> IntoIterator
+	for NoSumSMA<Sample, Divisor, WINDOW_SIZE>
+{
+	type Item = Sample;
+	type IntoIter = IntoIter<Sample, WINDOW_SIZE>;
+
+	/// Consumes the [NoSumSMA], returning an iterator over the samples in the sample window, oldest
+	/// first.
+	fn into_iter(self) -> Self::IntoIter {
+		self.samples.into_iter()
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> fmt::Debug
+	for NoSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("NoSumSMA")
+			.field("window_size", &WINDOW_SIZE)
+			.field("num_samples", &self.samples.len())
+			.field("samples", &self.samples.iter().collect::<Vec<&Sample>>())
+			.field("divisor", &type_name::<Divisor>())
+			.finish()
+	}
+}
+
+/// Formats the average of the samples in the sample window, as returned by
+/// [get_average](SMA::get_average), forwarding the formatter's flags, e.g. its precision.
+impl<Sample, Divisor, const WINDOW_SIZE: usize> fmt::Display
+	for NoSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + AddAssign + Div<Divisor, Output = Sample> + fmt::Display,
+	Divisor: DivisorFromCount,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&self.get_average(), f)
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> PartialEq for NoSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + PartialEq,
+{
+	/// Two instances are equal if their sample windows contain the same samples, in the same order.
+	fn eq(&self, other: &Self) -> bool {
+		self.samples.iter().eq(other.samples.iter())
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> Eq for NoSumSMA<Sample, Divisor, WINDOW_SIZE> where
+	Sample: Copy + Eq
+{
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> Hash for NoSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Hash,
+{
+	/// Hashes the number of samples and the samples in the sample window, in order, consistent with
+	/// [PartialEq].
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		state.write_usize(self.samples.len());
+		for sample in self.samples.iter() {
+			sample.hash(state);
+		}
+	}
 }
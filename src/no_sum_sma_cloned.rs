@@ -0,0 +1,197 @@
+use crate::{
+	common::{cast_to_divisor_type, pairwise_sum},
+	DivisorFromCount,
+};
+#[cfg(feature = "num-traits")]
+use num_traits::Zero;
+use std::{
+	collections::{vec_deque, VecDeque},
+	marker::{self, PhantomData},
+	ops::{AddAssign, Div},
+};
+
+/// A variant of [NoSumSMA](crate::NoSumSMA) for `Sample` types that implement [Clone], but not
+/// [Copy], e.g. heap allocated vectors or arbitrary precision numbers. Samples are cloned wherever
+/// [NoSumSMA](crate::NoSumSMA) copies them, and summed using the same pairwise summation.
+///
+/// Since the [SMA](crate::SMA) trait requires samples to be returned by value, this type provides
+/// the same interface through inherent methods instead, returning samples by reference. The
+/// samples are stored in a [VecDeque], which is allocated once, when the instance is constructed.
+#[derive(Clone, Debug)]
+pub struct NoSumSMACloned<Sample, Divisor, const WINDOW_SIZE: usize> {
+	samples: VecDeque<Sample>,
+	zero: Sample,
+	_marker: marker::PhantomData<Divisor>,
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> NoSumSMACloned<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Clone + AddAssign + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	/// Adds a sample to the series of samples. If the sample window is full, this will cause the
+	/// oldest sample to be dropped, i.e. no longer contribute to the average.
+	pub fn add_sample(&mut self, new_sample: Sample) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+
+		if self.samples.len() == WINDOW_SIZE {
+			self.samples.pop_front();
+		}
+
+		self.samples.push_back(new_sample);
+	}
+
+	/// Returns the simple moving average value of all the samples in the sample window.
+	pub fn get_average(&self) -> Sample {
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return self.zero.clone();
+		}
+
+		let (older_samples, newer_samples) = self.samples.as_slices();
+		pairwise_sum(&self.zero, older_samples, newer_samples) / cast_to_divisor_type(num_samples)
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> NoSumSMACloned<Sample, Divisor, WINDOW_SIZE> {
+	/// Constructs a new [NoSumSMACloned] with window size `WINDOW_SIZE` from the given `zero`
+	/// sample. If the `Sample` type implements [num_traits::Zero], the
+	/// [new](NoSumSMACloned::new) constructor might be preferable to this.
+	pub fn from_zero(zero: Sample) -> Self {
+		Self {
+			samples: VecDeque::with_capacity(WINDOW_SIZE),
+			zero,
+			_marker: PhantomData,
+		}
+	}
+
+	/// Returns the most recently added sample, if any.
+	pub fn get_most_recent_sample(&self) -> Option<&Sample> {
+		self.samples.back()
+	}
+
+	/// Returns the oldest sample in the sample window, i.e. the sample that will be dropped next,
+	/// if any.
+	pub fn get_oldest_sample(&self) -> Option<&Sample> {
+		self.samples.front()
+	}
+
+	/// Returns the total number of samples currently in the in the sample window. This value never
+	/// exceeds the sample window size.
+	pub fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	/// Returns the maximum number of samples that fit in the sample window.
+	pub fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+
+	/// Returns an iterator over the samples currently in the sample window, oldest first.
+	pub fn get_sample_window_iter(&self) -> vec_deque::Iter<'_, Sample> {
+		self.samples.iter()
+	}
+}
+
+#[cfg(feature = "num-traits")]
+impl<Sample: Zero, Divisor, const WINDOW_SIZE: usize> NoSumSMACloned<Sample, Divisor, WINDOW_SIZE> {
+	/// Constructs a new [NoSumSMACloned] with window size `WINDOW_SIZE`. This constructor is only
+	/// available for `Sample` types that implement [num_traits::Zero]. If the `Sample` type does
+	/// not, use the [from_zero](NoSumSMACloned::from_zero) constructor instead.
+	///
+	/// Note that the `Divisor` type usually cannot be derived by the compiler when using this
+	/// constructor and must be explicitly stated, even if it is the same as the `Sample` type.
+	pub fn new() -> Self {
+		Self::from_zero(Sample::zero())
+	}
+}
+
+#[cfg(feature = "num-traits")]
+impl<Sample: Zero, Divisor, const WINDOW_SIZE: usize> Default
+	for NoSumSMACloned<Sample, Divisor, WINDOW_SIZE>
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(all(test, feature = "num-traits"))]
+mod tests {
+	use super::*;
+	use nalgebra::DVector;
+
+	#[test]
+	fn heap_allocated_vector_samples() {
+		let mut sma = NoSumSMACloned::<_, f64, 2>::from_zero(DVector::<f64>::zeros(3));
+		assert_eq!(sma.get_average(), DVector::zeros(3));
+
+		sma.add_sample(DVector::from_vec(vec![1.0, 2.0, 3.0]));
+		assert_eq!(sma.get_average(), DVector::from_vec(vec![1.0, 2.0, 3.0]));
+
+		sma.add_sample(DVector::from_vec(vec![-4.0, -2.0, -1.0]));
+		assert_eq!(sma.get_average(), DVector::from_vec(vec![-1.5, 0.0, 1.0]));
+
+		sma.add_sample(DVector::from_vec(vec![0.0, 4.0, 5.0]));
+		assert_eq!(sma.get_average(), DVector::from_vec(vec![-2.0, 1.0, 2.0]));
+		assert_eq!(sma.get_num_samples(), 2);
+		assert_eq!(
+			sma.get_oldest_sample(),
+			Some(&DVector::from_vec(vec![-4.0, -2.0, -1.0]))
+		);
+		assert_eq!(
+			sma.get_most_recent_sample(),
+			Some(&DVector::from_vec(vec![0.0, 4.0, 5.0]))
+		);
+	}
+
+	#[test]
+	fn arbitrary_precision_samples() {
+		use num_bigint::BigInt;
+
+		let mut sma = NoSumSMACloned::<BigInt, BigInt, 3>::new();
+		assert_eq!(sma.get_average(), BigInt::from(0));
+
+		let large_sample = BigInt::from(u128::MAX) * BigInt::from(u128::MAX);
+		sma.add_sample(large_sample.clone());
+		sma.add_sample(large_sample.clone());
+		assert_eq!(sma.get_average(), large_sample);
+
+		sma.add_sample(-large_sample.clone() * 2);
+		assert_eq!(sma.get_average(), BigInt::from(0));
+
+		sma.add_sample(BigInt::from(6));
+		assert_eq!(sma.get_average(), -large_sample / 3 + 2);
+		assert_eq!(sma.get_num_samples(), 3);
+		assert_eq!(sma.get_most_recent_sample(), Some(&BigInt::from(6)));
+	}
+
+	#[test]
+	fn matches_no_sum_sma() {
+		use crate::{NoSumSMA, SMA};
+
+		let mut sma = NoSumSMACloned::<f64, f64, 20>::new();
+		let mut reference_sma = NoSumSMA::<f64, f64, 20>::new();
+		for idx in 0..50 {
+			let sample = f64::from(idx).sin();
+			sma.add_sample(sample);
+			reference_sma.add_sample(sample);
+			assert!((sma.get_average() - reference_sma.get_average()).abs() < 1e-12);
+		}
+
+		assert!(sma
+			.get_sample_window_iter()
+			.eq(reference_sma.get_sample_window_iter()));
+	}
+
+	#[test]
+	fn edge_case_zero_sized() {
+		let mut sma = NoSumSMACloned::<f64, f64, 0>::new();
+		sma.add_sample(1.0);
+		assert_eq!(sma.get_average(), 0.0);
+		assert_eq!(sma.get_num_samples(), 0);
+		assert_eq!(sma.get_most_recent_sample(), None);
+	}
+}
@@ -1,6 +1,6 @@
 use crate::{
 	common::{wrapping_add, wrapping_sub},
-	Iter,
+	IntoIter, Iter,
 };
 
 #[derive(Clone, Copy, Debug)]
@@ -11,7 +11,7 @@ pub struct RingBuffer<Item, const CAPACITY: usize> {
 }
 
 impl<Item: Copy, const CAPACITY: usize> RingBuffer<Item, CAPACITY> {
-	pub fn new(zero: Item) -> Self {
+	pub const fn new(zero: Item) -> Self {
 		Self {
 			items: [zero; CAPACITY],
 			front_idx: 0, // Index of the next available slot
@@ -35,6 +35,30 @@ impl<Item: Copy, const CAPACITY: usize> RingBuffer<Item, CAPACITY> {
 		self.num_items = CAPACITY.min(self.num_items + 1);
 	}
 
+	// The mirror image of `push_front`: adds an item as the oldest item and, if the buffer is full,
+	// overwrites the most recent item, whereas `push_front` (and `shift`) overwrites the oldest one
+	#[allow(dead_code)]
+	pub fn push_back(&mut self, item: Item) {
+		if self.num_items == CAPACITY {
+			self.front_idx = wrapping_sub::<CAPACITY>(self.front_idx, 1);
+		} else {
+			self.num_items += 1;
+		}
+		self.items[wrapping_sub::<CAPACITY>(self.front_idx, self.num_items)] = item;
+	}
+
+	// The mirror image of `pop_back`: removes and returns the most recent item
+	#[allow(dead_code)]
+	pub fn pop_front(&mut self) -> Option<Item> {
+		if 0 < self.num_items {
+			self.num_items -= 1;
+			self.front_idx = wrapping_sub::<CAPACITY>(self.front_idx, 1);
+			Some(self.items[self.front_idx])
+		} else {
+			None
+		}
+	}
+
 	pub fn pop_back(&mut self) -> Option<Item> {
 		if 0 < self.num_items {
 			let num_items = self.num_items;
@@ -45,6 +69,37 @@ impl<Item: Copy, const CAPACITY: usize> RingBuffer<Item, CAPACITY> {
 		}
 	}
 
+	// Leaves the backing array untouched, as the items in it are never read before being overwritten
+	pub fn clear(&mut self) {
+		self.front_idx = 0;
+		self.num_items = 0;
+	}
+
+	#[allow(dead_code)]
+	pub const fn capacity(&self) -> usize {
+		CAPACITY
+	}
+
+	// Removes the items for which `predicate` returns false, keeping the order of the others. The
+	// retained items are moved towards the back in place, oldest first, so that every item is read
+	// before it can be overwritten.
+	pub fn retain<F: FnMut(&Item) -> bool>(&mut self, mut predicate: F) {
+		let back_idx = wrapping_sub::<CAPACITY>(self.front_idx, self.num_items);
+		let mut num_retained_items = 0;
+
+		for offset in 0..self.num_items {
+			let item = self.items[wrapping_add::<CAPACITY>(back_idx, offset)];
+			if predicate(&item) {
+				self.items[wrapping_add::<CAPACITY>(back_idx, num_retained_items)] = item;
+				num_retained_items += 1;
+			}
+		}
+
+		self.front_idx =
+			wrapping_sub::<CAPACITY>(self.front_idx, self.num_items - num_retained_items);
+		self.num_items = num_retained_items;
+	}
+
 	pub fn front(&self) -> Option<&Item> {
 		if 0 < self.num_items {
 			Some(&self.items[wrapping_sub::<CAPACITY>(self.front_idx, 1)])
@@ -53,6 +108,44 @@ impl<Item: Copy, const CAPACITY: usize> RingBuffer<Item, CAPACITY> {
 		}
 	}
 
+	pub fn back(&self) -> Option<&Item> {
+		if 0 < self.num_items {
+			Some(&self.items[wrapping_sub::<CAPACITY>(self.front_idx, self.num_items)])
+		} else {
+			None
+		}
+	}
+
+	// Index 0 is the oldest item, index `len() - 1` the most recent one
+	#[cfg_attr(not(feature = "num-traits"), allow(dead_code))]
+	pub fn get(&self, idx: usize) -> Option<&Item> {
+		if idx < self.num_items {
+			Some(
+				&self.items[wrapping_add::<CAPACITY>(
+					wrapping_sub::<CAPACITY>(self.front_idx, self.num_items),
+					idx,
+				)],
+			)
+		} else {
+			None
+		}
+	}
+
+	pub fn into_iter(self) -> IntoIter<Item, CAPACITY> {
+		IntoIter::new(self.items, self.front_idx, self.num_items)
+	}
+
+	// Returns the items as two slices, oldest items first, the second slice being empty unless the
+	// items wrap around the end of the backing array
+	pub fn as_slices(&self) -> (&[Item], &[Item]) {
+		let back_idx = wrapping_sub::<CAPACITY>(self.front_idx, self.num_items);
+		if back_idx + self.num_items <= CAPACITY {
+			(&self.items[back_idx..back_idx + self.num_items], &[])
+		} else {
+			(&self.items[back_idx..], &self.items[..self.front_idx])
+		}
+	}
+
 	pub fn len(&self) -> usize {
 		self.num_items
 	}
@@ -69,6 +162,13 @@ mod tests {
 	fn assert_rb_state(rb: &RingBuffer<u32, 3>, items: &[u32]) {
 		assert_eq!(rb.len(), items.len());
 		assert_eq!(rb.front(), items.get(items.len().wrapping_sub(1)));
+		assert_eq!(rb.back(), items.first());
+		assert!(rb.into_iter().eq(items.iter().cloned()));
+		let (older_items, newer_items) = rb.as_slices();
+		assert_eq!(
+			older_items.iter().chain(newer_items).collect::<Vec<&u32>>(),
+			items.iter().collect::<Vec<&u32>>()
+		);
 		assert_eq!(
 			rb.iter().collect::<Vec<&u32>>(),
 			items.iter().collect::<Vec<&u32>>()
@@ -119,4 +219,235 @@ mod tests {
 		assert_eq!(rb.pop_back(), None);
 		assert_rb_state(&rb, &[]);
 	}
+
+	#[test]
+	fn back() {
+		let mut rb: RingBuffer<u32, 3> = RingBuffer::new(0);
+		assert_eq!(rb.back(), None);
+
+		for item in 1..=7 {
+			let back = rb.back().cloned();
+			let shifted_item = rb.shift(item);
+			if item > 3 {
+				assert_eq!(shifted_item, back);
+			}
+			assert_eq!(rb.back(), rb.iter().next());
+		}
+
+		let back = rb.back().cloned();
+		assert_eq!(back, Some(5));
+		assert_eq!(rb.pop_back(), back);
+		assert_eq!(rb.back(), Some(&6));
+	}
+
+	#[test]
+	fn get() {
+		let mut rb: RingBuffer<u32, 3> = RingBuffer::new(0);
+		assert_eq!(rb.get(0), None);
+
+		for item in 1..=7 {
+			rb.push_front(item);
+			let items = rb.iter().collect::<Vec<&u32>>();
+			for (idx, item) in items.iter().enumerate() {
+				assert_eq!(rb.get(idx), Some(*item));
+			}
+			assert_eq!(rb.get(rb.len()), None);
+		}
+
+		assert_eq!(rb.get(0), Some(&5));
+		assert_eq!(rb.get(rb.len() - 1), Some(&7));
+
+		rb.pop_back();
+		assert_eq!(rb.get(0), Some(&6));
+		assert_eq!(rb.get(2), None);
+	}
+
+	#[test]
+	fn as_slices_wraparound() {
+		let mut rb: RingBuffer<u32, 5> = RingBuffer::new(0);
+		assert_eq!(rb.as_slices(), (&[][..], &[][..]));
+
+		for item in 1..=5 {
+			rb.push_front(item);
+		}
+		assert_eq!(rb.as_slices(), (&[1, 2, 3, 4, 5][..], &[][..]));
+
+		// The two most recent items wrap around to the start of the backing array
+		rb.shift(6);
+		rb.shift(7);
+		assert_eq!(rb.as_slices(), (&[3, 4, 5][..], &[6, 7][..]));
+
+		rb.pop_back();
+		rb.pop_back();
+		assert_eq!(rb.as_slices(), (&[5][..], &[6, 7][..]));
+		rb.pop_back();
+		assert_eq!(rb.as_slices(), (&[6, 7][..], &[][..]));
+
+		for item in 8..40 {
+			rb.shift(item);
+			let (older_items, newer_items) = rb.as_slices();
+			assert_eq!(
+				[older_items, newer_items].concat(),
+				((item - 4).max(6)..=item).collect::<Vec<u32>>()
+			);
+		}
+	}
+
+	#[test]
+	fn push_back_pop_front() {
+		let mut rb: RingBuffer<u32, 3> = RingBuffer::new(0);
+
+		assert_eq!(rb.pop_front(), None);
+		assert_rb_state(&rb, &[]);
+
+		rb.push_back(1);
+		assert_rb_state(&rb, &[1]);
+
+		assert_eq!(rb.pop_front(), Some(1));
+		assert_rb_state(&rb, &[]);
+
+		rb.push_back(2);
+		rb.push_back(3);
+		assert_rb_state(&rb, &[3, 2]);
+
+		rb.push_front(4);
+		assert_rb_state(&rb, &[3, 2, 4]);
+
+		// A full buffer drops its most recent item
+		rb.push_back(5);
+		assert_rb_state(&rb, &[5, 3, 2]);
+
+		rb.push_front(6);
+		assert_rb_state(&rb, &[3, 2, 6]);
+
+		assert_eq!(rb.pop_front(), Some(6));
+		assert_rb_state(&rb, &[3, 2]);
+
+		assert_eq!(rb.pop_back(), Some(3));
+		assert_rb_state(&rb, &[2]);
+
+		rb.push_back(7);
+		rb.push_back(8);
+		assert_rb_state(&rb, &[8, 7, 2]);
+
+		assert_eq!(rb.pop_front(), Some(2));
+		assert_eq!(rb.pop_front(), Some(7));
+		assert_eq!(rb.pop_front(), Some(8));
+		assert_eq!(rb.pop_front(), None);
+		assert_rb_state(&rb, &[]);
+	}
+
+	#[test]
+	fn push_back_wraparound() {
+		let mut rb: RingBuffer<u32, 4> = RingBuffer::new(0);
+		let mut reference = std::collections::VecDeque::new();
+
+		for item in 0..40 {
+			match item % 5 {
+				0 | 3 => {
+					if reference.len() == 4 {
+						reference.pop_back();
+					}
+					reference.push_front(item);
+					rb.push_back(item);
+				}
+				1 => assert_eq!(rb.pop_front(), reference.pop_back()),
+				_ => {
+					assert_eq!(
+						rb.shift(item),
+						if reference.len() == 4 {
+							reference.pop_front()
+						} else {
+							None
+						}
+					);
+					reference.push_back(item);
+				}
+			}
+
+			assert_eq!(rb.len(), reference.len());
+			assert!(rb.iter().eq(reference.iter()));
+			assert_eq!(rb.front(), reference.back());
+			assert_eq!(rb.back(), reference.front());
+		}
+	}
+
+	#[test]
+	fn retain() {
+		let mut rb: RingBuffer<u32, 3> = RingBuffer::new(0);
+		for item in 1..=5 {
+			rb.push_front(item);
+		}
+
+		rb.retain(|item| item % 2 == 1);
+		assert_rb_state(&rb, &[3, 5]);
+
+		rb.push_front(6);
+		assert_rb_state(&rb, &[3, 5, 6]);
+
+		rb.push_front(7);
+		assert_rb_state(&rb, &[5, 6, 7]);
+
+		rb.retain(|_| false);
+		assert_rb_state(&rb, &[]);
+	}
+
+	#[test]
+	fn clear() {
+		let mut rb: RingBuffer<u32, 3> = RingBuffer::new(0);
+		assert_eq!(rb.capacity(), 3);
+
+		for item in 1..=5 {
+			rb.push_front(item);
+		}
+		rb.clear();
+		assert_rb_state(&rb, &[]);
+		assert_eq!(rb.pop_back(), None);
+		assert_eq!(rb.capacity(), 3);
+
+		assert_eq!(rb.shift(6), None);
+		assert_rb_state(&rb, &[6]);
+
+		rb.push_front(7);
+		rb.push_front(8);
+		assert_eq!(rb.shift(9), Some(6));
+		assert_rb_state(&rb, &[7, 8, 9]);
+
+		rb.clear();
+		rb.clear();
+		assert_rb_state(&rb, &[]);
+	}
+
+	#[test]
+	fn iter_reversed() {
+		let mut rb: RingBuffer<u32, 3> = RingBuffer::new(0);
+		assert_eq!(rb.iter().rev().count(), 0);
+
+		for item in 1..=5 {
+			rb.push_front(item);
+			let mut items = rb.iter().collect::<Vec<&u32>>();
+			items.reverse();
+			assert_eq!(rb.iter().rev().collect::<Vec<&u32>>(), items);
+			assert_eq!(rb.iter().len(), rb.len());
+		}
+	}
+
+	#[test]
+	fn iter_double_ended() {
+		let mut rb: RingBuffer<u32, 3> = RingBuffer::new(0);
+		for item in 1..=4 {
+			rb.push_front(item);
+		}
+
+		let mut iter = rb.iter();
+		assert_eq!(iter.len(), 3);
+		assert_eq!(iter.next(), Some(&2));
+		assert_eq!(iter.len(), 2);
+		assert_eq!(iter.next_back(), Some(&4));
+		assert_eq!(iter.len(), 1);
+		assert_eq!(iter.next_back(), Some(&3));
+		assert_eq!(iter.len(), 0);
+		assert_eq!(iter.next(), None);
+		assert_eq!(iter.next_back(), None);
+	}
 }
@@ -0,0 +1,150 @@
+use crate::{common::cast_to_divisor_type, ring_buffer::RingBuffer, DivisorFromCount};
+use num_traits::Float;
+
+/// Keeps track of the root mean square (RMS) of the samples in a sliding sample window of size
+/// `WINDOW_SIZE`, i.e. the square root of the average of the squared samples, which is a measure of
+/// signal power.
+///
+/// Unlike [SMA::get_rms](crate::SMA::get_rms), which sums the squared samples every time it is
+/// called, the sum of the squared samples is cached and updated in `O(1)` time whenever a sample is
+/// added or dropped, which makes reading the RMS an `O(1)` operation as well. To bound the floating
+/// point rounding error that adding and subtracting accumulates, the sum is recalculated from the
+/// samples in the sample window every `WINDOW_SIZE` added samples, and clamped to zero in between.
+/// Only floating point `Sample` types are supported.
+#[derive(Clone, Copy, Debug)]
+pub struct RmsMovingAverage<Sample, const WINDOW_SIZE: usize> {
+	samples: RingBuffer<Sample, WINDOW_SIZE>,
+	square_sum: Sample,
+	num_samples_since_recalculation: usize,
+}
+
+impl<Sample, const WINDOW_SIZE: usize> RmsMovingAverage<Sample, WINDOW_SIZE>
+where
+	Sample: Float + DivisorFromCount,
+{
+	/// Constructs a new [RmsMovingAverage] with window size `WINDOW_SIZE`.
+	pub fn new() -> Self {
+		Self {
+			samples: RingBuffer::new(Sample::zero()),
+			square_sum: Sample::zero(),
+			num_samples_since_recalculation: 0,
+		}
+	}
+
+	/// Adds a sample to the series of samples. If the sample window is full, this will cause the
+	/// oldest sample to be dropped, i.e. no longer contribute to the RMS.
+	pub fn add_sample(&mut self, new_sample: Sample) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+
+		self.square_sum = self.square_sum + new_sample * new_sample;
+		if let Some(shifted_sample) = self.samples.shift(new_sample) {
+			self.square_sum =
+				(self.square_sum - shifted_sample * shifted_sample).max(Sample::zero());
+		}
+
+		self.num_samples_since_recalculation += 1;
+		if self.num_samples_since_recalculation == WINDOW_SIZE {
+			self.square_sum = self
+				.samples
+				.iter()
+				.fold(Sample::zero(), |sum, sample| sum + *sample * *sample);
+			self.num_samples_since_recalculation = 0;
+		}
+	}
+
+	/// Returns the average of the squared samples in the sample window, or zero if it is empty.
+	pub fn get_mean_square(&self) -> Sample {
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return Sample::zero();
+		}
+
+		self.square_sum / cast_to_divisor_type::<Sample>(num_samples)
+	}
+
+	/// Returns the root mean square of the samples in the sample window, i.e. the square root of
+	/// [get_mean_square](RmsMovingAverage::get_mean_square), or zero if it is empty.
+	pub fn get_rms(&self) -> Sample {
+		self.get_mean_square().sqrt()
+	}
+
+	/// Returns the total number of samples currently in the in the sample window. This value never
+	/// exceeds the sample window size.
+	pub fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	/// Returns the maximum number of samples that fit in the sample window.
+	pub fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+}
+
+impl<Sample, const WINDOW_SIZE: usize> Default for RmsMovingAverage<Sample, WINDOW_SIZE>
+where
+	Sample: Float + DivisorFromCount,
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{SumTreeSMA, SMA};
+	use std::f64::consts::PI;
+
+	// Samples of a sine wave with amplitude 2 and a period of 8 samples
+	fn sine_sample(idx: usize) -> f64 {
+		2.0 * (2.0 * PI * idx as f64 / 8.0).sin()
+	}
+
+	#[test]
+	fn sine_wave() {
+		// Over a whole number of periods, the RMS of a sine wave is its amplitude divided by sqrt(2)
+		let mut rms = RmsMovingAverage::<f64, 16>::new();
+		let mut sma = SumTreeSMA::<f64, f64, 16>::new();
+		for idx in 0..100 {
+			rms.add_sample(sine_sample(idx));
+			sma.add_sample(sine_sample(idx));
+
+			assert!((rms.get_rms() - sma.get_rms()).abs() < 1e-12);
+			if 16 <= idx + 1 {
+				assert!((rms.get_rms() - 2.0_f64.sqrt()).abs() < 1e-12);
+				assert!((rms.get_mean_square() - 2.0).abs() < 1e-12);
+			}
+		}
+		assert_eq!(rms.get_num_samples(), 16);
+	}
+
+	#[test]
+	fn constant_and_alternating_signals() {
+		let mut rms = RmsMovingAverage::<f32, 4>::new();
+		for idx in 0..10 {
+			rms.add_sample(if idx % 2 == 0 { 3.0 } else { -3.0 });
+		}
+		assert_eq!(rms.get_rms(), 3.0);
+
+		// (4 + 16) / 2
+		let mut rms = RmsMovingAverage::<f32, 2>::new();
+		rms.add_sample(2.0);
+		rms.add_sample(4.0);
+		assert_eq!(rms.get_mean_square(), 10.0);
+	}
+
+	#[test]
+	fn edge_cases() {
+		let rms = RmsMovingAverage::<f64, 3>::new();
+		assert_eq!(rms.get_rms(), 0.0);
+		assert_eq!(rms.get_num_samples(), 0);
+
+		let mut zero_sized = RmsMovingAverage::<f64, 0>::new();
+		zero_sized.add_sample(1.0);
+		assert_eq!(zero_sized.get_rms(), 0.0);
+		assert_eq!(zero_sized.get_sample_window_size(), 0);
+	}
+}
@@ -0,0 +1,226 @@
+use super::SMA;
+use crate::{common::cast_to_divisor_type, ring_buffer::RingBuffer, DivisorFromCount, Iter};
+use num_traits::Signed;
+use std::{
+	cmp::Ordering,
+	marker::{self, PhantomData},
+	ops::Div,
+};
+
+/// An SMA implementation that rejects outliers, i.e. samples that deviate from the median of the
+/// sample window by more than `threshold` times the
+/// [median absolute deviation](https://en.wikipedia.org/wiki/Median_absolute_deviation) (MAD) of
+/// the sample window. Rejected samples never enter the sample window and do not contribute to the
+/// average.
+///
+/// Samples are only rejected once the sample window is full, as the median and MAD of just a few
+/// samples say little about what an outlier is. Note that a sustained change in the sample level
+/// that exceeds the threshold is rejected as well, for as long as it lasts. If more than half of
+/// the samples in the sample window are equal, the MAD is zero, which gives no scale to compare
+/// deviations against, so all samples are accepted until the MAD is non-zero again. Otherwise the
+/// sample window would lock up, rejecting every sample that differs from the median.
+///
+/// A sorted copy of the sample window is kept alongside the samples. Both inserting a sample into
+/// it and removing one from it shift the samples after it, and calculating the MAD allocates and
+/// partitions a vector of `N` deviations, making adding a sample an `O(N)` operation, `N` being the
+/// sample window size. Reading the average is an `O(1)` operation. Samples that are not comparable
+/// with other samples, e.g. floating point `NaN`, are not supported.
+#[derive(Clone, Debug)]
+pub struct RobustSMA<Sample, Divisor, const WINDOW_SIZE: usize> {
+	samples: RingBuffer<Sample, WINDOW_SIZE>,
+	sorted_samples: Vec<Sample>,
+	sum: Sample,
+	threshold: Sample,
+	_marker: marker::PhantomData<Divisor>,
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> SMA<Sample, Divisor, WINDOW_SIZE>
+	for RobustSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + PartialOrd + Signed + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	fn add_sample(&mut self, new_sample: Sample) {
+		if WINDOW_SIZE == 0 || self.is_outlier(new_sample) {
+			return;
+		}
+
+		if let Some(shifted_sample) = self.samples.shift(new_sample) {
+			self.sum = self.sum - shifted_sample;
+			let shifted_idx = self.find_sorted_idx(&shifted_sample);
+			self.sorted_samples.remove(shifted_idx);
+		}
+
+		self.sum = self.sum + new_sample;
+		let new_idx = self.find_sorted_idx(&new_sample);
+		self.sorted_samples.insert(new_idx, new_sample);
+	}
+
+	fn get_average(&self) -> Sample {
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return self.sum;
+		}
+
+		self.sum / cast_to_divisor_type::<Divisor>(num_samples)
+	}
+
+	fn get_most_recent_sample(&self) -> Option<Sample> {
+		self.samples.front().cloned()
+	}
+
+	fn get_oldest_sample(&self) -> Option<Sample> {
+		self.samples.back().cloned()
+	}
+
+	fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
+		self.samples.iter()
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> RobustSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + PartialOrd + Signed,
+{
+	/// Constructs a new [RobustSMA] with window size `WINDOW_SIZE`, which rejects samples that
+	/// deviate from the median of the sample window by more than `threshold` times the MAD of the
+	/// sample window. A `threshold` of around 3 is a common choice.
+	///
+	/// Note that the `Divisor` type usually cannot be derived by the compiler when using this
+	/// constructor and must be explicitly stated, even if it is the same as the `Sample` type.
+	pub fn new(threshold: Sample) -> Self {
+		Self {
+			samples: RingBuffer::new(Sample::zero()),
+			sorted_samples: Vec::with_capacity(WINDOW_SIZE),
+			sum: Sample::zero(),
+			threshold,
+			_marker: PhantomData,
+		}
+	}
+
+	/// Returns `true` if the given sample would be rejected by [add_sample](SMA::add_sample), i.e.
+	/// if the sample window is full, its MAD is non-zero and the sample deviates from its median by
+	/// more than `threshold` times its MAD.
+	pub fn is_outlier(&self, sample: Sample) -> bool {
+		if WINDOW_SIZE == 0 || self.samples.len() < WINDOW_SIZE {
+			return false;
+		}
+
+		let median = self.sorted_samples[(WINDOW_SIZE - 1) / 2];
+		let mut abs_deviations: Vec<Sample> = self
+			.sorted_samples
+			.iter()
+			.map(|sample| (*sample - median).abs())
+			.collect();
+		let median_abs_deviation = get_lower_median(&mut abs_deviations);
+
+		if median_abs_deviation.is_zero() {
+			return false;
+		}
+
+		(sample - median).abs() > self.threshold * median_abs_deviation
+	}
+
+	fn find_sorted_idx(&self, sample: &Sample) -> usize {
+		self.sorted_samples
+			.partition_point(|sorted_sample| sorted_sample < sample)
+	}
+}
+
+fn get_lower_median<Sample: Copy + PartialOrd>(samples: &mut [Sample]) -> Sample {
+	let median_idx = (samples.len() - 1) / 2;
+	*samples
+		.select_nth_unstable_by(median_idx, |a, b| {
+			a.partial_cmp(b).unwrap_or(Ordering::Equal)
+		})
+		.1
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rejects_outlier() {
+		let mut sma = RobustSMA::<f64, f64, 5>::new(3.0);
+		for sample in [10.0, 11.0, 9.0, 10.0, 12.0] {
+			sma.add_sample(sample);
+		}
+		assert_eq!(sma.get_average(), 10.4);
+
+		// Median = 10, MAD = 1
+		assert!(sma.is_outlier(1000.0));
+		assert!(sma.is_outlier(6.5));
+		assert!(!sma.is_outlier(13.0));
+
+		sma.add_sample(1000.0);
+		assert_eq!(sma.get_average(), 10.4);
+		assert_eq!(sma.get_most_recent_sample(), Some(12.0));
+
+		sma.add_sample(13.0);
+		assert_eq!(sma.get_average(), 11.0);
+		assert_eq!(
+			sma.get_sample_window_iter().collect::<Vec<&f64>>(),
+			vec![&11.0, &9.0, &10.0, &12.0, &13.0]
+		);
+	}
+
+	#[test]
+	fn accepts_all_until_full() {
+		let mut sma = RobustSMA::<i32, i32, 3>::new(1);
+		sma.add_sample(0);
+		sma.add_sample(100);
+		sma.add_sample(-100);
+		assert_eq!(sma.get_num_samples(), 3);
+		assert_eq!(sma.get_average(), 0);
+
+		// Median = 0, MAD = 100
+		sma.add_sample(50);
+		assert_eq!(sma.get_average(), 16);
+
+		// Median = 50, MAD = 50
+		sma.add_sample(-50);
+		assert_eq!(sma.get_average(), 16);
+		assert_eq!(sma.get_most_recent_sample(), Some(50));
+	}
+
+	#[test]
+	fn zero_mad_does_not_lock_up() {
+		let mut sma = RobustSMA::<i32, i32, 5>::new(3);
+		for sample in [10, 10, 10, 11, 12] {
+			sma.add_sample(sample);
+		}
+
+		// Median = 10, MAD = 0, so there is no scale to reject samples by
+		assert!(!sma.is_outlier(11));
+		assert!(!sma.is_outlier(1000));
+
+		sma.add_sample(11);
+		sma.add_sample(12);
+		assert_eq!(
+			sma.get_sample_window_iter().collect::<Vec<&i32>>(),
+			vec![&10, &11, &12, &11, &12]
+		);
+
+		// Median = 11, MAD = 1
+		assert!(sma.is_outlier(1000));
+		assert!(!sma.is_outlier(14));
+	}
+
+	#[test]
+	fn edge_case_zero_sized() {
+		let mut sma = RobustSMA::<f32, f32, 0>::new(3.0);
+		sma.add_sample(1.0);
+		assert_eq!(sma.get_num_samples(), 0);
+		assert_eq!(sma.get_average(), 0.0);
+	}
+}
@@ -0,0 +1,207 @@
+use crate::{common::cast_to_divisor_type, ring_buffer::RingBuffer, DivisorFromCount};
+use num_traits::Float;
+
+/// Keeps track of the [Pearson correlation coefficient](https://en.wikipedia.org/wiki/Pearson_correlation_coefficient)
+/// and covariance of two series of samples, over a sliding sample window of size `WINDOW_SIZE`.
+///
+/// The sums of `x`, `y`, `x²`, `y²` and `xy` over the sample window are cached, and updated in
+/// `O(1)` time whenever a pair of samples is added or dropped. To bound the floating point rounding
+/// error that adding and subtracting accumulates, the sums are recalculated from the samples in the
+/// sample window every `WINDOW_SIZE` added pairs, which makes adding a pair an amortized `O(1)`
+/// operation. Only floating point `Sample` types are supported.
+#[derive(Clone, Copy, Debug)]
+pub struct RollingCorrelation<Sample, const WINDOW_SIZE: usize> {
+	pairs: RingBuffer<(Sample, Sample), WINDOW_SIZE>,
+	sums: Sums<Sample>,
+	num_pairs_since_recalculation: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Sums<Sample> {
+	x: Sample,
+	y: Sample,
+	xx: Sample,
+	yy: Sample,
+	xy: Sample,
+}
+
+impl<Sample: Float> Sums<Sample> {
+	fn new() -> Self {
+		Self {
+			x: Sample::zero(),
+			y: Sample::zero(),
+			xx: Sample::zero(),
+			yy: Sample::zero(),
+			xy: Sample::zero(),
+		}
+	}
+
+	fn add(&mut self, (x, y): (Sample, Sample)) {
+		self.x = self.x + x;
+		self.y = self.y + y;
+		self.xx = self.xx + x * x;
+		self.yy = self.yy + y * y;
+		self.xy = self.xy + x * y;
+	}
+
+	fn sub(&mut self, (x, y): (Sample, Sample)) {
+		self.x = self.x - x;
+		self.y = self.y - y;
+		self.xx = self.xx - x * x;
+		self.yy = self.yy - y * y;
+		self.xy = self.xy - x * y;
+	}
+}
+
+impl<Sample, const WINDOW_SIZE: usize> RollingCorrelation<Sample, WINDOW_SIZE>
+where
+	Sample: Float + DivisorFromCount,
+{
+	/// Constructs a new [RollingCorrelation] with window size `WINDOW_SIZE`.
+	pub fn new() -> Self {
+		Self {
+			pairs: RingBuffer::new((Sample::zero(), Sample::zero())),
+			sums: Sums::new(),
+			num_pairs_since_recalculation: 0,
+		}
+	}
+
+	/// Adds a pair of samples, one of each series. If the sample window is full, this will cause the
+	/// oldest pair to be dropped, i.e. no longer contribute to the correlation.
+	pub fn add_pair(&mut self, x: Sample, y: Sample) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+
+		self.sums.add((x, y));
+		if let Some(shifted_pair) = self.pairs.shift((x, y)) {
+			self.sums.sub(shifted_pair);
+		}
+
+		self.num_pairs_since_recalculation += 1;
+		if self.num_pairs_since_recalculation == WINDOW_SIZE {
+			self.sums = Sums::new();
+			for pair in self.pairs.iter() {
+				self.sums.add(*pair);
+			}
+			self.num_pairs_since_recalculation = 0;
+		}
+	}
+
+	/// Returns the Pearson correlation coefficient of the pairs in the sample window, which lies in
+	/// the interval `[-1, 1]`. Returns `None` if there are fewer than two pairs in the sample window,
+	/// or if either series has zero variance, since the coefficient is undefined then.
+	pub fn get_correlation(&self) -> Option<Sample> {
+		let num_pairs = self.pairs.len();
+
+		if num_pairs < 2 {
+			return None;
+		}
+
+		let n = cast_to_divisor_type::<Sample>(num_pairs);
+		let x_spread = n * self.sums.xx - self.sums.x * self.sums.x;
+		let y_spread = n * self.sums.yy - self.sums.y * self.sums.y;
+		if x_spread <= Sample::zero() || y_spread <= Sample::zero() {
+			return None;
+		}
+
+		let correlation =
+			(n * self.sums.xy - self.sums.x * self.sums.y) / (x_spread * y_spread).sqrt();
+		Some(correlation.max(-Sample::one()).min(Sample::one()))
+	}
+
+	/// Returns the population covariance of the pairs in the sample window, or zero if it is empty.
+	pub fn get_covariance(&self) -> Sample {
+		let num_pairs = self.pairs.len();
+
+		if num_pairs == 0 {
+			return Sample::zero();
+		}
+
+		let n = cast_to_divisor_type::<Sample>(num_pairs);
+		(self.sums.xy - self.sums.x * self.sums.y / n) / n
+	}
+
+	/// Returns the total number of pairs currently in the in the sample window. This value never
+	/// exceeds the sample window size.
+	pub fn get_num_samples(&self) -> usize {
+		self.pairs.len()
+	}
+
+	/// Returns the maximum number of pairs that fit in the sample window.
+	pub fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+}
+
+impl<Sample, const WINDOW_SIZE: usize> Default for RollingCorrelation<Sample, WINDOW_SIZE>
+where
+	Sample: Float + DivisorFromCount,
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn perfect_correlation() {
+		let mut correlation = RollingCorrelation::<f64, 4>::new();
+		assert_eq!(correlation.get_correlation(), None);
+
+		correlation.add_pair(1.0, 3.0);
+		assert_eq!(correlation.get_correlation(), None);
+
+		for x in 2..10 {
+			let x = f64::from(x);
+			correlation.add_pair(x, 2.0 * x + 1.0);
+			assert!((correlation.get_correlation().unwrap() - 1.0).abs() < 1e-12);
+		}
+		assert_eq!(correlation.get_num_samples(), 4);
+
+		// Window: [(6, 13), (7, 15), (8, 17), (9, 19)]
+		assert!((correlation.get_covariance() - 2.5).abs() < 1e-12);
+	}
+
+	#[test]
+	fn perfect_anti_correlation() {
+		let mut correlation = RollingCorrelation::<f64, 5>::new();
+		for x in 0..20 {
+			let x = f64::from(x);
+			correlation.add_pair(x, 100.0 - 0.5 * x);
+		}
+		assert!((correlation.get_correlation().unwrap() + 1.0).abs() < 1e-12);
+		assert!(correlation.get_covariance() < 0.0);
+	}
+
+	#[test]
+	fn uncorrelated() {
+		let mut correlation = RollingCorrelation::<f64, 4>::new();
+		for (x, y) in [(1.0, 1.0), (-1.0, 1.0), (1.0, -1.0), (-1.0, -1.0)] {
+			correlation.add_pair(x, y);
+		}
+		assert_eq!(correlation.get_correlation(), Some(0.0));
+		assert_eq!(correlation.get_covariance(), 0.0);
+	}
+
+	#[test]
+	fn zero_variance() {
+		let mut correlation = RollingCorrelation::<f32, 3>::new();
+		for x in 0..5 {
+			correlation.add_pair(x as f32, 7.0);
+		}
+		assert_eq!(correlation.get_correlation(), None);
+	}
+
+	#[test]
+	fn edge_case_zero_sized() {
+		let mut correlation = RollingCorrelation::<f32, 0>::new();
+		correlation.add_pair(1.0, 2.0);
+		correlation.add_pair(2.0, 4.0);
+		assert_eq!(correlation.get_correlation(), None);
+		assert_eq!(correlation.get_num_samples(), 0);
+	}
+}
@@ -0,0 +1,180 @@
+use std::collections::VecDeque;
+
+/// Keeps track of the minimum and maximum values of the samples in a sliding sample window of
+/// size `WINDOW_SIZE`, e.g. for plotting an envelope around a moving average fed the same samples.
+///
+/// The extremes are maintained using two monotonic deques, one of minimum and one of maximum
+/// candidates, meaning that adding a sample is an amortized `O(1)` operation and reading the
+/// extremes is an `O(1)` operation.
+///
+/// Samples that are not comparable with other samples, e.g. floating point `NaN`, are not
+/// supported.
+#[derive(Clone, Debug)]
+pub struct RollingExtremes<Sample, const WINDOW_SIZE: usize> {
+	// Candidates are stored as (sample index, sample) pairs, oldest first
+	min_candidates: VecDeque<(usize, Sample)>,
+	max_candidates: VecDeque<(usize, Sample)>,
+	num_samples_added: usize,
+}
+
+impl<Sample: Copy + PartialOrd, const WINDOW_SIZE: usize> RollingExtremes<Sample, WINDOW_SIZE> {
+	/// Constructs a new [RollingExtremes] with window size `WINDOW_SIZE`.
+	pub fn new() -> Self {
+		Self {
+			min_candidates: VecDeque::with_capacity(WINDOW_SIZE),
+			max_candidates: VecDeque::with_capacity(WINDOW_SIZE),
+			num_samples_added: 0,
+		}
+	}
+
+	/// Adds a sample to the series of samples. If the sample window is full, this will cause the
+	/// oldest sample to be dropped, i.e. no longer be considered a minimum or maximum candidate.
+	pub fn add_sample(&mut self, new_sample: Sample) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+
+		let sample_idx = self.num_samples_added;
+		self.num_samples_added = self.num_samples_added.wrapping_add(1);
+
+		push_candidate::<_, WINDOW_SIZE>(
+			&mut self.min_candidates,
+			sample_idx,
+			new_sample,
+			Sample::le,
+		);
+		push_candidate::<_, WINDOW_SIZE>(
+			&mut self.max_candidates,
+			sample_idx,
+			new_sample,
+			Sample::ge,
+		);
+	}
+
+	/// Returns the smallest sample in the sample window, if any.
+	pub fn get_min(&self) -> Option<Sample> {
+		self.min_candidates.front().map(|(_, sample)| *sample)
+	}
+
+	/// Returns the largest sample in the sample window, if any.
+	pub fn get_max(&self) -> Option<Sample> {
+		self.max_candidates.front().map(|(_, sample)| *sample)
+	}
+
+	/// Returns the total number of samples currently in the in the sample window. This value never
+	/// exceeds the sample window size.
+	pub fn get_num_samples(&self) -> usize {
+		self.num_samples_added.min(WINDOW_SIZE)
+	}
+
+	/// Returns the maximum number of samples that fit in the sample window.
+	pub fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+}
+
+impl<Sample: Copy + PartialOrd, const WINDOW_SIZE: usize> Default
+	for RollingExtremes<Sample, WINDOW_SIZE>
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+// Adds a new sample to a monotonic deque of candidates, after removing the candidates that have
+// left the sample window and the candidates that can no longer become the extreme value, i.e.
+// those that are not `is_more_extreme` than the new sample.
+fn push_candidate<Sample: Copy, const WINDOW_SIZE: usize>(
+	candidates: &mut VecDeque<(usize, Sample)>,
+	sample_idx: usize,
+	sample: Sample,
+	is_more_extreme: impl Fn(&Sample, &Sample) -> bool,
+) {
+	while let Some((oldest_idx, _)) = candidates.front() {
+		if sample_idx.wrapping_sub(*oldest_idx) < WINDOW_SIZE {
+			break;
+		}
+		candidates.pop_front();
+	}
+
+	while let Some((_, newest_sample)) = candidates.back() {
+		if is_more_extreme(newest_sample, &sample) {
+			break;
+		}
+		candidates.pop_back();
+	}
+
+	candidates.push_back((sample_idx, sample));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn assert_matches_brute_force<const WINDOW_SIZE: usize>(samples: &[i32]) {
+		let mut extremes = RollingExtremes::<_, WINDOW_SIZE>::new();
+
+		for (idx, sample) in samples.iter().enumerate() {
+			extremes.add_sample(*sample);
+
+			let window = &samples[(idx + 1).saturating_sub(WINDOW_SIZE)..=idx];
+			assert_eq!(extremes.get_min(), window.iter().min().cloned());
+			assert_eq!(extremes.get_max(), window.iter().max().cloned());
+			assert_eq!(extremes.get_num_samples(), window.len());
+		}
+	}
+
+	#[test]
+	fn basics() {
+		let mut extremes = RollingExtremes::<u32, 3>::new();
+		assert_eq!(extremes.get_min(), None);
+		assert_eq!(extremes.get_max(), None);
+		assert_eq!(extremes.get_num_samples(), 0);
+		assert_eq!(extremes.get_sample_window_size(), 3);
+
+		extremes.add_sample(5);
+		assert_eq!(extremes.get_min(), Some(5));
+		assert_eq!(extremes.get_max(), Some(5));
+
+		extremes.add_sample(2);
+		extremes.add_sample(8);
+		assert_eq!(extremes.get_min(), Some(2));
+		assert_eq!(extremes.get_max(), Some(8));
+
+		extremes.add_sample(4);
+		assert_eq!(extremes.get_min(), Some(2));
+
+		extremes.add_sample(6);
+		assert_eq!(extremes.get_min(), Some(4));
+		assert_eq!(extremes.get_max(), Some(8));
+
+		extremes.add_sample(5);
+		assert_eq!(extremes.get_min(), Some(4));
+		assert_eq!(extremes.get_max(), Some(6));
+	}
+
+	#[test]
+	fn decreasing_then_increasing() {
+		let samples: Vec<i32> = (0..20).rev().chain(0..20).collect();
+		assert_matches_brute_force::<1>(&samples);
+		assert_matches_brute_force::<4>(&samples);
+		assert_matches_brute_force::<7>(&samples);
+		assert_matches_brute_force::<50>(&samples);
+	}
+
+	#[test]
+	fn duplicates() {
+		let samples = [3, 3, 1, 1, 3, 3, 3, 1, 2, 2, 2, 2];
+		assert_matches_brute_force::<2>(&samples);
+		assert_matches_brute_force::<3>(&samples);
+	}
+
+	#[test]
+	fn edge_case_zero_sized() {
+		let mut extremes = RollingExtremes::<u32, 0>::new();
+		extremes.add_sample(1);
+		assert_eq!(extremes.get_min(), None);
+		assert_eq!(extremes.get_max(), None);
+		assert_eq!(extremes.get_num_samples(), 0);
+	}
+}
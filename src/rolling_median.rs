@@ -0,0 +1,221 @@
+use std::{
+	cmp::Reverse,
+	collections::{BinaryHeap, VecDeque},
+};
+
+/// Keeps track of the median of the samples in a sliding sample window of size `WINDOW_SIZE`.
+/// Unlike the average, the median is robust against outliers, which makes it well suited for
+/// smoothing spiky data.
+///
+/// The samples are split into a max-heap containing the lower half of the samples and a min-heap
+/// containing the upper half. Samples that leave the sample window are removed from the heaps
+/// lazily, i.e. only once they reach the top of a heap. Adding a sample is an `O(log(N))`
+/// operation and reading the median is an `O(1)` operation.
+///
+/// The `Sample` type must implement [Ord]. Floating point samples can be used by wrapping them in
+/// a type that provides a total order.
+#[derive(Clone, Debug)]
+pub struct RollingMedian<Sample, const WINDOW_SIZE: usize> {
+	samples: VecDeque<Sample>,
+	// Heap entries are (sample, sample index) pairs, which makes every entry unique
+	lower_half: BinaryHeap<(Sample, usize)>,
+	upper_half: BinaryHeap<Reverse<(Sample, usize)>>,
+	lower_half_len: usize,
+	upper_half_len: usize,
+	num_samples_added: usize,
+}
+
+impl<Sample: Copy + Ord, const WINDOW_SIZE: usize> RollingMedian<Sample, WINDOW_SIZE> {
+	/// Constructs a new [RollingMedian] with window size `WINDOW_SIZE`.
+	pub fn new() -> Self {
+		Self {
+			samples: VecDeque::with_capacity(WINDOW_SIZE),
+			lower_half: BinaryHeap::with_capacity(WINDOW_SIZE),
+			upper_half: BinaryHeap::with_capacity(WINDOW_SIZE),
+			lower_half_len: 0,
+			upper_half_len: 0,
+			num_samples_added: 0,
+		}
+	}
+
+	/// Adds a sample to the series of samples. If the sample window is full, this will cause the
+	/// oldest sample to be dropped, i.e. no longer contribute to the median.
+	pub fn add_sample(&mut self, new_sample: Sample) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+
+		if self.samples.len() == WINDOW_SIZE {
+			let shifted_idx = self.num_samples_added - WINDOW_SIZE;
+			if let Some(shifted_sample) = self.samples.pop_front() {
+				// The top of the lower half is always a sample in the sample window
+				match self.lower_half.peek() {
+					Some(lower_top) if (shifted_sample, shifted_idx) <= *lower_top => {
+						self.lower_half_len -= 1
+					}
+					_ => self.upper_half_len -= 1,
+				}
+			}
+		}
+
+		let new_entry = (new_sample, self.num_samples_added);
+		self.samples.push_back(new_sample);
+		self.num_samples_added += 1;
+
+		match self.lower_half.peek() {
+			Some(lower_top) if new_entry > *lower_top => {
+				self.upper_half.push(Reverse(new_entry));
+				self.upper_half_len += 1;
+			}
+			_ => {
+				self.lower_half.push(new_entry);
+				self.lower_half_len += 1;
+			}
+		}
+
+		self.remove_shifted_tops();
+		self.rebalance();
+		self.compact();
+	}
+
+	/// Returns the median of the samples in the sample window, if any. If the number of samples
+	/// is even, the lower of the two middle samples is returned.
+	pub fn get_median(&self) -> Option<Sample> {
+		self.lower_half.peek().map(|(sample, _)| *sample)
+	}
+
+	/// Returns the total number of samples currently in the in the sample window. This value never
+	/// exceeds the sample window size.
+	pub fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	/// Returns the maximum number of samples that fit in the sample window.
+	pub fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+
+	fn first_sample_idx(&self) -> usize {
+		self.num_samples_added - self.samples.len()
+	}
+
+	fn rebalance(&mut self) {
+		while self.lower_half_len > self.upper_half_len + 1 {
+			if let Some(entry) = self.lower_half.pop() {
+				self.upper_half.push(Reverse(entry));
+				self.lower_half_len -= 1;
+				self.upper_half_len += 1;
+			}
+			self.remove_shifted_tops();
+		}
+
+		while self.upper_half_len > self.lower_half_len {
+			if let Some(Reverse(entry)) = self.upper_half.pop() {
+				self.lower_half.push(entry);
+				self.upper_half_len -= 1;
+				self.lower_half_len += 1;
+			}
+			self.remove_shifted_tops();
+		}
+	}
+
+	fn remove_shifted_tops(&mut self) {
+		let first_sample_idx = self.first_sample_idx();
+
+		while matches!(self.lower_half.peek(), Some((_, idx)) if *idx < first_sample_idx) {
+			self.lower_half.pop();
+		}
+
+		while matches!(self.upper_half.peek(), Some(Reverse((_, idx))) if *idx < first_sample_idx) {
+			self.upper_half.pop();
+		}
+	}
+
+	// Shifted out samples that never reach the top of a heap would otherwise accumulate
+	fn compact(&mut self) {
+		if self.lower_half.len() + self.upper_half.len() <= 2 * WINDOW_SIZE {
+			return;
+		}
+
+		let first_sample_idx = self.first_sample_idx();
+		self.lower_half.retain(|(_, idx)| first_sample_idx <= *idx);
+		self.upper_half
+			.retain(|Reverse((_, idx))| first_sample_idx <= *idx);
+	}
+}
+
+impl<Sample: Copy + Ord, const WINDOW_SIZE: usize> Default for RollingMedian<Sample, WINDOW_SIZE> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn assert_matches_sorted_window<const WINDOW_SIZE: usize>(samples: &[i32]) {
+		let mut median = RollingMedian::<_, WINDOW_SIZE>::new();
+
+		for (idx, sample) in samples.iter().enumerate() {
+			median.add_sample(*sample);
+
+			let mut window = samples[(idx + 1).saturating_sub(WINDOW_SIZE)..=idx].to_vec();
+			window.sort_unstable();
+			assert_eq!(median.get_median(), Some(window[(window.len() - 1) / 2]));
+			assert_eq!(median.get_num_samples(), window.len());
+		}
+	}
+
+	#[test]
+	fn basics() {
+		let mut median = RollingMedian::<u32, 3>::new();
+		assert_eq!(median.get_median(), None);
+		assert_eq!(median.get_sample_window_size(), 3);
+
+		median.add_sample(5);
+		assert_eq!(median.get_median(), Some(5));
+
+		median.add_sample(1);
+		assert_eq!(median.get_median(), Some(1));
+
+		median.add_sample(100);
+		assert_eq!(median.get_median(), Some(5));
+
+		median.add_sample(7);
+		assert_eq!(median.get_median(), Some(7));
+
+		median.add_sample(6);
+		assert_eq!(median.get_median(), Some(7));
+	}
+
+	#[test]
+	fn random_samples() {
+		use rand::{distributions::Uniform, rngs::SmallRng, Rng, SeedableRng};
+
+		let samples: Vec<i32> = SmallRng::seed_from_u64(0xCAFEBABE)
+			.sample_iter(&Uniform::from(-50..50))
+			.take(1000)
+			.collect();
+
+		assert_matches_sorted_window::<1>(&samples);
+		assert_matches_sorted_window::<2>(&samples);
+		assert_matches_sorted_window::<5>(&samples);
+		assert_matches_sorted_window::<16>(&samples);
+	}
+
+	#[test]
+	fn monotonic_samples() {
+		let samples: Vec<i32> = (0..50).chain((0..50).rev()).collect();
+		assert_matches_sorted_window::<5>(&samples);
+		assert_matches_sorted_window::<8>(&samples);
+	}
+
+	#[test]
+	fn edge_case_zero_sized() {
+		let mut median = RollingMedian::<u32, 0>::new();
+		median.add_sample(1);
+		assert_eq!(median.get_median(), None);
+		assert_eq!(median.get_num_samples(), 0);
+	}
+}
@@ -0,0 +1,260 @@
+use std::{cmp::Ordering, collections::VecDeque, time::Duration};
+
+/// Linear interpolation between two samples, as used by [RollingQuantile] to calculate quantiles
+/// that fall between two samples.
+pub trait Interpolate: Copy {
+	/// Returns the value at `fraction` of the way from `self` to `other`, where `fraction` lies in
+	/// the interval `[0, 1]` and `self <= other`.
+	fn interpolate(self, other: Self, fraction: f64) -> Self;
+}
+
+macro_rules! impl_interpolate_float {
+	($($float_type:ty),+) => {
+		$(
+			impl Interpolate for $float_type {
+				fn interpolate(self, other: Self, fraction: f64) -> Self {
+					self + (other - self) * fraction as $float_type
+				}
+			}
+		)+
+	};
+}
+
+macro_rules! impl_interpolate_integer {
+	($($integer_type:ty),+) => {
+		$(
+			impl Interpolate for $integer_type {
+				fn interpolate(self, other: Self, fraction: f64) -> Self {
+					// The difference may not fit in the sample type, e.g. for `i8::MIN` and
+					// `i8::MAX`, but it always fits in an i128. The offset is rounded to the nearest
+					// integer and clamped, so that the result never exceeds `other`.
+					let difference = other as i128 - self as i128;
+					let offset = ((difference as f64 * fraction).round() as i128).min(difference);
+					(self as i128 + offset) as $integer_type
+				}
+			}
+		)+
+	};
+}
+
+impl_interpolate_float!(f32, f64);
+impl_interpolate_integer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl Interpolate for Duration {
+	fn interpolate(self, other: Self, fraction: f64) -> Self {
+		self + (other - self).mul_f64(fraction)
+	}
+}
+
+/// Keeps track of arbitrary quantiles, e.g. the 95th percentile, of the samples in a sliding
+/// sample window of size `WINDOW_SIZE`.
+///
+/// The samples are kept in a sorted [Vec], alongside their insertion order, which makes adding a
+/// sample an `O(N)` operation and reading a quantile an `O(1)` operation. Quantiles that fall
+/// between two samples are linearly interpolated between them.
+///
+/// Samples that are not comparable with other samples, e.g. floating point `NaN`, are not
+/// supported.
+#[derive(Clone, Debug)]
+pub struct RollingQuantile<Sample, const WINDOW_SIZE: usize> {
+	samples: VecDeque<Sample>,
+	// Entries are (sample, sample index) pairs, which makes every entry unique
+	sorted_samples: Vec<(Sample, usize)>,
+	num_samples_added: usize,
+}
+
+impl<Sample: Copy + PartialOrd, const WINDOW_SIZE: usize> RollingQuantile<Sample, WINDOW_SIZE> {
+	/// Constructs a new [RollingQuantile] with window size `WINDOW_SIZE`.
+	pub fn new() -> Self {
+		Self {
+			samples: VecDeque::with_capacity(WINDOW_SIZE),
+			sorted_samples: Vec::with_capacity(WINDOW_SIZE),
+			num_samples_added: 0,
+		}
+	}
+
+	/// Adds a sample to the series of samples. If the sample window is full, this will cause the
+	/// oldest sample to be dropped, i.e. no longer contribute to the quantiles.
+	pub fn add_sample(&mut self, new_sample: Sample) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+
+		if self.samples.len() == WINDOW_SIZE {
+			let shifted_idx = self.num_samples_added - WINDOW_SIZE;
+			if let Some(shifted_sample) = self.samples.pop_front() {
+				let sorted_idx = self.find_sorted_idx(&(shifted_sample, shifted_idx));
+				self.sorted_samples.remove(sorted_idx);
+			}
+		}
+
+		let new_entry = (new_sample, self.num_samples_added);
+		let sorted_idx = self.find_sorted_idx(&new_entry);
+		self.sorted_samples.insert(sorted_idx, new_entry);
+		self.samples.push_back(new_sample);
+		self.num_samples_added += 1;
+	}
+
+	/// Returns the `q` quantile of the samples in the sample window, e.g. the median for a `q` of
+	/// `0.5` and the largest sample for a `q` of `1.0`, or `None` if the sample window is empty.
+	///
+	/// # Panics
+	///
+	/// Panics if `q` does not lie in the interval `[0, 1]`.
+	pub fn get_quantile(&self, q: f64) -> Option<Sample>
+	where
+		Sample: Interpolate,
+	{
+		assert!((0.0..=1.0).contains(&q), "The quantile must lie in [0, 1]");
+
+		let max_rank = self.sorted_samples.len().checked_sub(1)?;
+		let rank = q * max_rank as f64;
+		let lower_rank = rank.floor() as usize;
+		let upper_rank = rank.ceil() as usize;
+
+		let (lower_sample, _) = self.sorted_samples[lower_rank];
+		let (upper_sample, _) = self.sorted_samples[upper_rank];
+		Some(lower_sample.interpolate(upper_sample, rank - lower_rank as f64))
+	}
+
+	/// Returns the total number of samples currently in the in the sample window. This value never
+	/// exceeds the sample window size.
+	pub fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	/// Returns the maximum number of samples that fit in the sample window.
+	pub fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+
+	fn find_sorted_idx(&self, entry: &(Sample, usize)) -> usize {
+		self.sorted_samples
+			.partition_point(|sorted_entry| compare_entries(sorted_entry, entry) == Ordering::Less)
+	}
+}
+
+impl<Sample: Copy + PartialOrd, const WINDOW_SIZE: usize> Default
+	for RollingQuantile<Sample, WINDOW_SIZE>
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+fn compare_entries<Sample: PartialOrd>(a: &(Sample, usize), b: &(Sample, usize)) -> Ordering {
+	a.0.partial_cmp(&b.0)
+		.unwrap_or(Ordering::Equal)
+		.then(a.1.cmp(&b.1))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn brute_force_quantile<Sample: Interpolate + PartialOrd>(window: &[Sample], q: f64) -> Sample {
+		let mut window = window.to_vec();
+		window.sort_by(|a, b| a.partial_cmp(b).unwrap());
+		let rank = q * (window.len() - 1) as f64;
+		window[rank.floor() as usize].interpolate(window[rank.ceil() as usize], rank.fract())
+	}
+
+	fn assert_matches_brute_force<const WINDOW_SIZE: usize>(samples: &[i32]) {
+		let mut quantile = RollingQuantile::<_, WINDOW_SIZE>::new();
+
+		for (idx, sample) in samples.iter().enumerate() {
+			quantile.add_sample(*sample);
+
+			let window = &samples[(idx + 1).saturating_sub(WINDOW_SIZE)..=idx];
+			for q in [0.0, 0.5, 0.9, 1.0] {
+				assert_eq!(
+					quantile.get_quantile(q),
+					Some(brute_force_quantile(window, q))
+				);
+			}
+			assert_eq!(quantile.get_num_samples(), window.len());
+		}
+	}
+
+	#[test]
+	fn basics() {
+		let mut quantile = RollingQuantile::<f64, 5>::new();
+		assert_eq!(quantile.get_quantile(0.5), None);
+
+		for sample in [3.0, 1.0, 4.0, 1.0, 5.0, 9.0] {
+			quantile.add_sample(sample);
+		}
+
+		// Window: [1, 4, 1, 5, 9], sorted: [1, 1, 4, 5, 9]
+		assert_eq!(quantile.get_quantile(0.0), Some(1.0));
+		assert_eq!(quantile.get_quantile(0.5), Some(4.0));
+		assert_eq!(quantile.get_quantile(0.9), Some(5.0 + 0.6 * 4.0));
+		assert_eq!(quantile.get_quantile(1.0), Some(9.0));
+	}
+
+	#[test]
+	fn durations() {
+		let mut quantile = RollingQuantile::<Duration, 10>::new();
+		for millis in (1..=20).rev() {
+			quantile.add_sample(Duration::from_millis(millis));
+		}
+
+		// Window: 10 ms to 1 ms
+		assert_eq!(
+			quantile.get_quantile(0.5),
+			Some(Duration::from_micros(5500))
+		);
+		assert_eq!(
+			quantile.get_quantile(0.9),
+			Some(Duration::from_micros(9100))
+		);
+		assert_eq!(quantile.get_quantile(1.0), Some(Duration::from_millis(10)));
+	}
+
+	#[test]
+	fn random_samples_with_duplicates() {
+		use rand::{distributions::Uniform, rngs::SmallRng, Rng, SeedableRng};
+
+		let samples: Vec<i32> = SmallRng::seed_from_u64(0xCAFEBABE)
+			.sample_iter(&Uniform::from(-10..10))
+			.take(500)
+			.collect();
+
+		assert_matches_brute_force::<1>(&samples);
+		assert_matches_brute_force::<2>(&samples);
+		assert_matches_brute_force::<7>(&samples);
+		assert_matches_brute_force::<32>(&samples);
+	}
+
+	#[test]
+	fn interpolate_extreme_spread() {
+		assert_eq!((-100_i8).interpolate(100, 0.25), -50);
+		assert_eq!((-100_i8).interpolate(100, 0.5), 0);
+		assert_eq!(i8::MIN.interpolate(i8::MAX, 1.0), i8::MAX);
+		assert_eq!(i64::MIN.interpolate(i64::MAX, 0.0), i64::MIN);
+		assert_eq!(i64::MIN.interpolate(i64::MAX, 1.0), i64::MAX);
+		assert_eq!(0_u64.interpolate(u64::MAX, 1.0), u64::MAX);
+
+		let mut quantile = RollingQuantile::<i8, 2>::new();
+		quantile.add_sample(-100);
+		quantile.add_sample(100);
+		assert_eq!(quantile.get_quantile(0.5), Some(0));
+		assert_eq!(quantile.get_quantile(0.75), Some(50));
+	}
+
+	#[test]
+	#[should_panic]
+	fn invalid_quantile() {
+		let mut quantile = RollingQuantile::<u32, 3>::new();
+		quantile.add_sample(1);
+		quantile.get_quantile(1.5);
+	}
+
+	#[test]
+	fn edge_case_zero_sized() {
+		let mut quantile = RollingQuantile::<u32, 0>::new();
+		quantile.add_sample(1);
+		assert_eq!(quantile.get_quantile(0.5), None);
+		assert_eq!(quantile.get_num_samples(), 0);
+	}
+}
@@ -0,0 +1,90 @@
+use num_traits::PrimInt;
+
+/// How [SMA::get_rounded_average](crate::SMA::get_rounded_average) rounds the average of integer
+/// samples, whose sum generally isn't divisible by the number of samples.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+	/// Rounds towards zero, discarding the fractional part, just like integer division does. This
+	/// is what [SMA::get_average](crate::SMA::get_average) does for integer samples.
+	Truncate,
+	/// Rounds to the nearest integer, and halfway cases towards positive infinity, e.g. `1.5` to `2`
+	/// and `-1.5` to `-1`.
+	HalfUp,
+	/// Rounds to the nearest integer, and halfway cases to the nearest even integer, e.g. `1.5` and
+	/// `2.5` to `2` and `-1.5` to `-2`. This avoids the upwards bias of [RoundingMode::HalfUp].
+	HalfToEven,
+}
+
+// Divides `dividend` by the positive `divisor` without ever calculating anything larger than the
+// operands, so it does not overflow where a plain division would not.
+pub(crate) fn divide_rounded<Sample: PrimInt>(
+	dividend: Sample,
+	divisor: Sample,
+	rounding_mode: RoundingMode,
+) -> Sample {
+	let quotient = dividend / divisor;
+	let remainder = dividend % divisor;
+
+	if rounding_mode == RoundingMode::Truncate || remainder == Sample::zero() {
+		return quotient;
+	}
+
+	let is_negative = remainder < Sample::zero();
+	let remainder = if is_negative {
+		Sample::zero() - remainder
+	} else {
+		remainder
+	};
+	let away_from_zero = if is_negative {
+		quotient - Sample::one()
+	} else {
+		quotient + Sample::one()
+	};
+
+	match remainder.cmp(&(divisor - remainder)) {
+		std::cmp::Ordering::Less => quotient,
+		std::cmp::Ordering::Greater => away_from_zero,
+		std::cmp::Ordering::Equal => match rounding_mode {
+			RoundingMode::HalfUp if !is_negative => away_from_zero,
+			RoundingMode::HalfToEven if quotient & Sample::one() == Sample::one() => away_from_zero,
+			_ => quotient,
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rounding_modes() {
+		use RoundingMode::*;
+
+		let cases: [(i32, i32, i32, i32, i32); 9] = [
+			// (dividend, divisor, truncated, half up, half to even)
+			(3, 2, 1, 2, 2),
+			(5, 2, 2, 3, 2),
+			(5, 3, 1, 2, 2),
+			(4, 3, 1, 1, 1),
+			(-3, 2, -1, -1, -2),
+			(-5, 2, -2, -2, -2),
+			(-5, 3, -1, -2, -2),
+			(-4, 3, -1, -1, -1),
+			(6, 3, 2, 2, 2),
+		];
+
+		for (dividend, divisor, truncated, half_up, half_to_even) in cases {
+			assert_eq!(divide_rounded(dividend, divisor, Truncate), truncated);
+			assert_eq!(divide_rounded(dividend, divisor, HalfUp), half_up);
+			assert_eq!(divide_rounded(dividend, divisor, HalfToEven), half_to_even);
+		}
+	}
+
+	#[test]
+	fn no_overflow() {
+		assert_eq!(divide_rounded(i8::MAX, 2, RoundingMode::HalfUp), 64);
+		assert_eq!(divide_rounded(i8::MIN, 3, RoundingMode::HalfToEven), -43);
+		assert_eq!(divide_rounded(u8::MAX, 100, RoundingMode::HalfUp), 3);
+		assert_eq!(divide_rounded(u8::MAX, 2, RoundingMode::HalfToEven), 128);
+	}
+}
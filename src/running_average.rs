@@ -0,0 +1,89 @@
+use crate::{DivisorFromCount, SumTreeSMA, SMA};
+use num_traits::Zero;
+use std::{
+	fmt,
+	ops::{Add, Div},
+};
+
+/// Extends all iterators with the [running_average](SMAIterExt::running_average) adapter.
+pub trait SMAIterExt: Iterator + Sized {
+	/// Turns an iterator of samples into an iterator of simple moving averages, yielding the
+	/// average of the last (up to) `WINDOW_SIZE` samples for every sample yielded by the original
+	/// iterator.
+	///
+	/// Note that the `Divisor` type usually cannot be derived by the compiler and must be explicitly
+	/// stated, even if it is the same as the `Sample` type.
+	fn running_average<Divisor, const WINDOW_SIZE: usize>(
+		self,
+	) -> RunningAverage<Self, Divisor, WINDOW_SIZE>
+	where
+		Self::Item: Copy + Zero + Add<Output = Self::Item> + Div<Divisor, Output = Self::Item>,
+		Divisor: DivisorFromCount,
+	{
+		RunningAverage {
+			iter: self,
+			sma: SumTreeSMA::new(),
+		}
+	}
+}
+
+impl<I: Iterator> SMAIterExt for I {}
+
+/// An iterator that yields the simple moving average of the samples yielded by another iterator.
+/// Created by the [running_average](SMAIterExt::running_average) method.
+#[derive(Clone)]
+pub struct RunningAverage<I: Iterator, Divisor, const WINDOW_SIZE: usize> {
+	iter: I,
+	sma: SumTreeSMA<I::Item, Divisor, WINDOW_SIZE>,
+}
+
+impl<I, Divisor, const WINDOW_SIZE: usize> Iterator for RunningAverage<I, Divisor, WINDOW_SIZE>
+where
+	I: Iterator,
+	I::Item: Copy + Add<Output = I::Item> + Div<Divisor, Output = I::Item>,
+	Divisor: DivisorFromCount,
+{
+	type Item = I::Item;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let sample = self.iter.next()?;
+		self.sma.add_sample(sample);
+		Some(self.sma.get_average())
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.iter.size_hint()
+	}
+}
+
+impl<I, Divisor, const WINDOW_SIZE: usize> fmt::Debug for RunningAverage<I, Divisor, WINDOW_SIZE>
+where
+	I: Iterator + fmt::Debug,
+	I::Item: Copy + Add<Output = I::Item> + Div<Divisor, Output = I::Item> + fmt::Debug,
+	Divisor: DivisorFromCount,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("RunningAverage")
+			.field("iter", &self.iter)
+			.field("sma", &self.sma)
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn running_average() {
+		let averages: Vec<f64> = (1..=5).map(f64::from).running_average::<f64, 3>().collect();
+
+		assert_eq!(averages, vec![1.0, 1.5, 2.0, 3.0, 4.0]);
+	}
+
+	#[test]
+	fn empty_iter() {
+		let mut averages = std::iter::empty::<u32>().running_average::<u32, 3>();
+		assert_eq!(averages.next(), None);
+	}
+}
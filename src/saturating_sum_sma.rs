@@ -0,0 +1,186 @@
+use super::SMA;
+use crate::{common::cast_to_divisor_type, ring_buffer::RingBuffer, DivisorFromCount, Iter};
+use num_traits::{CheckedAdd, CheckedSub, SaturatingAdd, Zero};
+use std::{
+	marker::{self, PhantomData},
+	ops::Div,
+};
+
+/// An SMA implementation that, like [SingleSumSMA](crate::SingleSumSMA), caches the sum of all
+/// samples currently in the sample window as a single value, but clamps that sum to the range of
+/// the `Sample` type instead of overflowing.
+///
+/// As long as the sum of the samples in the sample window fits in the `Sample` type, adding a
+/// sample is an `O(1)` operation. While it does not, the clamped sum is recalculated from the
+/// samples in the sample window every time a sample is added, making that an `O(N)` operation.
+/// This ensures that the sum becomes exact again as soon as the saturating samples have left the
+/// sample window. The cached sum is always the exact sum of the samples in the sample window,
+/// clamped to the range of the `Sample` type, regardless of the order of positive and negative
+/// samples.
+#[derive(Clone, Copy, Debug)]
+pub struct SaturatingSumSMA<Sample, Divisor, const WINDOW_SIZE: usize> {
+	samples: RingBuffer<Sample, WINDOW_SIZE>,
+	sum: Sample,
+	is_saturated: bool,
+	_marker: marker::PhantomData<Divisor>,
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> SMA<Sample, Divisor, WINDOW_SIZE>
+	for SaturatingSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy
+		+ Zero
+		+ PartialOrd
+		+ CheckedAdd
+		+ CheckedSub
+		+ SaturatingAdd
+		+ Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	fn add_sample(&mut self, new_sample: Sample) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+
+		let shifted_sample = self.samples.shift(new_sample);
+
+		if !self.is_saturated {
+			let sum = match shifted_sample {
+				Some(shifted_sample) => self.sum.checked_sub(&shifted_sample),
+				None => Some(self.sum),
+			}
+			.and_then(|sum| sum.checked_add(&new_sample));
+
+			if let Some(sum) = sum {
+				self.sum = sum;
+				return;
+			}
+		}
+
+		self.recalculate_sum();
+	}
+
+	fn get_average(&self) -> Sample {
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return self.sum;
+		}
+
+		self.sum / cast_to_divisor_type(num_samples)
+	}
+
+	fn get_most_recent_sample(&self) -> Option<Sample> {
+		self.samples.front().cloned()
+	}
+
+	fn get_oldest_sample(&self) -> Option<Sample> {
+		self.samples.back().cloned()
+	}
+
+	fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
+		self.samples.iter()
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> SaturatingSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Zero + PartialOrd + CheckedAdd + SaturatingAdd,
+{
+	/// Returns `true` if the sum of the samples in the sample window is currently clamped to the
+	/// range of the `Sample` type, meaning that [get_average](SMA::get_average) is not exact.
+	pub fn is_saturated(&self) -> bool {
+		self.is_saturated
+	}
+
+	// Adding a sample to a sum of the opposite sign never overflows, so the samples are added in an
+	// order that alternates between the signs for as long as possible. This keeps the intermediate
+	// sums in range, unless the sum of all samples is out of range, in which case only samples that
+	// push it further out of range remain. Clamping is then equivalent to clamping the exact sum.
+	fn recalculate_sum(&mut self) {
+		let zero = Sample::zero();
+		let mut non_negative_samples = self.samples.iter().filter(|sample| zero <= **sample);
+		let mut negative_samples = self.samples.iter().filter(|sample| **sample < zero);
+
+		let mut sum = zero;
+		let mut is_saturated = false;
+		loop {
+			let sample = if sum < zero {
+				non_negative_samples
+					.next()
+					.or_else(|| negative_samples.next())
+			} else {
+				negative_samples
+					.next()
+					.or_else(|| non_negative_samples.next())
+			};
+
+			let sample = match sample {
+				Some(sample) => sample,
+				None => break,
+			};
+
+			sum = match sum.checked_add(sample) {
+				Some(sum) => sum,
+				None => {
+					is_saturated = true;
+					sum.saturating_add(sample)
+				}
+			};
+		}
+
+		self.sum = sum;
+		self.is_saturated = is_saturated;
+	}
+}
+
+impl<Sample: Copy + Zero, Divisor, const WINDOW_SIZE: usize>
+	SaturatingSumSMA<Sample, Divisor, WINDOW_SIZE>
+{
+	/// Constructs a new [SaturatingSumSMA] with window size `WINDOW_SIZE`.
+	///
+	/// Note that the `Divisor` type usually cannot be derived by the compiler when using this
+	/// constructor and must be explicitly stated, even if it is the same as the `Sample` type.
+	pub fn new() -> Self {
+		Self {
+			samples: RingBuffer::new(Sample::zero()),
+			sum: Sample::zero(),
+			is_saturated: false,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<Sample: Copy + Zero, Divisor, const WINDOW_SIZE: usize> Default
+	for SaturatingSumSMA<Sample, Divisor, WINDOW_SIZE>
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> PartialEq
+	for SaturatingSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + PartialEq,
+{
+	/// Two instances are equal if their sample windows contain the same samples, in the same order.
+	fn eq(&self, other: &Self) -> bool {
+		self.samples.iter().eq(other.samples.iter())
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> Eq
+	for SaturatingSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Eq,
+{
+}
@@ -0,0 +1,268 @@
+use super::SMA;
+use crate::{common::cast_to_divisor_type, ring_buffer::RingBuffer, DivisorFromCount, Iter};
+use num_traits::{Float, Zero};
+use std::ops::{Add, Div, Mul};
+
+/// A [Savitzky-Golay filter](https://en.wikipedia.org/wiki/Savitzky%E2%80%93Golay_filter), which
+/// fits a polynomial of a given degree to the samples in the sample window, using least squares,
+/// and returns the value of that polynomial at the center of the sample window. Compared to a plain
+/// SMA, this preserves the height and shape of peaks in the samples much better.
+///
+/// Fitting the polynomial and evaluating it at the center of the sample window is equivalent to a
+/// weighted sum of the samples, whose weights (the convolution coefficients) only depend on the
+/// window size and the degree. They are calculated once, when the instance is constructed, so
+/// adding a sample is an `O(1)` operation and reading the average, i.e. the smoothed center value,
+/// is an `O(N)` operation. Until the sample window is full, the plain average of the samples is
+/// returned instead.
+///
+/// Note that the smoothed value lags `(WINDOW_SIZE - 1) / 2` samples behind the most recent sample.
+#[derive(Clone, Copy, Debug)]
+pub struct SavitzkyGolayFilter<Sample, Divisor, const WINDOW_SIZE: usize> {
+	samples: RingBuffer<Sample, WINDOW_SIZE>,
+	coefficients: [Divisor; WINDOW_SIZE],
+	zero: Sample,
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> SMA<Sample, Divisor, WINDOW_SIZE>
+	for SavitzkyGolayFilter<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample:
+		Copy + Add<Output = Sample> + Mul<Divisor, Output = Sample> + Div<Divisor, Output = Sample>,
+	Divisor: Float + DivisorFromCount,
+{
+	fn add_sample(&mut self, new_sample: Sample) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+		self.samples.push_front(new_sample);
+	}
+
+	fn get_average(&self) -> Sample {
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return self.zero;
+		}
+
+		if num_samples < WINDOW_SIZE {
+			let sum = self
+				.samples
+				.iter()
+				.fold(self.zero, |sum, sample| sum + *sample);
+			return sum / cast_to_divisor_type::<Divisor>(num_samples);
+		}
+
+		self.samples
+			.iter()
+			.zip(self.coefficients.iter())
+			.fold(self.zero, |sum, (sample, coefficient)| {
+				sum + *sample * *coefficient
+			})
+	}
+
+	fn get_most_recent_sample(&self) -> Option<Sample> {
+		self.samples.front().cloned()
+	}
+
+	fn get_oldest_sample(&self) -> Option<Sample> {
+		self.samples.back().cloned()
+	}
+
+	fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
+		self.samples.iter()
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> SavitzkyGolayFilter<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy,
+	Divisor: Float + DivisorFromCount,
+{
+	/// Constructs a new [SavitzkyGolayFilter] with window size `WINDOW_SIZE`, which fits a
+	/// polynomial of degree `degree` to the samples. This constructor is only available for
+	/// `Sample` types that implement [num_traits::Zero]. If the `Sample` type does not, use the
+	/// [with_degree_from_zero](SavitzkyGolayFilter::with_degree_from_zero) constructor instead.
+	///
+	/// # Panics
+	///
+	/// Panics if `degree` is not smaller than `WINDOW_SIZE`.
+	pub fn with_degree(degree: usize) -> Self
+	where
+		Sample: Zero,
+	{
+		Self::with_degree_from_zero(Sample::zero(), degree)
+	}
+
+	/// Constructs a new [SavitzkyGolayFilter] with window size `WINDOW_SIZE` from the given `zero`
+	/// sample, which fits a polynomial of degree `degree` to the samples.
+	///
+	/// # Panics
+	///
+	/// Panics if `degree` is not smaller than `WINDOW_SIZE`.
+	pub fn with_degree_from_zero(zero: Sample, degree: usize) -> Self {
+		assert!(
+			degree < WINDOW_SIZE,
+			"The polynomial degree must be smaller than the sample window size"
+		);
+
+		Self {
+			samples: RingBuffer::new(zero),
+			coefficients: calculate_coefficients(degree),
+			zero,
+		}
+	}
+
+	/// Returns the convolution coefficients that the samples are multiplied by, oldest sample
+	/// window position first. The coefficients sum to one.
+	pub fn get_coefficients(&self) -> &[Divisor; WINDOW_SIZE] {
+		&self.coefficients
+	}
+}
+
+// The coefficients are the first row of `(J^T J)^-1 J^T`, where `J` is the Vandermonde matrix of
+// the sample window positions, which are relative to the center of the sample window. They are
+// scaled to [-1, 1], to keep the normal matrix `J^T J` well conditioned.
+fn calculate_coefficients<Divisor, const WINDOW_SIZE: usize>(
+	degree: usize,
+) -> [Divisor; WINDOW_SIZE]
+where
+	Divisor: Float + DivisorFromCount,
+{
+	let num_terms = degree + 1;
+	let center =
+		cast_to_divisor_type::<Divisor>(WINDOW_SIZE - 1) / (Divisor::one() + Divisor::one());
+	let scale = center.max(Divisor::one());
+	let positions: Vec<Divisor> = (0..WINDOW_SIZE)
+		.map(|idx| (cast_to_divisor_type::<Divisor>(idx) - center) / scale)
+		.collect();
+
+	// Augmented normal matrix [J^T J | e_0]
+	let mut matrix: Vec<Vec<Divisor>> = (0..num_terms)
+		.map(|row| {
+			let mut matrix_row: Vec<Divisor> = (0..num_terms)
+				.map(|col| {
+					positions.iter().fold(Divisor::zero(), |sum, position| {
+						sum + position.powi((row + col) as i32)
+					})
+				})
+				.collect();
+			matrix_row.push(if row == 0 {
+				Divisor::one()
+			} else {
+				Divisor::zero()
+			});
+			matrix_row
+		})
+		.collect();
+
+	// Gauss-Jordan elimination with partial pivoting
+	for col in 0..num_terms {
+		let pivot_row = (col..num_terms)
+			.max_by(|a, b| {
+				matrix[*a][col]
+					.abs()
+					.partial_cmp(&matrix[*b][col].abs())
+					.unwrap()
+			})
+			.unwrap();
+		matrix.swap(col, pivot_row);
+
+		let pivot = matrix[col][col];
+		for value in matrix[col].iter_mut() {
+			*value = *value / pivot;
+		}
+
+		let pivot_values = matrix[col].clone();
+		for (row_idx, row) in matrix.iter_mut().enumerate() {
+			if row_idx != col {
+				let factor = row[col];
+				for (value, pivot_value) in row.iter_mut().zip(pivot_values.iter()) {
+					*value = *value - factor * *pivot_value;
+				}
+			}
+		}
+	}
+
+	let mut coefficients = [Divisor::zero(); WINDOW_SIZE];
+	for (coefficient, position) in coefficients.iter_mut().zip(positions) {
+		*coefficient = matrix
+			.iter()
+			.enumerate()
+			.fold(Divisor::zero(), |sum, (power, row)| {
+				sum + row[num_terms] * position.powi(power as i32)
+			});
+	}
+	coefficients
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn coefficients() {
+		let sgf = SavitzkyGolayFilter::<f64, f64, 5>::with_degree(2);
+		let expected = [-3.0, 12.0, 17.0, 12.0, -3.0].map(|c: f64| c / 35.0);
+		for (coefficient, expected) in sgf.get_coefficients().iter().zip(expected) {
+			assert!((coefficient - expected).abs() < 1e-12);
+		}
+
+		let sgf = SavitzkyGolayFilter::<f64, f64, 7>::with_degree(3);
+		let expected = [-2.0, 3.0, 6.0, 7.0, 6.0, 3.0, -2.0].map(|c: f64| c / 21.0);
+		for (coefficient, expected) in sgf.get_coefficients().iter().zip(expected) {
+			assert!((coefficient - expected).abs() < 1e-12);
+		}
+
+		// A polynomial of degree zero is fitted by the plain average
+		let sgf = SavitzkyGolayFilter::<f32, f32, 4>::with_degree(0);
+		for coefficient in sgf.get_coefficients() {
+			assert!((coefficient - 0.25).abs() < 1e-6);
+		}
+	}
+
+	#[test]
+	fn noisy_parabola() {
+		let parabola = |x: f64| 3.0 - 0.5 * x + 0.25 * x * x;
+		let noise = [
+			0.03, -0.02, 0.01, -0.04, 0.02, 0.0, -0.01, 0.04, -0.03, 0.02, -0.01,
+		];
+
+		let mut sgf = SavitzkyGolayFilter::<f64, f64, 11>::with_degree(2);
+		for (x, noise) in noise.iter().enumerate() {
+			sgf.add_sample(parabola(x as f64) + noise);
+		}
+
+		// The window center is at x = 5
+		assert!((sgf.get_average() - parabola(5.0)).abs() < 0.02);
+
+		let mut sma = crate::NoSumSMA::<f64, f64, 11>::new();
+		for (x, noise) in noise.iter().enumerate() {
+			sma.add_sample(parabola(x as f64) + noise);
+		}
+		assert!((sma.get_average() - parabola(5.0)).abs() > 1.0);
+	}
+
+	#[test]
+	fn partial_window() {
+		let mut sgf = SavitzkyGolayFilter::<f32, f32, 5>::with_degree(2);
+		assert_eq!(sgf.get_average(), 0.0);
+
+		sgf.add_sample(2.0);
+		sgf.add_sample(4.0);
+		assert_eq!(sgf.get_average(), 3.0);
+	}
+
+	#[test]
+	#[should_panic]
+	fn degree_too_large() {
+		SavitzkyGolayFilter::<f64, f64, 3>::with_degree(3);
+	}
+}
@@ -0,0 +1,107 @@
+use crate::SMA;
+use std::sync::{Mutex, MutexGuard};
+
+/// A thread-safe wrapper around any [SMA] implementation, allowing samples to be added and the
+/// average to be read through a shared reference, e.g. from several threads via an
+/// [Arc](std::sync::Arc).
+///
+/// Every method call locks an internal [Mutex] for its whole duration, so concurrent calls are
+/// serialized. Adding a sample and reading the average are both short operations for all
+/// implementations except [NoSumSMA](crate::NoSumSMA), whose `O(N)` average calculation holds
+/// the lock correspondingly longer. A [Mutex] rather than an `RwLock` is used, since writes are
+/// typically at least as common as reads and the wrapped implementation is then only required to
+/// be [Send], not [Sync], for the wrapper to be [Sync].
+///
+/// # Panics
+///
+/// All methods panic if another thread panicked while holding the lock.
+#[derive(Debug, Default)]
+pub struct SharedSMA<M> {
+	sma: Mutex<M>,
+}
+
+impl<M> SharedSMA<M> {
+	/// Wraps the given [SMA] implementation.
+	pub fn new(sma: M) -> Self {
+		Self {
+			sma: Mutex::new(sma),
+		}
+	}
+
+	/// Adds a sample to the series of samples. See [SMA::add_sample].
+	pub fn add_sample<Sample, Divisor, const WINDOW_SIZE: usize>(&self, new_sample: Sample)
+	where
+		M: SMA<Sample, Divisor, WINDOW_SIZE>,
+	{
+		self.lock().add_sample(new_sample);
+	}
+
+	/// Returns the simple moving average value of all the samples in the sample window. See
+	/// [SMA::get_average].
+	pub fn get_average<Sample, Divisor, const WINDOW_SIZE: usize>(&self) -> Sample
+	where
+		M: SMA<Sample, Divisor, WINDOW_SIZE>,
+	{
+		self.lock().get_average()
+	}
+
+	/// Returns the total number of samples currently in the in the sample window. See
+	/// [SMA::get_num_samples].
+	pub fn get_num_samples<Sample, Divisor, const WINDOW_SIZE: usize>(&self) -> usize
+	where
+		M: SMA<Sample, Divisor, WINDOW_SIZE>,
+	{
+		self.lock().get_num_samples()
+	}
+
+	/// Locks the wrapped [SMA] implementation, giving access to its full interface for as long as
+	/// the returned guard is held.
+	pub fn lock(&self) -> MutexGuard<'_, M> {
+		self.sma.lock().unwrap()
+	}
+
+	/// Consumes the wrapper, returning the wrapped [SMA] implementation.
+	pub fn into_inner(self) -> M {
+		self.sma.into_inner().unwrap()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::SingleSumSMA;
+	use std::{sync::Arc, thread};
+
+	#[test]
+	fn concurrent_add_sample() {
+		let shared_sma = Arc::new(SharedSMA::new(SingleSumSMA::<u64, u64, 400>::from_zero(0)));
+
+		let threads: Vec<_> = (0..4)
+			.map(|thread_idx| {
+				let shared_sma = Arc::clone(&shared_sma);
+				thread::spawn(move || {
+					for _ in 0..100 {
+						shared_sma.add_sample(thread_idx);
+					}
+				})
+			})
+			.collect();
+
+		for thread in threads {
+			thread.join().unwrap();
+		}
+
+		assert_eq!(shared_sma.get_num_samples(), 400);
+		// (100 * 0 + 100 * 1 + 100 * 2 + 100 * 3) / 400
+		assert_eq!(shared_sma.get_average(), 1);
+		assert_eq!(shared_sma.lock().get_sample_window_size(), 400);
+	}
+
+	#[test]
+	fn into_inner() {
+		let shared_sma = SharedSMA::new(SingleSumSMA::<f32, f32, 2>::from_zero(0.0));
+		shared_sma.add_sample(1.0);
+		shared_sma.add_sample(2.0);
+		assert_eq!(shared_sma.into_inner().get_average(), 1.5);
+	}
+}
@@ -1,17 +1,33 @@
 use super::SMA;
-use crate::{common::cast_to_divisor_type, ring_buffer::RingBuffer, Iter};
-use num_traits::{FromPrimitive, Zero};
+use crate::{
+	common::cast_to_divisor_type, ring_buffer::RingBuffer, DivisorFromCount, IntoIter, Iter,
+	Snapshot,
+};
+#[cfg(feature = "num-traits")]
+use crate::{rounding_mode::divide_rounded, OverflowError, RoundingMode, WindowSizeError};
+#[cfg(feature = "num-traits")]
+use num_traits::{CheckedAdd, CheckedSub, PrimInt, Zero};
 use std::{
+	any::type_name,
+	fmt,
+	hash::{Hash, Hasher},
 	marker::{self, PhantomData},
+	mem::size_of,
 	ops::{AddAssign, Div, SubAssign},
 };
+#[cfg(feature = "num-traits")]
+use std::{convert::TryFrom, iter::FromIterator};
 
 /// An SMA implementation that caches the sum of all samples currently in the sample window as a
 /// single value.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy)]
 pub struct SingleSumSMA<Sample, Divisor, const WINDOW_SIZE: usize> {
 	samples: RingBuffer<Sample, WINDOW_SIZE>,
 	sum: Sample,
+	// Incremented whenever the sample window is modified
+	generation: u64,
+	// The number of samples that get_average_if_ready requires
+	min_samples: usize,
 	_marker: marker::PhantomData<Divisor>,
 }
 
@@ -19,7 +35,7 @@ impl<Sample, Divisor, const WINDOW_SIZE: usize> SMA<Sample, Divisor, WINDOW_SIZE
 	for SingleSumSMA<Sample, Divisor, WINDOW_SIZE>
 where
 	Sample: Copy + AddAssign + SubAssign + Div<Divisor, Output = Sample>,
-	Divisor: FromPrimitive,
+	Divisor: DivisorFromCount,
 {
 	fn add_sample(&mut self, new_sample: Sample) {
 		if WINDOW_SIZE == 0 {
@@ -31,6 +47,7 @@ where
 		if let Some(shifted_sample) = self.samples.shift(new_sample) {
 			self.sum -= shifted_sample;
 		}
+		self.generation += 1;
 	}
 
 	fn get_average(&self) -> Sample {
@@ -43,10 +60,48 @@ where
 		self.sum / cast_to_divisor_type(num_samples)
 	}
 
+	#[cfg(feature = "num-traits")]
+	fn get_average_as<T>(&self) -> T
+	where
+		Self: Sized,
+		Sample: Copy + Into<T>,
+		T: Zero + Div<Output = T> + DivisorFromCount,
+	{
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return T::zero();
+		}
+
+		self.sum.into() / cast_to_divisor_type::<T>(num_samples)
+	}
+
+	#[cfg(feature = "num-traits")]
+	fn get_rounded_average(&self, rounding_mode: RoundingMode) -> Sample
+	where
+		Sample: PrimInt + DivisorFromCount,
+	{
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return Sample::zero();
+		}
+
+		divide_rounded(
+			self.sum,
+			cast_to_divisor_type::<Sample>(num_samples),
+			rounding_mode,
+		)
+	}
+
 	fn get_most_recent_sample(&self) -> Option<Sample> {
 		self.samples.front().cloned()
 	}
 
+	fn get_oldest_sample(&self) -> Option<Sample> {
+		self.samples.back().cloned()
+	}
+
 	fn get_num_samples(&self) -> usize {
 		self.samples.len()
 	}
@@ -55,14 +110,17 @@ where
 		WINDOW_SIZE
 	}
 
-	fn get_sample_window_iter(&self) -> Iter<Sample, WINDOW_SIZE> {
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
 		self.samples.iter()
 	}
 }
 
+#[cfg(feature = "num-traits")]
 impl<Sample: Copy + Zero, Divisor, const WINDOW_SIZE: usize>
 	SingleSumSMA<Sample, Divisor, WINDOW_SIZE>
 {
+	const NONZERO_WINDOW_SIZE_CHECK: () = assert!(0 < WINDOW_SIZE, "WINDOW_SIZE must be non-zero");
+
 	/// Constructs a new [SingleSumSMA] with window size `WINDOW_SIZE`. This constructor is
 	/// only available for `Sample` types that implement [num_traits::Zero]. If the `Sample` type
 	/// does not, use the [from_zero](SingleSumSMA::from_zero) constructor instead.
@@ -73,20 +131,455 @@ impl<Sample: Copy + Zero, Divisor, const WINDOW_SIZE: usize>
 		Self {
 			samples: RingBuffer::new(Sample::zero()),
 			sum: Sample::zero(),
+			generation: 0,
+			min_samples: 0,
 			_marker: PhantomData,
 		}
 	}
+
+	/// Constructs a new [SingleSumSMA] with window size `WINDOW_SIZE`, just like [new](SingleSumSMA::new),
+	/// except that a `WINDOW_SIZE` of zero fails to compile, rather than resulting in an SMA that
+	/// ignores all samples.
+	///
+	/// ```compile_fail
+	/// # use simple_moving_average::SingleSumSMA;
+	/// let sma = SingleSumSMA::<f32, f32, 0>::new_nonzero();
+	/// ```
+	pub fn new_nonzero() -> Self {
+		let () = Self::NONZERO_WINDOW_SIZE_CHECK;
+
+		Self::new()
+	}
+}
+
+#[cfg(feature = "num-traits")]
+impl<Sample: Copy + Zero, Divisor, const WINDOW_SIZE: usize> Default
+	for SingleSumSMA<Sample, Divisor, WINDOW_SIZE>
+{
+	fn default() -> Self {
+		Self::new()
+	}
 }
 
 impl<Sample: Copy, Divisor, const WINDOW_SIZE: usize> SingleSumSMA<Sample, Divisor, WINDOW_SIZE> {
+	/// The sample window size, as a constant that can be used in const contexts, e.g. to size an
+	/// array. Equal to [get_sample_window_size](SMA::get_sample_window_size).
+	pub const WINDOW_SIZE: usize = WINDOW_SIZE;
+
 	/// Constructs a new [SingleSumSMA] with window size `WINDOW_SIZE` from the given
 	/// `zero` sample. If the `Sample` type implements [num_traits::Zero], the
 	/// [new](SingleSumSMA::new) constructor might be preferable to this.
-	pub fn from_zero(zero: Sample) -> Self {
+	///
+	/// This constructor is a `const fn`, so it can be used to construct a [SingleSumSMA] in a `const`
+	/// or `static` item, without any heap allocation.
+	pub const fn from_zero(zero: Sample) -> Self {
 		Self {
 			samples: RingBuffer::new(zero),
 			sum: zero,
+			generation: 0,
+			min_samples: 0,
 			_marker: PhantomData,
 		}
 	}
 }
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> SingleSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + AddAssign + SubAssign + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	/// Returns the average of the samples that are in the sample window of `self`, but not in
+	/// that of `other`, assuming that the sample window of `other` contains the most recent
+	/// samples of the sample window of `self`, e.g. because both were fed the same samples. This is
+	/// calculated in `O(1)` time from the cached sums.
+	///
+	/// Only the sample counts are checked, not the samples themselves: `None` is returned if
+	/// `other` does not contain fewer samples than `self`.
+	pub fn get_difference_average<const OTHER_WINDOW_SIZE: usize>(
+		&self,
+		other: &SingleSumSMA<Sample, Divisor, OTHER_WINDOW_SIZE>,
+	) -> Option<Sample> {
+		let num_samples = self
+			.get_num_samples()
+			.checked_sub(other.get_num_samples())?;
+		if num_samples == 0 {
+			return None;
+		}
+
+		let mut sum = self.sum;
+		sum -= other.sum;
+		Some(sum / cast_to_divisor_type(num_samples))
+	}
+
+	/// Adds the samples in the sample window of `other` to `self`, oldest first, as if they had been
+	/// added one by one. Only the last `WINDOW_SIZE` of the combined samples remain in the sample
+	/// window. The cached sum is updated incrementally, one sample at a time.
+	pub fn merge_window(&mut self, other: &Self) {
+		for sample in other.samples.iter() {
+			self.add_sample(*sample);
+		}
+	}
+
+	/// Sets the number of samples that the sample window must contain for
+	/// [get_average_if_ready](SingleSumSMA::get_average_if_ready) to return an average, which lets a
+	/// caller trade responsiveness for stability, rather than waiting for the sample window to be
+	/// full. Defaults to zero, i.e. no threshold.
+	///
+	/// # Panics
+	///
+	/// Panics if `min_samples` exceeds `WINDOW_SIZE`.
+	pub fn with_min_samples(mut self, min_samples: usize) -> Self {
+		assert!(
+			min_samples <= WINDOW_SIZE,
+			"min_samples must not exceed WINDOW_SIZE"
+		);
+		self.min_samples = min_samples;
+		self
+	}
+
+	/// Returns the number of samples that the sample window must contain for
+	/// [get_average_if_ready](SingleSumSMA::get_average_if_ready) to return an average.
+	pub fn get_min_samples(&self) -> usize {
+		self.min_samples
+	}
+
+	/// Returns the simple moving average value of all the samples in the sample window, but only
+	/// once it contains at least [get_min_samples](SingleSumSMA::get_min_samples) samples. Before that,
+	/// `None` is returned.
+	pub fn get_average_if_ready(&self) -> Option<Sample> {
+		if self.get_num_samples() < self.min_samples {
+			return None;
+		}
+
+		Some(self.get_average())
+	}
+
+	/// Adds a sample to the series of samples, like [add_sample](SMA::add_sample), but using checked
+	/// arithmetic to update the cached sum. Returns the sample that was dropped from the sample
+	/// window, if any, or an error if the sum of the samples in the resulting sample window can't be
+	/// calculated without overflowing, in which case the sample is not added.
+	#[cfg(feature = "num-traits")]
+	pub fn checked_add_sample(
+		&mut self,
+		new_sample: Sample,
+	) -> Result<Option<Sample>, OverflowError>
+	where
+		Sample: CheckedAdd + CheckedSub,
+	{
+		if WINDOW_SIZE == 0 {
+			return Ok(None);
+		}
+
+		let shifted_sample = if self.samples.len() == WINDOW_SIZE {
+			self.samples.back().cloned()
+		} else {
+			None
+		};
+
+		// Subtract before adding, so that the intermediate sum is within the range of the final one
+		let sum = match shifted_sample {
+			Some(shifted_sample) => self.sum.checked_sub(&shifted_sample),
+			None => Some(self.sum),
+		}
+		.and_then(|sum| sum.checked_add(&new_sample))
+		.ok_or_else(|| OverflowError::new(type_name::<Sample>()))?;
+
+		self.sum = sum;
+		self.generation += 1;
+		Ok(self.samples.shift(new_sample))
+	}
+
+	/// Consumes the [SingleSumSMA], returning the samples in the sample window, oldest first.
+	pub fn into_inner(self) -> Vec<Sample> {
+		let (older_samples, newer_samples) = self.samples.as_slices();
+		[older_samples, newer_samples].concat()
+	}
+
+	/// Returns the number of bytes of memory used by this instance. Since a [SingleSumSMA] is
+	/// completely backed by an array, this is its size on the stack.
+	pub fn get_memory_footprint(&self) -> usize {
+		size_of::<Self>()
+	}
+
+	/// Returns the generation of the sample window, a counter that is incremented whenever it is
+	/// modified, e.g. by adding a sample. A reader that polls the average, e.g. through a
+	/// [SharedSMA](crate::SharedSMA), can compare the generation with the one it last saw to find
+	/// out whether anything has changed, without recalculating or comparing anything. Reading never
+	/// changes the generation.
+	pub fn get_generation(&self) -> u64 {
+		self.generation
+	}
+
+	/// Removes the samples for which `predicate` returns `false` from the sample window, e.g.
+	/// samples that turned out to be invalid, keeping the order of the remaining samples. The
+	/// removed samples are subtracted from the cached sum.
+	///
+	/// This temporarily breaks the invariant that the sample window holds the last `WINDOW_SIZE`
+	/// samples, until enough new samples have been added to fill it up again.
+	pub fn retain<F: FnMut(&Sample) -> bool>(&mut self, mut predicate: F) {
+		let sum = &mut self.sum;
+		self.samples.retain(|sample| {
+			let is_retained = predicate(sample);
+			if !is_retained {
+				*sum -= *sample;
+			}
+			is_retained
+		});
+		self.generation += 1;
+	}
+
+	/// Removes the oldest sample from the sample window and subtracts it from the cached sum,
+	/// without adding a new sample. Returns the removed sample, or `None` if the sample window is
+	/// empty.
+	///
+	/// This deliberately breaks the invariant that the sample window holds the last `WINDOW_SIZE`
+	/// samples, until enough new samples have been added to fill it up again.
+	pub fn shift_out(&mut self) -> Option<Sample> {
+		let shifted_sample = self.samples.pop_back()?;
+		self.sum -= shifted_sample;
+		self.generation += 1;
+		Some(shifted_sample)
+	}
+
+	/// Completely fills the sample window with `value`, dropping all samples that were in it, so
+	/// that the average becomes `value`. Unlike emptying the sample window, this resets it to a
+	/// known steady state, e.g. after a regime change. The cached sum is recalculated from scratch,
+	/// discarding any rounding error it had accumulated.
+	pub fn reset_to(&mut self, value: Sample) {
+		self.clear();
+
+		for _ in 0..WINDOW_SIZE {
+			self.sum += value;
+			self.samples.push_front(value);
+		}
+		self.generation += 1;
+	}
+
+	/// Returns a [Snapshot] of the samples in the sample window and their cached sum, which can
+	/// later be restored using [restore](SingleSumSMA::restore).
+	pub fn snapshot(&self) -> Snapshot<Sample> {
+		Snapshot::new(self.samples.iter().cloned().collect(), Some(self.sum))
+	}
+
+	/// Replaces the samples in the sample window with those of `snapshot`. The cached sum is
+	/// restored as well, including any rounding errors it had accumulated, so that the average is
+	/// exactly the same as when the snapshot was taken. If the snapshot contains more samples than
+	/// fit in the sample window, only the most recent ones are kept and their sum is recalculated
+	/// from scratch. The rounding errors accumulated before restoring are discarded either way.
+	pub fn restore(&mut self, snapshot: Snapshot<Sample>) {
+		let (samples, sum) = snapshot.into_parts();
+		let num_dropped_samples = samples.len().saturating_sub(WINDOW_SIZE);
+
+		self.clear();
+		for sample in &samples[num_dropped_samples..] {
+			self.sum += *sample;
+			self.samples.push_front(*sample);
+		}
+
+		if let Some(sum) = sum.filter(|_| num_dropped_samples == 0) {
+			self.sum = sum;
+		}
+		self.generation += 1;
+	}
+
+	// Empties the sample window and resets the cached sum to an exact zero, discarding any rounding
+	// error it had accumulated. Subtracting the sum from itself yields that zero, without requiring
+	// `Sample: Zero`.
+	fn clear(&mut self) {
+		let sum = self.sum;
+		self.sum -= sum;
+		self.samples.clear();
+	}
+
+	/// Constructs a new [SingleSumSMA] with window size `WINDOW_SIZE`, whose sample window is completely
+	/// filled with the samples returned by `f(0)` (the oldest) through `f(WINDOW_SIZE - 1)` (the
+	/// most recent), like [std::array::from_fn]. The sum is accumulated while
+	/// filling the sample window.
+	#[cfg(feature = "num-traits")]
+	pub fn from_fn<F: FnMut(usize) -> Sample>(mut f: F) -> Self
+	where
+		Sample: Zero,
+	{
+		let mut sma = Self::new();
+		for idx in 0..WINDOW_SIZE {
+			sma.add_sample(f(idx));
+		}
+		sma
+	}
+
+	/// Constructs a new [SingleSumSMA] with window size `WINDOW_SIZE`, whose sample window is completely
+	/// filled with `value`. This constructor is only available for `Sample` types that implement
+	/// [num_traits::Zero]. If the `Sample` type does not, use the
+	/// [with_initial_from_zero](SingleSumSMA::with_initial_from_zero) constructor instead.
+	#[cfg(feature = "num-traits")]
+	pub fn with_initial(value: Sample) -> Self
+	where
+		Sample: Zero,
+	{
+		Self::with_initial_from_zero(Sample::zero(), value)
+	}
+
+	/// Constructs a new [SingleSumSMA] with window size `WINDOW_SIZE` from the given `zero` sample, whose
+	/// sample window is completely filled with `value`.
+	pub fn with_initial_from_zero(zero: Sample, value: Sample) -> Self {
+		let mut sma = Self::from_zero(zero);
+		for _ in 0..WINDOW_SIZE {
+			sma.add_sample(value);
+		}
+		sma
+	}
+}
+
+#[cfg(feature = "num-traits")]
+impl<Sample, Divisor, const WINDOW_SIZE: usize> FromIterator<Sample>
+	for SingleSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Zero + AddAssign + SubAssign + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	/// Constructs a new [SingleSumSMA] and adds all samples yielded by `iter` to it. Only the last
+	/// `WINDOW_SIZE` samples will end up in the sample window.
+	fn from_iter<I: IntoIterator<Item = Sample>>(iter: I) -> Self {
+		let mut sma = Self::new();
+		sma.extend(iter);
+		sma
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> Extend<Sample>
+	for SingleSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + AddAssign + SubAssign + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	fn extend<I: IntoIterator<Item = Sample>>(&mut self, iter: I) {
+		for sample in iter {
+			self.add_sample(sample);
+		}
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> AddAssign<Sample>
+	for SingleSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + AddAssign + SubAssign + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	/// Adds a sample to the series of samples, like [add_sample](SMA::add_sample).
+	fn add_assign(&mut self, new_sample: Sample) {
+		self.add_sample(new_sample);
+	}
+}
+
+#[cfg(feature = "num-traits")]
+impl<Sample, Divisor, const WINDOW_SIZE: usize> From<[Sample; WINDOW_SIZE]>
+	for SingleSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Zero + AddAssign + SubAssign + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	/// Constructs a new [SingleSumSMA] whose sample window is completely filled with `samples`, the last
+	/// sample being the most recent one.
+	fn from(samples: [Sample; WINDOW_SIZE]) -> Self {
+		let mut sma = Self::new();
+		sma.add_samples(&samples);
+		sma
+	}
+}
+
+#[cfg(feature = "num-traits")]
+impl<Sample, Divisor, const WINDOW_SIZE: usize> TryFrom<&[Sample]>
+	for SingleSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Zero + AddAssign + SubAssign + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	type Error = WindowSizeError;
+
+	/// Constructs a new [SingleSumSMA] whose sample window contains `samples`, the last sample being the
+	/// most recent one. Fails if there are more samples than fit in the sample window, rather than
+	/// silently dropping the oldest ones.
+	fn try_from(samples: &[Sample]) -> Result<Self, Self::Error> {
+		if WINDOW_SIZE < samples.len() {
+			return Err(WindowSizeError::new(samples.len(), WINDOW_SIZE));
+		}
+
+		let mut sma = Self::new();
+		sma.add_samples(samples);
+		Ok(sma)
+	}
+}
+
+impl<Sample: Copy, Divisor, const WINDOW_SIZE: usize> IntoIterator
+	for SingleSumSMA<Sample, Divisor, WINDOW_SIZE>
+{
+	type Item = Sample;
+	type IntoIter = IntoIter<Sample, WINDOW_SIZE>;
+
+	/// Consumes the [SingleSumSMA], returning an iterator over the samples in the sample window, oldest
+	/// first.
+	fn into_iter(self) -> Self::IntoIter {
+		self.samples.into_iter()
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> fmt::Debug
+	for SingleSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("SingleSumSMA")
+			.field("window_size", &WINDOW_SIZE)
+			.field("num_samples", &self.samples.len())
+			.field("samples", &self.samples.iter().collect::<Vec<&Sample>>())
+			.field("sum", &self.sum)
+			.field("divisor", &type_name::<Divisor>())
+			.finish()
+	}
+}
+
+/// Formats the average of the samples in the sample window, as returned by
+/// [get_average](SMA::get_average), forwarding the formatter's flags, e.g. its precision.
+impl<Sample, Divisor, const WINDOW_SIZE: usize> fmt::Display
+	for SingleSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + AddAssign + SubAssign + Div<Divisor, Output = Sample> + fmt::Display,
+	Divisor: DivisorFromCount,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&self.get_average(), f)
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> PartialEq
+	for SingleSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + PartialEq,
+{
+	/// Two instances are equal if their sample windows contain the same samples, in the same order.
+	/// Any cached sums are not compared, as they only depend on the samples, apart from rounding
+	/// errors.
+	fn eq(&self, other: &Self) -> bool {
+		self.samples.iter().eq(other.samples.iter())
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> Eq for SingleSumSMA<Sample, Divisor, WINDOW_SIZE> where
+	Sample: Copy + Eq
+{
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> Hash for SingleSumSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Hash,
+{
+	/// Hashes the number of samples and the samples in the sample window, in order, consistent with
+	/// [PartialEq].
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		state.write_usize(self.samples.len());
+		for sample in self.samples.iter() {
+			sample.hash(state);
+		}
+	}
+}
@@ -1,4 +1,15 @@
-use crate::Iter;
+use crate::{
+	common::{cast_to_divisor_type, try_cast_to_divisor_type},
+	DivisorError, DivisorFromCount, Iter,
+};
+#[cfg(feature = "num-traits")]
+use crate::{rounding_mode::divide_rounded, RoundingMode};
+#[cfg(feature = "num-traits")]
+use num_traits::{Float, PrimInt, Zero};
+use std::{
+	iter::Rev,
+	ops::{Add, Div, Mul, Sub},
+};
 
 /// This trait provides an common interface for algorithms that can calculate a simple moving
 /// average.
@@ -15,24 +26,365 @@ use crate::Iter;
 /// Terminology:
 ///  - Sample: A data point, a value.
 ///  - Sample window: The subset of all samples used for average calculations.
+///
+/// A `WINDOW_SIZE` of zero results in a disabled SMA, see [is_disabled](SMA::is_disabled), which
+/// ignores all added samples. Its sample window is always empty, and at the same time always full,
+/// as it can't hold any more samples. All methods behave accordingly, without panicking: averages
+/// are zero, or `None` where the return type allows telling an empty sample window apart, and no
+/// samples are ever returned.
 pub trait SMA<Sample, Divisor, const WINDOW_SIZE: usize> {
 	/// Adds a sample to the series of samples. If the sample window is full, this will cause the
 	/// oldest sample to be dropped, i.e. no longer contribute to the average.
 	fn add_sample(&mut self, new_sample: Sample);
 
+	/// Adds all the given samples to the series of samples, in order. This has the same effect as
+	/// calling [add_sample](SMA::add_sample) once per sample, but some implementations are able to
+	/// do it more efficiently.
+	fn add_samples(&mut self, new_samples: &[Sample])
+	where
+		Sample: Copy,
+	{
+		for new_sample in new_samples {
+			self.add_sample(*new_sample);
+		}
+	}
+
+	/// Adds a sample to the series of samples, like [add_sample](SMA::add_sample), and returns the
+	/// resulting average, like [get_average](SMA::get_average). For [NoSumSMA](crate::NoSumSMA),
+	/// the sample window is summed once, right after the new sample has been shifted into it. The
+	/// other implementations calculate the average from their cached sums anyway.
+	fn add_and_get_average(&mut self, new_sample: Sample) -> Sample {
+		self.add_sample(new_sample);
+		self.get_average()
+	}
+
+	/// Adds the given samples to the series of samples, one by one, and returns the average after
+	/// each of them was added, e.g. to backtest a whole series of samples in a single call.
+	fn scan_averages(&mut self, new_samples: &[Sample]) -> Vec<Sample>
+	where
+		Sample: Copy,
+	{
+		new_samples
+			.iter()
+			.map(|new_sample| self.add_and_get_average(*new_sample))
+			.collect()
+	}
+
+	/// Adds a floating point sample to the series of samples, unless it is `NaN` or infinite, in
+	/// which case the sample is ignored and the sample window is left untouched. Returns `true` if
+	/// the sample was added.
+	///
+	/// This prevents a single bad sample, e.g. from a faulty sensor, from poisoning the cached sum
+	/// of an implementation such as [SingleSumSMA](crate::SingleSumSMA) forever.
+	#[cfg(feature = "num-traits")]
+	fn add_finite_sample(&mut self, new_sample: Sample) -> bool
+	where
+		Sample: Float,
+	{
+		if !new_sample.is_finite() {
+			return false;
+		}
+		self.add_sample(new_sample);
+		true
+	}
+
+	/// Adds `new_sample` to the series of samples if it is `Some`. A `None` sample is treated as a
+	/// gap in the series, e.g. a dropped reading, and leaves the sample window untouched.
+	fn add_sample_opt(&mut self, new_sample: Option<Sample>) {
+		if let Some(new_sample) = new_sample {
+			self.add_sample(new_sample);
+		}
+	}
+
 	/// Returns the simple moving average value of all the samples in the sample window.
+	///
+	/// # Panics
+	///
+	/// Panics if the number of samples in the sample window can't be represented by the `Divisor`
+	/// type. Use [try_get_average](SMA::try_get_average) to handle that case gracefully.
 	fn get_average(&self) -> Sample;
 
+	/// Returns the simple moving average value of all the samples in the sample window, or a
+	/// [DivisorError] if the number of samples in the sample window can't be represented by the
+	/// `Divisor` type.
+	fn try_get_average(&self) -> Result<Sample, DivisorError>
+	where
+		Divisor: DivisorFromCount,
+	{
+		try_cast_to_divisor_type::<Divisor>(self.get_num_samples())?;
+		Ok(self.get_average())
+	}
+
+	/// Returns the simple moving average value of all the samples in the sample window, or `None` if
+	/// the sample window is empty. This makes it possible to tell an empty sample window apart
+	/// from samples that average to zero, which [get_average](SMA::get_average) does not.
+	fn get_average_if_nonempty(&self) -> Option<Sample> {
+		if self.is_empty() {
+			None
+		} else {
+			Some(self.get_average())
+		}
+	}
+
+	/// Returns the mean of the samples in the sample window as a `T`, e.g. an [f64] average of
+	/// [u32] samples. The sum of the samples is converted to `T` before dividing it, which preserves
+	/// the fractional part that [get_average](SMA::get_average) truncates for integer `Sample`
+	/// types. An empty sample window has an average of zero.
+	///
+	/// The default implementation converts every sample in the sample window while summing them.
+	/// Implementations that cache the sum convert only that.
+	#[cfg(feature = "num-traits")]
+	fn get_average_as<T>(&self) -> T
+	where
+		Self: Sized,
+		Sample: Copy + Into<T>,
+		T: Zero + Div<Output = T> + DivisorFromCount,
+	{
+		let num_samples = self.get_num_samples();
+
+		if num_samples == 0 {
+			return T::zero();
+		}
+
+		let sum = self
+			.get_sample_window_iter()
+			.fold(T::zero(), |sum, sample| sum + (*sample).into());
+		sum / cast_to_divisor_type::<T>(num_samples)
+	}
+
+	/// Returns the simple moving average value of all the samples in the sample window, rounded
+	/// according to `rounding_mode`. This is only available for primitive integer `Sample` types,
+	/// whose [get_average](SMA::get_average) always truncates. An empty sample window has an
+	/// average of zero.
+	///
+	/// The rounding mode is given per call, rather than configured on the instance, since
+	/// [get_average](SMA::get_average) is implemented for all `Sample` types at once and can't
+	/// round integer samples only. To round consistently, pass the same [RoundingMode] everywhere,
+	/// e.g. through a constant.
+	///
+	/// The default implementation sums the samples in the sample window. Implementations that cache
+	/// the sum use that instead.
+	#[cfg(feature = "num-traits")]
+	fn get_rounded_average(&self, rounding_mode: RoundingMode) -> Sample
+	where
+		Sample: PrimInt + DivisorFromCount,
+	{
+		let num_samples = self.get_num_samples();
+
+		if num_samples == 0 {
+			return Sample::zero();
+		}
+
+		let sum = self
+			.get_sample_window_iter()
+			.fold(Sample::zero(), |sum, sample| sum + *sample);
+		divide_rounded(
+			sum,
+			cast_to_divisor_type::<Sample>(num_samples),
+			rounding_mode,
+		)
+	}
+
+	/// Returns the average of the `num_samples` most recent samples in the sample window, or of all
+	/// samples in it if there are fewer. Returns `None` if `num_samples` is zero or the sample
+	/// window is empty. This is an `O(num_samples)` operation, except for
+	/// [SumTreeSMA](crate::SumTreeSMA), for which it is `O(log(WINDOW_SIZE))`.
+	fn get_average_of_last(&self, num_samples: usize) -> Option<Sample>
+	where
+		Sample: Copy + Add<Output = Sample> + Div<Divisor, Output = Sample>,
+		Divisor: DivisorFromCount,
+	{
+		let num_samples = num_samples.min(self.get_num_samples());
+
+		let mut samples = self.get_sample_window_rev_iter().take(num_samples);
+		let first_sample = *samples.next()?;
+		let sum = samples.fold(first_sample, |sum, sample| sum + *sample);
+		Some(sum / cast_to_divisor_type(num_samples))
+	}
+
 	/// Returns the total number of samples currently in the in the sample window. This value never
 	/// exceeds the sample window size.
 	fn get_num_samples(&self) -> usize;
 
 	/// Returns the maximum number of samples that fit in the sample window.
+	///
+	/// The SMA implementations also provide the sample window size as an associated constant, e.g.
+	/// [SingleSumSMA::WINDOW_SIZE](crate::SingleSumSMA::WINDOW_SIZE). It is not an associated
+	/// constant of this trait, since that would prevent it from being used as a trait object.
 	fn get_sample_window_size(&self) -> usize;
 
-	// Returns an iterator over the samples currently in the sample window.
-	fn get_sample_window_iter(&self) -> Iter<Sample, WINDOW_SIZE>;
+	/// Returns an iterator over the samples currently in the sample window, oldest first.
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE>;
+
+	/// Returns an iterator over the samples currently in the sample window, most recent first.
+	fn get_sample_window_rev_iter(&self) -> Rev<Iter<'_, Sample, WINDOW_SIZE>> {
+		self.get_sample_window_iter().rev()
+	}
+
+	/// Calls `f` on each sample in the sample window, oldest first, without copying or reordering
+	/// the underlying storage.
+	fn for_each_sample<F: FnMut(&Sample)>(&self, f: F)
+	where
+		Self: Sized,
+	{
+		self.get_sample_window_iter().for_each(f);
+	}
 
 	/// Returns the most recently added sample, if any.
 	fn get_most_recent_sample(&self) -> Option<Sample>;
+
+	/// Returns the oldest sample in the sample window, i.e. the sample that will be dropped next,
+	/// if any.
+	///
+	/// The default implementation takes the first sample of
+	/// [get_sample_window_iter](SMA::get_sample_window_iter). The implementations in this crate
+	/// read it from their sample storage directly.
+	fn get_oldest_sample(&self) -> Option<Sample>
+	where
+		Sample: Copy,
+	{
+		self.get_sample_window_iter().next().copied()
+	}
+
+	/// Returns the sample that the next call to [add_sample](SMA::add_sample) will drop from the
+	/// sample window. Unlike [get_oldest_sample](SMA::get_oldest_sample), this returns `None` until
+	/// the sample window is full, as no sample is dropped before that.
+	fn peek_would_evict(&self) -> Option<Sample>
+	where
+		Sample: Copy,
+	{
+		if !self.is_full() {
+			return None;
+		}
+
+		self.get_oldest_sample()
+	}
+
+	/// Returns the simple moving average value of all the samples in the sample window, but only
+	/// once the sample window is full. Before that, and always for a sample window of size zero,
+	/// `None` is returned.
+	fn get_average_when_full(&self) -> Option<Sample> {
+		if self.get_sample_window_size() == 0 || !self.is_full() {
+			return None;
+		}
+
+		Some(self.get_average())
+	}
+
+	/// Returns `true` if the sample window size is zero, meaning that all added samples are ignored.
+	fn is_disabled(&self) -> bool {
+		self.get_sample_window_size() == 0
+	}
+
+	/// Returns `true` if there are no samples in the sample window.
+	fn is_empty(&self) -> bool {
+		self.get_num_samples() == 0
+	}
+
+	/// Returns `true` if the sample window is full, meaning that adding another sample will cause
+	/// the oldest sample to be dropped. A sample window of size zero is always full.
+	fn is_full(&self) -> bool {
+		self.get_num_samples() == self.get_sample_window_size()
+	}
+
+	/// Returns the number of samples in the sample window divided by the sample window size. A
+	/// sample window of size zero is always full, i.e. has a fill ratio of `1.0`.
+	fn fill_ratio(&self) -> f32 {
+		let sample_window_size = self.get_sample_window_size();
+
+		if sample_window_size == 0 {
+			return 1.0;
+		}
+
+		self.get_num_samples() as f32 / sample_window_size as f32
+	}
+
+	/// Returns the momentum of the samples in the sample window, i.e. the most recent sample minus
+	/// the oldest one, or `None` if there are fewer than two samples in the sample window.
+	fn get_momentum(&self) -> Option<Sample>
+	where
+		Sample: Copy + Sub<Output = Sample>,
+	{
+		if self.get_num_samples() < 2 {
+			return None;
+		}
+
+		Some(self.get_most_recent_sample()? - self.get_oldest_sample()?)
+	}
+
+	/// Returns the average rate of change of the samples in the sample window, i.e. the
+	/// [momentum](SMA::get_momentum) divided by the number of intervals between the samples, or
+	/// `None` if there are fewer than two samples in the sample window.
+	fn get_rate_of_change(&self) -> Option<Sample>
+	where
+		Sample: Copy + Sub<Output = Sample> + Div<Divisor, Output = Sample>,
+		Divisor: DivisorFromCount,
+	{
+		let momentum = self.get_momentum()?;
+		Some(momentum / cast_to_divisor_type(self.get_num_samples() - 1))
+	}
+
+	/// Returns the population variance of the samples in the sample window, i.e. the average of
+	/// their squared deviations from [get_average](SMA::get_average). An empty sample window has a
+	/// variance of zero.
+	///
+	/// The deviations are calculated as the difference between the larger and the smaller of each
+	/// sample and the average, so that unsigned integer samples do not underflow. For integer
+	/// samples, the average, and hence the variance, is truncated, and the squared deviations may
+	/// overflow if the samples are spread over more than the square root of the `Sample` range.
+	fn get_variance(&self) -> Sample
+	where
+		Sample: Copy
+			+ PartialOrd
+			+ Add<Output = Sample>
+			+ Sub<Output = Sample>
+			+ Mul<Output = Sample>
+			+ Div<Divisor, Output = Sample>,
+		Divisor: DivisorFromCount,
+	{
+		// The average of an empty sample window is zero, and serves as zero for the sum as well
+		let average = self.get_average();
+		let num_samples = self.get_num_samples();
+
+		if num_samples == 0 {
+			return average;
+		}
+
+		let mut squared_deviations = self.get_sample_window_iter().map(|sample| {
+			let deviation = if *sample < average {
+				average - *sample
+			} else {
+				*sample - average
+			};
+			deviation * deviation
+		});
+		let first_squared_deviation = squared_deviations.next().unwrap();
+		let sum = squared_deviations.fold(first_squared_deviation, |sum, squared_deviation| {
+			sum + squared_deviation
+		});
+		sum / cast_to_divisor_type::<Divisor>(num_samples)
+	}
+
+	/// Returns the root mean square (RMS) of the samples in the sample window, i.e. the square root
+	/// of the average of the squared samples, which is a measure of signal power. An empty sample
+	/// window has an RMS of zero. Only available for floating point `Sample` types. Calculating it
+	/// is an `O(N)` operation; use [RmsMovingAverage](crate::RmsMovingAverage) if it needs to be
+	/// read often.
+	#[cfg(feature = "num-traits")]
+	fn get_rms(&self) -> Sample
+	where
+		Sample: Float + Div<Divisor, Output = Sample>,
+		Divisor: DivisorFromCount,
+	{
+		let num_samples = self.get_num_samples();
+
+		if num_samples == 0 {
+			return Sample::zero();
+		}
+
+		let square_sum = self
+			.get_sample_window_iter()
+			.fold(Sample::zero(), |sum, sample| sum + *sample * *sample);
+		(square_sum / cast_to_divisor_type::<Divisor>(num_samples)).sqrt()
+	}
 }
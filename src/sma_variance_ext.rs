@@ -0,0 +1,28 @@
+use crate::{DivisorFromCount, SMA};
+use num_traits::Float;
+use std::ops::Div;
+
+/// Extends all [SMA] implementations with the [get_std_dev](SMAVarianceExt::get_std_dev) method,
+/// which calculates the spread of the samples in the sample window. It is kept out of the [SMA]
+/// trait, as it requires a square root, and hence a floating point `Sample` type.
+pub trait SMAVarianceExt<Sample, Divisor, const WINDOW_SIZE: usize>:
+	SMA<Sample, Divisor, WINDOW_SIZE>
+{
+	/// Returns the population standard deviation of the samples in the sample window, i.e. the
+	/// square root of [get_variance](SMA::get_variance). Only available for floating point
+	/// `Sample` types.
+	fn get_std_dev(&self) -> Sample
+	where
+		Sample: Float + Div<Divisor, Output = Sample>,
+		Divisor: DivisorFromCount,
+	{
+		self.get_variance().sqrt()
+	}
+}
+
+impl<T, Sample, Divisor, const WINDOW_SIZE: usize> SMAVarianceExt<Sample, Divisor, WINDOW_SIZE>
+	for T
+where
+	T: SMA<Sample, Divisor, WINDOW_SIZE> + ?Sized,
+{
+}
@@ -0,0 +1,160 @@
+use super::SMA;
+use crate::{common::cast_to_divisor_type, ring_buffer::RingBuffer, DivisorFromCount, Iter};
+use num_traits::Zero;
+use std::{
+	marker::{self, PhantomData},
+	ops::{Add, Div, Mul},
+};
+
+/// Calculates Wilder's [smoothed moving average](https://en.wikipedia.org/wiki/Moving_average#Modified_moving_average)
+/// (SMMA, also known as RMA), as used by e.g. the relative strength index and average true range
+/// indicators.
+///
+/// Until `WINDOW_SIZE` samples have been added, the average is the simple moving average of the
+/// samples added so far. Once the sample window is full, every new sample is blended into the
+/// previous average as `(average * (WINDOW_SIZE - 1) + new_sample) / WINDOW_SIZE`, which means that
+/// samples keep contributing to the average even after they have left the sample window.
+#[derive(Clone, Copy, Debug)]
+pub struct SmoothedMovingAverage<Sample, Divisor, const WINDOW_SIZE: usize> {
+	samples: RingBuffer<Sample, WINDOW_SIZE>,
+	// Sum of the samples added while the sample window is not yet full
+	sum: Sample,
+	average: Sample,
+	_marker: marker::PhantomData<Divisor>,
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> SMA<Sample, Divisor, WINDOW_SIZE>
+	for SmoothedMovingAverage<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample:
+		Copy + Add<Output = Sample> + Mul<Divisor, Output = Sample> + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	fn add_sample(&mut self, new_sample: Sample) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+
+		if self.samples.shift(new_sample).is_some() {
+			self.average = (self.average * cast_to_divisor_type::<Divisor>(WINDOW_SIZE - 1)
+				+ new_sample)
+				/ cast_to_divisor_type::<Divisor>(WINDOW_SIZE);
+			return;
+		}
+
+		self.sum = self.sum + new_sample;
+		self.average = self.sum / cast_to_divisor_type::<Divisor>(self.samples.len());
+	}
+
+	fn get_average(&self) -> Sample {
+		self.average
+	}
+
+	fn get_most_recent_sample(&self) -> Option<Sample> {
+		self.samples.front().cloned()
+	}
+
+	fn get_oldest_sample(&self) -> Option<Sample> {
+		self.samples.back().cloned()
+	}
+
+	fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
+		self.samples.iter()
+	}
+}
+
+impl<Sample: Copy + Zero, Divisor, const WINDOW_SIZE: usize>
+	SmoothedMovingAverage<Sample, Divisor, WINDOW_SIZE>
+{
+	/// Constructs a new [SmoothedMovingAverage] with window size `WINDOW_SIZE`. This constructor is
+	/// only available for `Sample` types that implement [num_traits::Zero]. If the `Sample` type
+	/// does not, use the [from_zero](SmoothedMovingAverage::from_zero) constructor instead.
+	///
+	/// Note that the `Divisor` type usually cannot be derived by the compiler when using this
+	/// constructor and must be explicitly stated, even if it is the same as the `Sample` type.
+	pub fn new() -> Self {
+		Self::from_zero(Sample::zero())
+	}
+}
+
+impl<Sample: Copy + Zero, Divisor, const WINDOW_SIZE: usize> Default
+	for SmoothedMovingAverage<Sample, Divisor, WINDOW_SIZE>
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<Sample: Copy, Divisor, const WINDOW_SIZE: usize>
+	SmoothedMovingAverage<Sample, Divisor, WINDOW_SIZE>
+{
+	/// Constructs a new [SmoothedMovingAverage] with window size `WINDOW_SIZE` from the given
+	/// `zero` sample. If the `Sample` type implements [num_traits::Zero], the
+	/// [new](SmoothedMovingAverage::new) constructor might be preferable to this.
+	pub const fn from_zero(zero: Sample) -> Self {
+		Self {
+			samples: RingBuffer::new(zero),
+			sum: zero,
+			average: zero,
+			_marker: PhantomData,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn reference_series() {
+		let mut sma = SmoothedMovingAverage::<f64, f64, 3>::new();
+		assert_eq!(sma.get_average(), 0.0);
+
+		// Simple average until the sample window is full, then Wilder's recurrence
+		let expected = [
+			1.0,
+			1.5,
+			2.0,
+			8.0 / 3.0,    // (2 * 2 + 4) / 3
+			31.0 / 9.0,   // (8 / 3 * 2 + 5) / 3
+			116.0 / 27.0, // (31 / 9 * 2 + 6) / 3
+		];
+
+		for (sample, expected) in (1..=6).map(f64::from).zip(expected.iter()) {
+			sma.add_sample(sample);
+			assert!((sma.get_average() - expected).abs() < 1e-12);
+		}
+
+		assert_eq!(sma.get_num_samples(), 3);
+		assert_eq!(
+			sma.get_sample_window_iter().collect::<Vec<&f64>>(),
+			vec![&4.0, &5.0, &6.0]
+		);
+	}
+
+	#[test]
+	fn integer_samples() {
+		let mut sma = SmoothedMovingAverage::<u32, u32, 2>::new();
+		for sample in [10, 20, 40, 80] {
+			sma.add_sample(sample);
+		}
+		// (10 + 20) / 2 = 15, (15 + 40) / 2 = 27, (27 + 80) / 2 = 53
+		assert_eq!(sma.get_average(), 53);
+	}
+
+	#[test]
+	fn edge_case_zero_sized() {
+		let mut sma = SmoothedMovingAverage::<f32, f32, 0>::new();
+		sma.add_sample(1.0);
+		assert_eq!(sma.get_average(), 0.0);
+		assert_eq!(sma.get_num_samples(), 0);
+	}
+}
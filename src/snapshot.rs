@@ -0,0 +1,26 @@
+/// An opaque, owned copy of the state of an SMA, as returned by e.g.
+/// [SingleSumSMA::snapshot](crate::SingleSumSMA::snapshot). Unlike a clone of the SMA itself, a
+/// snapshot can be stored independently of the SMA and later be restored into any instance of the
+/// same type, e.g. a freshly constructed one, to continue from the state it was taken at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot<Sample> {
+	// Oldest sample first
+	samples: Vec<Sample>,
+	// Only present if the SMA the snapshot was taken of caches a single sum
+	sum: Option<Sample>,
+}
+
+impl<Sample> Snapshot<Sample> {
+	pub(crate) fn new(samples: Vec<Sample>, sum: Option<Sample>) -> Self {
+		Self { samples, sum }
+	}
+
+	pub(crate) fn into_parts(self) -> (Vec<Sample>, Option<Sample>) {
+		(self.samples, self.sum)
+	}
+
+	/// Returns the number of samples that were in the sample window when the snapshot was taken.
+	pub fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+}
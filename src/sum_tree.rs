@@ -1,4 +1,6 @@
-use std::ops::Add;
+#[cfg(feature = "num-traits")]
+use num_traits::CheckedAdd;
+use std::{mem::size_of, ops::Add};
 
 #[derive(Clone, Debug)]
 pub struct SumTree<Sample> {
@@ -18,10 +20,6 @@ impl<Sample> SumTree<Sample>
 where
 	Sample: Copy + Add<Output = Sample>,
 {
-	pub fn get_root_sum(&self) -> Sample {
-		self.nodes[ROOT_NODE_IDX]
-	}
-
 	pub fn get_leaf_node_sum(&self, leaf_node_idx: &usize) -> Sample {
 		self.nodes[self.get_leaf_nodes_offset() + leaf_node_idx]
 	}
@@ -29,7 +27,122 @@ where
 	pub fn update_leaf_node_sample(&mut self, leaf_node_idx: usize, new_sample: Sample) {
 		let node_idx = self.get_leaf_nodes_offset() + leaf_node_idx;
 		*self.get_node_mut(node_idx) = new_sample;
-		self.update_parent_recursive(node_idx, new_sample);
+		if node_idx != ROOT_NODE_IDX {
+			self.update_parent_recursive(node_idx, new_sample);
+		}
+	}
+
+	// Like update_leaf_node_sample, but leaves the sum tree unchanged and returns `None` if any
+	// subtree sum on the path to the root overflows
+	#[cfg(feature = "num-traits")]
+	pub fn checked_update_leaf_node_sample(
+		&mut self,
+		leaf_node_idx: usize,
+		new_sample: Sample,
+	) -> Option<()>
+	where
+		Sample: CheckedAdd,
+	{
+		let mut node_idx = self.get_leaf_nodes_offset() + leaf_node_idx;
+		let mut subtree_sum = new_sample;
+		let mut path = vec![(node_idx, subtree_sum)];
+
+		while node_idx != ROOT_NODE_IDX {
+			let other_child_subtree_sum = match get_position(node_idx) {
+				Position::Left => self.nodes[node_idx + 1],
+				Position::Right => self.nodes[node_idx - 1],
+			};
+			subtree_sum = subtree_sum.checked_add(&other_child_subtree_sum)?;
+			node_idx = get_parent_node_idx(node_idx);
+			path.push((node_idx, subtree_sum));
+		}
+
+		for (node_idx, subtree_sum) in path {
+			self.nodes[node_idx] = subtree_sum;
+		}
+		Some(())
+	}
+
+	pub fn update_leaf_node_samples(&mut self, leaf_node_samples: &[(usize, Sample)]) {
+		let leaf_nodes_offset = self.get_leaf_nodes_offset();
+
+		let mut node_idxs: Vec<usize> = leaf_node_samples
+			.iter()
+			.map(|(leaf_node_idx, new_sample)| {
+				let node_idx = leaf_nodes_offset + leaf_node_idx;
+				*self.get_node_mut(node_idx) = *new_sample;
+				node_idx
+			})
+			.collect();
+
+		// Re-calculate each parent node once per level, even if several of its descendants changed
+		while !node_idxs.is_empty() && !node_idxs.contains(&ROOT_NODE_IDX) {
+			for node_idx in node_idxs.iter_mut() {
+				*node_idx = get_parent_node_idx(*node_idx);
+			}
+			node_idxs.sort_unstable();
+			node_idxs.dedup();
+
+			for node_idx in node_idxs.iter() {
+				let left_child_subtree_sum = *self.get_node(get_left_child_node_idx(*node_idx));
+				let right_child_subtree_sum = *self.get_node(get_right_child_node_idx(*node_idx));
+				*self.get_node_mut(*node_idx) = left_child_subtree_sum + right_child_subtree_sum;
+			}
+		}
+	}
+
+	/// Builds a sum tree whose first leaf nodes contain `samples`, calculating each parent node
+	/// exactly once, bottom-up. This is `O(N)`, as opposed to the `O(N log(N))` of updating the
+	/// leaf nodes one by one.
+	pub fn build_from_slice(zero: Sample, num_leaf_nodes: usize, samples: &[Sample]) -> Self {
+		assert!(samples.len() <= num_leaf_nodes);
+
+		let mut sum_tree = Self::new(zero, num_leaf_nodes);
+		sum_tree
+			.get_leaf_nodes_mut(samples.len())
+			.copy_from_slice(samples);
+		sum_tree.update_internal_nodes();
+		sum_tree
+	}
+
+	/// Re-calculates all internal nodes from the leaf nodes, calculating each of them exactly once,
+	/// bottom-up. Used after modifying the leaf nodes through
+	/// [get_leaf_nodes_mut](SumTree::get_leaf_nodes_mut).
+	pub fn update_internal_nodes(&mut self) {
+		for node_idx in (ROOT_NODE_IDX..self.get_leaf_nodes_offset()).rev() {
+			let left_child_subtree_sum = *self.get_node(get_left_child_node_idx(node_idx));
+			let right_child_subtree_sum = *self.get_node(get_right_child_node_idx(node_idx));
+			*self.get_node_mut(node_idx) = left_child_subtree_sum + right_child_subtree_sum;
+		}
+	}
+
+	/// Returns the sum of the leaf nodes in the range `start_leaf_node_idx..end_leaf_node_idx`, by
+	/// combining the sums of the `O(log(N))` largest subtrees that exactly cover the range.
+	pub fn get_range_sum(&self, start_leaf_node_idx: usize, end_leaf_node_idx: usize) -> Sample {
+		let leaf_nodes_offset = self.get_leaf_nodes_offset();
+		assert!(start_leaf_node_idx <= end_leaf_node_idx);
+		assert!(end_leaf_node_idx <= leaf_nodes_offset);
+
+		let mut start_node_idx = leaf_nodes_offset + start_leaf_node_idx;
+		let mut end_node_idx = leaf_nodes_offset + end_leaf_node_idx;
+		let mut sum = self.get_zero();
+
+		// Walk up both range boundaries, adding the nodes that are only partially covered by the
+		// parent of either boundary node
+		while start_node_idx < end_node_idx {
+			if let Position::Right = get_position(start_node_idx) {
+				sum = sum + self.nodes[start_node_idx];
+				start_node_idx += 1;
+			}
+			if let Position::Right = get_position(end_node_idx) {
+				end_node_idx -= 1;
+				sum = sum + self.nodes[end_node_idx];
+			}
+			start_node_idx = get_parent_node_idx(start_node_idx);
+			end_node_idx = get_parent_node_idx(end_node_idx);
+		}
+
+		sum
 	}
 
 	fn update_parent_recursive(&mut self, child_node_idx: usize, new_child_subtree_sum: Sample) {
@@ -56,22 +169,16 @@ where
 	fn get_node_mut(&mut self, node_idx: usize) -> &mut Sample {
 		&mut self.nodes[node_idx]
 	}
-
-	fn get_leaf_nodes_offset(&self) -> usize {
-		self.nodes.len() / 2
-	}
-
-	pub fn get_leaf_nodes(&self, num_nodes: usize) -> &[Sample] {
-		let leaf_nodes_start = self.get_leaf_nodes_offset();
-		let leaf_nodes_end = leaf_nodes_start + num_nodes;
-		&self.nodes[leaf_nodes_start..leaf_nodes_end]
-	}
 }
 
 impl<Sample> SumTree<Sample>
 where
 	Sample: Copy,
 {
+	pub fn get_root_sum(&self) -> Sample {
+		self.nodes[ROOT_NODE_IDX]
+	}
+
 	pub fn new(zero: Sample, num_leaf_nodes: usize) -> Self {
 		// Let's create a perfect binary tree, large enough to accomodate all leaf nodes.
 		// The extra nodes will contain only zeros, which is alright for our purposes.
@@ -80,6 +187,52 @@ where
 			nodes: vec![zero; num_leaf_nodes],
 		}
 	}
+
+	// The unused node at index 0 is never written to after construction
+	pub fn get_zero(&self) -> Sample {
+		self.nodes[0]
+	}
+
+	// Includes both the leaf nodes and the internal nodes, but not the unused node at index 0
+	pub fn get_node_count(&self) -> usize {
+		self.nodes.len() - 1
+	}
+
+	// Includes the unused node at index 0
+	pub fn get_heap_size(&self) -> usize {
+		self.nodes.capacity() * size_of::<Sample>()
+	}
+
+	// The number of node levels, including the root and leaf node levels
+	pub fn get_depth(&self) -> usize {
+		self.get_leaf_nodes_offset().trailing_zeros() as usize + 1
+	}
+
+	fn get_leaf_nodes_offset(&self) -> usize {
+		self.nodes.len() / 2
+	}
+
+	pub fn get_leaf_nodes(&self, num_nodes: usize) -> &[Sample] {
+		let leaf_nodes_start = self.get_leaf_nodes_offset();
+		let leaf_nodes_end = leaf_nodes_start + num_nodes;
+		&self.nodes[leaf_nodes_start..leaf_nodes_end]
+	}
+
+	// Leaves the internal nodes untouched, see update_internal_nodes
+	pub fn get_leaf_nodes_mut(&mut self, num_nodes: usize) -> &mut [Sample] {
+		let leaf_nodes_start = self.get_leaf_nodes_offset();
+		let leaf_nodes_end = leaf_nodes_start + num_nodes;
+		&mut self.nodes[leaf_nodes_start..leaf_nodes_end]
+	}
+
+	// Reuses the allocation of the nodes
+	pub fn into_leaf_nodes(self, num_nodes: usize) -> Vec<Sample> {
+		let leaf_nodes_start = self.get_leaf_nodes_offset();
+		let mut nodes = self.nodes;
+		nodes.drain(..leaf_nodes_start);
+		nodes.truncate(num_nodes);
+		nodes
+	}
 }
 
 fn get_position(node_idx: usize) -> Position {
@@ -138,4 +291,112 @@ mod tests {
 		sum_tree.update_leaf_node_sample(1, 8); // 2 -> 8
 		assert_eq!(sum_tree.get_root_sum(), 33);
 	}
+
+	#[test]
+	fn single_leaf_node() {
+		let mut sum_tree = SumTree::new(0, 1);
+
+		sum_tree.update_leaf_node_sample(0, 1);
+		assert_eq!(sum_tree.get_root_sum(), 1);
+
+		sum_tree.update_leaf_node_samples(&[(0, 2)]);
+		assert_eq!(sum_tree.get_root_sum(), 2);
+	}
+
+	#[test]
+	fn batch_update() {
+		let mut sum_tree = SumTree::new(0, 6);
+		let mut reference_sum_tree = SumTree::new(0, 6);
+
+		let leaf_node_samples = [(4, 5), (5, 6), (0, 7), (1, 8)];
+		sum_tree.update_leaf_node_samples(&leaf_node_samples);
+		for (leaf_node_idx, sample) in leaf_node_samples {
+			reference_sum_tree.update_leaf_node_sample(leaf_node_idx, sample);
+		}
+		assert_eq!(sum_tree.nodes, reference_sum_tree.nodes);
+		assert_eq!(sum_tree.get_root_sum(), 26);
+
+		sum_tree.update_leaf_node_samples(&[]);
+		assert_eq!(sum_tree.get_root_sum(), 26);
+	}
+
+	#[test]
+	fn build_from_slice() {
+		for num_samples in 0..=6 {
+			let samples: Vec<u32> = (1..=num_samples).collect();
+
+			let sum_tree = SumTree::build_from_slice(0, 6, &samples);
+			let mut reference_sum_tree = SumTree::new(0, 6);
+			for (leaf_node_idx, sample) in samples.iter().enumerate() {
+				reference_sum_tree.update_leaf_node_sample(leaf_node_idx, *sample);
+			}
+
+			assert_eq!(sum_tree.nodes, reference_sum_tree.nodes);
+			assert_eq!(sum_tree.get_root_sum(), samples.iter().sum::<u32>());
+		}
+
+		let sum_tree = SumTree::build_from_slice(0, 1, &[3]);
+		assert_eq!(sum_tree.get_root_sum(), 3);
+	}
+
+	#[test]
+	fn range_sum() {
+		for num_leaf_nodes in [1, 2, 3, 6, 8, 13] {
+			let samples: Vec<u32> = (1..=num_leaf_nodes).map(|sample| sample * sample).collect();
+			let sum_tree = SumTree::build_from_slice(0, num_leaf_nodes as usize, &samples);
+
+			for start_leaf_node_idx in 0..=samples.len() {
+				for end_leaf_node_idx in start_leaf_node_idx..=samples.len() {
+					assert_eq!(
+						sum_tree.get_range_sum(start_leaf_node_idx, end_leaf_node_idx),
+						samples[start_leaf_node_idx..end_leaf_node_idx]
+							.iter()
+							.sum::<u32>()
+					);
+				}
+			}
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "num-traits")]
+	fn checked_update() {
+		let mut sum_tree = SumTree::build_from_slice(0u8, 4, &[100, 50, 50, 50]);
+
+		assert_eq!(sum_tree.checked_update_leaf_node_sample(0, 106), None);
+		assert_eq!(sum_tree.get_root_sum(), 250);
+		assert_eq!(sum_tree.get_leaf_node_sum(&0), 100);
+
+		assert_eq!(sum_tree.checked_update_leaf_node_sample(0, 105), Some(()));
+		assert_eq!(sum_tree.get_root_sum(), 255);
+		assert_eq!(
+			sum_tree.nodes,
+			SumTree::build_from_slice(0, 4, &[105, 50, 50, 50]).nodes
+		);
+	}
+
+	#[test]
+	fn depth_and_node_count() {
+		let depths_and_node_counts: Vec<(usize, usize)> = [1, 2, 3, 4, 5, 8, 9, 1000]
+			.iter()
+			.map(|num_leaf_nodes| {
+				let sum_tree = SumTree::new(0, *num_leaf_nodes);
+				(sum_tree.get_depth(), sum_tree.get_node_count())
+			})
+			.collect();
+
+		assert_eq!(
+			depths_and_node_counts,
+			vec![
+				(1, 1),
+				(2, 3),
+				(3, 7),
+				(3, 7),
+				(4, 15),
+				(4, 15),
+				(5, 31),
+				(11, 2047)
+			]
+		);
+	}
 }
@@ -1,19 +1,41 @@
 use super::{sum_tree::SumTree, SMA};
-use crate::{common::cast_to_divisor_type, ring_buffer::RingBuffer, Iter};
-use num_traits::{FromPrimitive, Zero};
+use crate::{
+	common::{cast_to_divisor_type, wrapping_add, wrapping_sub},
+	ring_buffer::RingBuffer,
+	DivisorFromCount, IntoIter, Iter, Snapshot,
+};
+#[cfg(feature = "num-traits")]
+use crate::{rounding_mode::divide_rounded, OverflowError, RoundingMode, WindowSizeError};
+#[cfg(feature = "num-traits")]
+use num_traits::{CheckedAdd, PrimInt, Zero};
+#[cfg(all(feature = "serde", feature = "num-traits"))]
+use serde::{de, Deserialize, Deserializer};
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer};
+#[cfg(feature = "num-traits")]
+use std::iter::FromIterator;
 use std::{
+	any::type_name,
+	convert::TryFrom,
+	fmt,
+	hash::{Hash, Hasher},
 	marker::{self, PhantomData},
-	ops::{Add, Div},
+	mem::size_of,
+	ops::{Add, AddAssign, Div, Sub},
 };
 
 type SumTreeNodeIdx = usize;
 
 /// An SMA implementation that caches the sum of all samples currently in the sample window as a
 /// tree of sums.
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct SumTreeSMA<Sample, Divisor, const WINDOW_SIZE: usize> {
 	samples: RingBuffer<SumTreeNodeIdx, WINDOW_SIZE>,
 	sum_tree: SumTree<Sample>,
+	// Incremented whenever the sample window is modified
+	generation: u64,
+	// The number of samples that get_average_if_ready requires
+	min_samples: usize,
 	_marker: marker::PhantomData<Divisor>,
 }
 
@@ -21,25 +43,36 @@ impl<Sample, Divisor, const WINDOW_SIZE: usize> SMA<Sample, Divisor, WINDOW_SIZE
 	for SumTreeSMA<Sample, Divisor, WINDOW_SIZE>
 where
 	Sample: Copy + Add<Output = Sample> + Div<Divisor, Output = Sample>,
-	Divisor: FromPrimitive,
+	Divisor: DivisorFromCount,
 {
 	fn add_sample(&mut self, new_sample: Sample) {
 		if WINDOW_SIZE == 0 {
 			return;
 		}
 
-		let tree_node_idx = if self.samples.len() < WINDOW_SIZE {
-			self.samples.len()
-		} else {
-			self.samples.pop_back().unwrap()
-		};
-
-		self.samples.push_front(tree_node_idx);
+		let tree_node_idx = self.push_tree_node_idx();
 
 		self.sum_tree
 			.update_leaf_node_sample(tree_node_idx, new_sample);
 	}
 
+	fn add_samples(&mut self, new_samples: &[Sample]) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+
+		// Samples that would be pushed out of the sample window by the later samples of the same
+		// batch never need to be written to the sum tree
+		let new_samples = &new_samples[new_samples.len().saturating_sub(WINDOW_SIZE)..];
+
+		let leaf_node_samples: Vec<(SumTreeNodeIdx, Sample)> = new_samples
+			.iter()
+			.map(|new_sample| (self.push_tree_node_idx(), *new_sample))
+			.collect();
+
+		self.sum_tree.update_leaf_node_samples(&leaf_node_samples);
+	}
+
 	fn get_average(&self) -> Sample {
 		let num_samples = self.samples.len();
 
@@ -50,12 +83,80 @@ where
 		self.sum_tree.get_root_sum() / cast_to_divisor_type(num_samples)
 	}
 
+	#[cfg(feature = "num-traits")]
+	fn get_average_as<T>(&self) -> T
+	where
+		Self: Sized,
+		Sample: Copy + Into<T>,
+		T: Zero + Div<Output = T> + DivisorFromCount,
+	{
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return T::zero();
+		}
+
+		self.sum_tree.get_root_sum().into() / cast_to_divisor_type::<T>(num_samples)
+	}
+
+	#[cfg(feature = "num-traits")]
+	fn get_rounded_average(&self, rounding_mode: RoundingMode) -> Sample
+	where
+		Sample: PrimInt + DivisorFromCount,
+	{
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return Sample::zero();
+		}
+
+		divide_rounded(
+			self.sum_tree.get_root_sum(),
+			cast_to_divisor_type::<Sample>(num_samples),
+			rounding_mode,
+		)
+	}
+
+	// The most recent samples occupy a contiguous range of leaf nodes, which ends at the leaf node
+	// of the most recent sample, and wraps around to the last leaf nodes if the range is longer
+	fn get_average_of_last(&self, num_samples: usize) -> Option<Sample>
+	where
+		Sample: Copy + Add<Output = Sample> + Div<Divisor, Output = Sample>,
+		Divisor: DivisorFromCount,
+	{
+		let num_samples = num_samples.min(self.samples.len());
+		if num_samples == 0 {
+			return None;
+		}
+
+		let end_idx = self
+			.samples
+			.front()
+			.map_or(0, |tree_node_idx| tree_node_idx + 1);
+		let sum = if num_samples <= end_idx {
+			self.sum_tree.get_range_sum(end_idx - num_samples, end_idx)
+		} else {
+			self.sum_tree.get_range_sum(0, end_idx)
+				+ self
+					.sum_tree
+					.get_range_sum(WINDOW_SIZE - (num_samples - end_idx), WINDOW_SIZE)
+		};
+
+		Some(sum / cast_to_divisor_type(num_samples))
+	}
+
 	fn get_most_recent_sample(&self) -> Option<Sample> {
 		self.samples
 			.front()
 			.map(|node_idx| self.sum_tree.get_leaf_node_sum(node_idx))
 	}
 
+	fn get_oldest_sample(&self) -> Option<Sample> {
+		self.samples
+			.back()
+			.map(|node_idx| self.sum_tree.get_leaf_node_sum(node_idx))
+	}
+
 	fn get_num_samples(&self) -> usize {
 		self.samples.len()
 	}
@@ -64,19 +165,96 @@ where
 		WINDOW_SIZE
 	}
 
-	fn get_sample_window_iter(&self) -> Iter<Sample, WINDOW_SIZE> {
-		let num_samples = self.get_num_samples();
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
+		self.iter()
+	}
+}
+
+impl<Sample: Copy, Divisor, const WINDOW_SIZE: usize> SumTreeSMA<Sample, Divisor, WINDOW_SIZE> {
+	// The leaf nodes are assigned to samples round-robin, so they form a ring buffer of samples,
+	// whose most recent sample is the one in the leaf node at the front of `samples`.
+	fn iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
 		Iter::new(
-			self.sum_tree.get_leaf_nodes(num_samples),
-			num_samples,
-			num_samples,
+			self.sum_tree.get_leaf_nodes(WINDOW_SIZE),
+			self.get_end_idx(),
+			self.samples.len(),
 		)
 	}
+
+	// Returns the index of the leaf node after the one of the most recent sample
+	fn get_end_idx(&self) -> usize {
+		self.samples
+			.front()
+			.map_or(0, |tree_node_idx| (tree_node_idx + 1) % WINDOW_SIZE)
+	}
+
+	/// Consumes the [SumTreeSMA], returning the samples in the sample window, oldest first. The
+	/// samples are moved within the allocation of the sum tree, which is reused for the returned
+	/// [Vec].
+	pub fn into_inner(self) -> Vec<Sample> {
+		let num_samples = self.samples.len();
+		let end_idx = self.get_end_idx();
+
+		// After the rotation, the samples are the last `num_samples` leaf nodes
+		let mut samples = self.sum_tree.into_leaf_nodes(WINDOW_SIZE);
+		samples.rotate_left(end_idx);
+		samples.drain(..WINDOW_SIZE - num_samples);
+		samples
+	}
+
+	/// Returns the number of bytes of memory used by this instance. This includes both the stack
+	/// allocated sample window and the heap allocated sum tree, all of whose nodes are allocated
+	/// up front.
+	pub fn get_memory_footprint(&self) -> usize {
+		size_of::<Self>() + self.sum_tree.get_heap_size()
+	}
+
+	/// Returns the generation of the sample window, a counter that is incremented whenever it is
+	/// modified, e.g. by adding a sample. A reader that polls the average, e.g. through a
+	/// [SharedSMA](crate::SharedSMA), can compare the generation with the one it last saw to find
+	/// out whether anything has changed, without recalculating or comparing anything. Reading never
+	/// changes the generation.
+	pub fn get_generation(&self) -> u64 {
+		self.generation
+	}
+
+	/// Returns the number of node levels of the sum tree, including the root and leaf node levels,
+	/// i.e. `ceil(log2(WINDOW_SIZE)) + 1`. Adding a sample updates one node per level.
+	pub fn get_tree_depth(&self) -> usize {
+		self.sum_tree.get_depth()
+	}
+
+	/// Returns the total number of nodes of the sum tree, i.e. the number of sums that are stored
+	/// in addition to the samples' sum tree leaf node indices. Since the sum tree is a perfect
+	/// binary tree, this is `2 * WINDOW_SIZE.next_power_of_two() - 1`.
+	pub fn get_tree_node_count(&self) -> usize {
+		self.sum_tree.get_node_count()
+	}
+
+	// Returns the index of the sum tree leaf node that the next sample should be written to, which
+	// is the leaf node of the oldest sample if the sample window is full. Since samples are only
+	// ever removed oldest first, the samples occupy a contiguous, possibly wrapping, range of leaf
+	// nodes, and the leaf node after that of the most recent sample is always free or the oldest.
+	fn push_tree_node_idx(&mut self) -> SumTreeNodeIdx {
+		let tree_node_idx = if self.samples.len() < WINDOW_SIZE {
+			self.get_end_idx()
+		} else {
+			self.samples.pop_back().unwrap()
+		};
+
+		self.samples.push_front(tree_node_idx);
+		self.generation += 1;
+
+		tree_node_idx
+	}
 }
 
+#[cfg(feature = "num-traits")]
 impl<Sample: Copy + Zero, Divisor, const WINDOW_SIZE: usize>
 	SumTreeSMA<Sample, Divisor, WINDOW_SIZE>
 {
+	const NONZERO_WINDOW_SIZE_CHECK: () = assert!(0 < WINDOW_SIZE, "WINDOW_SIZE must be non-zero");
+
 	/// Constructs a new [SumTreeSMA] with window size `WINDOW_SIZE`. This constructor is
 	/// only available for `Sample` types that implement [num_traits::Zero]. If the `Sample` type
 	/// does not, use the [from_zero](SumTreeSMA::from_zero) constructor instead.
@@ -87,12 +265,41 @@ impl<Sample: Copy + Zero, Divisor, const WINDOW_SIZE: usize>
 		Self {
 			samples: RingBuffer::new(0),
 			sum_tree: SumTree::new(Sample::zero(), WINDOW_SIZE),
+			generation: 0,
+			min_samples: 0,
 			_marker: PhantomData,
 		}
 	}
+
+	/// Constructs a new [SumTreeSMA] with window size `WINDOW_SIZE`, just like [new](SumTreeSMA::new),
+	/// except that a `WINDOW_SIZE` of zero fails to compile, rather than resulting in an SMA that
+	/// ignores all samples.
+	///
+	/// ```compile_fail
+	/// # use simple_moving_average::SumTreeSMA;
+	/// let sma = SumTreeSMA::<f32, f32, 0>::new_nonzero();
+	/// ```
+	pub fn new_nonzero() -> Self {
+		let () = Self::NONZERO_WINDOW_SIZE_CHECK;
+
+		Self::new()
+	}
+}
+
+#[cfg(feature = "num-traits")]
+impl<Sample: Copy + Zero, Divisor, const WINDOW_SIZE: usize> Default
+	for SumTreeSMA<Sample, Divisor, WINDOW_SIZE>
+{
+	fn default() -> Self {
+		Self::new()
+	}
 }
 
 impl<Sample: Copy, Divisor, const WINDOW_SIZE: usize> SumTreeSMA<Sample, Divisor, WINDOW_SIZE> {
+	/// The sample window size, as a constant that can be used in const contexts, e.g. to size an
+	/// array. Equal to [get_sample_window_size](SMA::get_sample_window_size).
+	pub const WINDOW_SIZE: usize = WINDOW_SIZE;
+
 	/// Constructs a new [SumTreeSMA] with window size `WINDOW_SIZE` from the given
 	/// `zero` sample. If the `Sample` type implements [num_traits::Zero], the
 	/// [new](SumTreeSMA::new) constructor might be preferable to this.
@@ -100,7 +307,436 @@ impl<Sample: Copy, Divisor, const WINDOW_SIZE: usize> SumTreeSMA<Sample, Divisor
 		Self {
 			samples: RingBuffer::new(0),
 			sum_tree: SumTree::new(zero, WINDOW_SIZE),
+			generation: 0,
+			min_samples: 0,
 			_marker: PhantomData,
 		}
 	}
 }
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> SumTreeSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Add<Output = Sample> + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	/// Returns the average of the samples that are in the sample window of `self`, but not in
+	/// that of `other`, assuming that the sample window of `other` contains the most recent
+	/// samples of the sample window of `self`, e.g. because both were fed the same samples. This is
+	/// calculated in `O(1)` time from the cached sums.
+	///
+	/// Only the sample counts are checked, not the samples themselves: `None` is returned if
+	/// `other` does not contain fewer samples than `self`.
+	pub fn get_difference_average<const OTHER_WINDOW_SIZE: usize>(
+		&self,
+		other: &SumTreeSMA<Sample, Divisor, OTHER_WINDOW_SIZE>,
+	) -> Option<Sample>
+	where
+		Sample: Sub<Output = Sample>,
+	{
+		let num_samples = self
+			.get_num_samples()
+			.checked_sub(other.get_num_samples())?;
+		if num_samples == 0 {
+			return None;
+		}
+
+		let sum = self.sum_tree.get_root_sum() - other.sum_tree.get_root_sum();
+		Some(sum / cast_to_divisor_type(num_samples))
+	}
+
+	/// Adds the samples in the sample window of `other` to `self`, oldest first, as if they had been
+	/// added one by one. Only the last `WINDOW_SIZE` of the combined samples remain in the sample
+	/// window. Each sum tree node is updated at most once.
+	pub fn merge_window(&mut self, other: &Self) {
+		let samples: Vec<Sample> = other.iter().cloned().collect();
+		self.add_samples(&samples);
+	}
+
+	/// Sets the number of samples that the sample window must contain for
+	/// [get_average_if_ready](SumTreeSMA::get_average_if_ready) to return an average, which lets a
+	/// caller trade responsiveness for stability, rather than waiting for the sample window to be
+	/// full. Defaults to zero, i.e. no threshold.
+	///
+	/// # Panics
+	///
+	/// Panics if `min_samples` exceeds `WINDOW_SIZE`.
+	pub fn with_min_samples(mut self, min_samples: usize) -> Self {
+		assert!(
+			min_samples <= WINDOW_SIZE,
+			"min_samples must not exceed WINDOW_SIZE"
+		);
+		self.min_samples = min_samples;
+		self
+	}
+
+	/// Returns the number of samples that the sample window must contain for
+	/// [get_average_if_ready](SumTreeSMA::get_average_if_ready) to return an average.
+	pub fn get_min_samples(&self) -> usize {
+		self.min_samples
+	}
+
+	/// Returns the simple moving average value of all the samples in the sample window, but only
+	/// once it contains at least [get_min_samples](SumTreeSMA::get_min_samples) samples. Before that,
+	/// `None` is returned.
+	pub fn get_average_if_ready(&self) -> Option<Sample> {
+		if self.get_num_samples() < self.min_samples {
+			return None;
+		}
+
+		Some(self.get_average())
+	}
+
+	/// Adds a sample to the series of samples, like [add_sample](SMA::add_sample), but using checked
+	/// arithmetic to update the sum tree. Returns the sample that was dropped from the sample window,
+	/// if any, or an error if any of the sums in the sum tree would overflow, in which case the
+	/// sample is not added.
+	#[cfg(feature = "num-traits")]
+	pub fn checked_add_sample(
+		&mut self,
+		new_sample: Sample,
+	) -> Result<Option<Sample>, OverflowError>
+	where
+		Sample: CheckedAdd,
+	{
+		if WINDOW_SIZE == 0 {
+			return Ok(None);
+		}
+
+		let (tree_node_idx, shifted_sample) = if self.samples.len() < WINDOW_SIZE {
+			(self.get_end_idx(), None)
+		} else {
+			let tree_node_idx = *self.samples.back().unwrap();
+			(
+				tree_node_idx,
+				Some(self.sum_tree.get_leaf_node_sum(&tree_node_idx)),
+			)
+		};
+
+		self.sum_tree
+			.checked_update_leaf_node_sample(tree_node_idx, new_sample)
+			.ok_or_else(|| OverflowError::new(type_name::<Sample>()))?;
+		self.push_tree_node_idx();
+
+		Ok(shifted_sample)
+	}
+
+	/// Constructs a new [SumTreeSMA] with window size `WINDOW_SIZE`, whose sample window is completely
+	/// filled with the samples returned by `f(0)` (the oldest) through `f(WINDOW_SIZE - 1)` (the
+	/// most recent), like [std::array::from_fn]. The sum tree is built from the
+	/// samples in a single `O(N)` pass.
+	#[cfg(feature = "num-traits")]
+	pub fn from_fn<F: FnMut(usize) -> Sample>(f: F) -> Self
+	where
+		Sample: Zero,
+	{
+		let samples: Vec<Sample> = (0..WINDOW_SIZE).map(f).collect();
+		Self::from_slice(&samples)
+	}
+
+	/// Constructs a new [SumTreeSMA] with window size `WINDOW_SIZE`, whose sample window is completely
+	/// filled with `value`. This constructor is only available for `Sample` types that implement
+	/// [num_traits::Zero]. If the `Sample` type does not, use the
+	/// [with_initial_from_zero](SumTreeSMA::with_initial_from_zero) constructor instead.
+	#[cfg(feature = "num-traits")]
+	pub fn with_initial(value: Sample) -> Self
+	where
+		Sample: Zero,
+	{
+		Self::with_initial_from_zero(Sample::zero(), value)
+	}
+
+	/// Constructs a new [SumTreeSMA] with window size `WINDOW_SIZE`, whose sample window contains
+	/// the last (up to) `WINDOW_SIZE` of the given `samples`, the last sample being the most recent
+	/// one. The sum tree is built in a single `O(N)` pass, rather than by adding the samples one by
+	/// one.
+	#[cfg(feature = "num-traits")]
+	pub fn from_slice(samples: &[Sample]) -> Self
+	where
+		Sample: Zero,
+	{
+		let mut sma = Self::from_zero(Sample::zero());
+		sma.rebuild_from_slice(samples);
+		sma
+	}
+
+	/// Removes the samples for which `predicate` returns `false` from the sample window, e.g.
+	/// samples that turned out to be invalid, keeping the order of the remaining samples. The
+	/// remaining samples are moved within the leaf nodes of the sum tree, after which its internal
+	/// nodes are recalculated in a single `O(N)` pass.
+	///
+	/// This temporarily breaks the invariant that the sample window holds the last `WINDOW_SIZE`
+	/// samples, until enough new samples have been added to fill it up again.
+	pub fn retain<F: FnMut(&Sample) -> bool>(&mut self, mut predicate: F) {
+		let zero = self.sum_tree.get_zero();
+		let num_samples = self.samples.len();
+		let start_idx = wrapping_sub::<WINDOW_SIZE>(self.get_end_idx(), num_samples);
+		let leaf_nodes = self.sum_tree.get_leaf_nodes_mut(WINDOW_SIZE);
+
+		// The leaf nodes of the removed samples must be zeroed, as they still count towards the sum
+		let mut num_retained_samples = 0;
+		for offset in 0..num_samples {
+			let leaf_node_idx = wrapping_add::<WINDOW_SIZE>(start_idx, offset);
+			let sample = leaf_nodes[leaf_node_idx];
+			leaf_nodes[leaf_node_idx] = zero;
+			if predicate(&sample) {
+				leaf_nodes[wrapping_add::<WINDOW_SIZE>(start_idx, num_retained_samples)] = sample;
+				num_retained_samples += 1;
+			}
+		}
+		self.sum_tree.update_internal_nodes();
+
+		self.samples.clear();
+		for offset in 0..num_retained_samples {
+			self.samples
+				.push_front(wrapping_add::<WINDOW_SIZE>(start_idx, offset));
+		}
+		self.generation += 1;
+	}
+
+	/// Removes the oldest sample from the sample window, without adding a new sample, by resetting
+	/// its sum tree leaf node to zero. Returns the removed sample, or `None` if the sample window is
+	/// empty.
+	///
+	/// This deliberately breaks the invariant that the sample window holds the last `WINDOW_SIZE`
+	/// samples, until enough new samples have been added to fill it up again.
+	pub fn shift_out(&mut self) -> Option<Sample> {
+		let tree_node_idx = self.samples.pop_back()?;
+		let shifted_sample = self.sum_tree.get_leaf_node_sum(&tree_node_idx);
+		self.sum_tree
+			.update_leaf_node_sample(tree_node_idx, self.sum_tree.get_zero());
+		self.generation += 1;
+		Some(shifted_sample)
+	}
+
+	/// Completely fills the sample window with `value`, dropping all samples that were in it, so
+	/// that the average becomes `value`. Unlike emptying the sample window, this resets it to a
+	/// known steady state, e.g. after a regime change. The sum tree is rebuilt in a single `O(N)`
+	/// pass.
+	pub fn reset_to(&mut self, value: Sample) {
+		self.rebuild_from_slice(&[value; WINDOW_SIZE]);
+	}
+
+	/// Returns a [Snapshot] of the samples in the sample window, which can later be restored using
+	/// [restore](SumTreeSMA::restore).
+	pub fn snapshot(&self) -> Snapshot<Sample> {
+		Snapshot::new(self.iter().cloned().collect(), None)
+	}
+
+	/// Replaces the samples in the sample window with those of `snapshot`, rebuilding the sum tree in
+	/// a single `O(N)` pass. If the snapshot contains more samples than fit in the sample window,
+	/// only the most recent ones are kept.
+	pub fn restore(&mut self, snapshot: Snapshot<Sample>) {
+		let (samples, _) = snapshot.into_parts();
+		self.rebuild_from_slice(&samples);
+	}
+
+	fn rebuild_from_slice(&mut self, samples: &[Sample]) {
+		let samples = &samples[samples.len().saturating_sub(WINDOW_SIZE)..];
+
+		self.sum_tree = SumTree::build_from_slice(self.sum_tree.get_zero(), WINDOW_SIZE, samples);
+		self.samples.clear();
+		for tree_node_idx in 0..samples.len() {
+			self.samples.push_front(tree_node_idx);
+		}
+		self.generation += 1;
+	}
+
+	/// Constructs a new [SumTreeSMA] with window size `WINDOW_SIZE` from the given `zero` sample, whose
+	/// sample window is completely filled with `value`.
+	pub fn with_initial_from_zero(zero: Sample, value: Sample) -> Self {
+		let mut sma = Self::from_zero(zero);
+		for _ in 0..WINDOW_SIZE {
+			sma.add_sample(value);
+		}
+		sma
+	}
+}
+
+#[cfg(feature = "num-traits")]
+impl<Sample, Divisor, const WINDOW_SIZE: usize> FromIterator<Sample>
+	for SumTreeSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Zero + Add<Output = Sample> + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	/// Constructs a new [SumTreeSMA] and adds all samples yielded by `iter` to it. Only the last
+	/// `WINDOW_SIZE` samples will end up in the sample window.
+	fn from_iter<I: IntoIterator<Item = Sample>>(iter: I) -> Self {
+		let mut sma = Self::new();
+		sma.extend(iter);
+		sma
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> Extend<Sample>
+	for SumTreeSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Add<Output = Sample> + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	fn extend<I: IntoIterator<Item = Sample>>(&mut self, iter: I) {
+		for sample in iter {
+			self.add_sample(sample);
+		}
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> AddAssign<Sample>
+	for SumTreeSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Add<Output = Sample> + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	/// Adds a sample to the series of samples, like [add_sample](SMA::add_sample).
+	fn add_assign(&mut self, new_sample: Sample) {
+		self.add_sample(new_sample);
+	}
+}
+
+#[cfg(feature = "num-traits")]
+impl<Sample, Divisor, const WINDOW_SIZE: usize> From<[Sample; WINDOW_SIZE]>
+	for SumTreeSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Zero + Add<Output = Sample> + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	/// Constructs a new [SumTreeSMA] whose sample window is completely filled with `samples`, the last
+	/// sample being the most recent one.
+	fn from(samples: [Sample; WINDOW_SIZE]) -> Self {
+		Self::from_slice(&samples)
+	}
+}
+
+#[cfg(feature = "num-traits")]
+impl<Sample, Divisor, const WINDOW_SIZE: usize> TryFrom<&[Sample]>
+	for SumTreeSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Zero + Add<Output = Sample> + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	type Error = WindowSizeError;
+
+	/// Constructs a new [SumTreeSMA] whose sample window contains `samples`, the last sample being the
+	/// most recent one. Fails if there are more samples than fit in the sample window, rather than
+	/// silently dropping the oldest ones.
+	fn try_from(samples: &[Sample]) -> Result<Self, Self::Error> {
+		if WINDOW_SIZE < samples.len() {
+			return Err(WindowSizeError::new(samples.len(), WINDOW_SIZE));
+		}
+
+		Ok(Self::from_slice(samples))
+	}
+}
+
+impl<Sample: Copy, Divisor, const WINDOW_SIZE: usize> IntoIterator
+	for SumTreeSMA<Sample, Divisor, WINDOW_SIZE>
+{
+	type Item = Sample;
+	type IntoIter = IntoIter<Sample, WINDOW_SIZE>;
+
+	/// Consumes the [SumTreeSMA], returning an iterator over the samples in the sample window,
+	/// oldest first.
+	fn into_iter(self) -> Self::IntoIter {
+		let leaf_nodes =
+			<[Sample; WINDOW_SIZE]>::try_from(self.sum_tree.get_leaf_nodes(WINDOW_SIZE)).unwrap();
+		IntoIter::new(leaf_nodes, self.get_end_idx(), self.samples.len())
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> fmt::Debug
+	for SumTreeSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + fmt::Debug,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("SumTreeSMA")
+			.field("window_size", &WINDOW_SIZE)
+			.field("num_samples", &self.samples.len())
+			.field("samples", &self.iter().collect::<Vec<&Sample>>())
+			.field("sum", &self.sum_tree.get_root_sum())
+			.field("divisor", &type_name::<Divisor>())
+			.finish()
+	}
+}
+
+/// Formats the average of the samples in the sample window, as returned by
+/// [get_average](SMA::get_average), forwarding the formatter's flags, e.g. its precision.
+impl<Sample, Divisor, const WINDOW_SIZE: usize> fmt::Display
+	for SumTreeSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Add<Output = Sample> + Div<Divisor, Output = Sample> + fmt::Display,
+	Divisor: DivisorFromCount,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&self.get_average(), f)
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> PartialEq
+	for SumTreeSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + PartialEq,
+{
+	/// Two instances are equal if their sample windows contain the same samples, in the same order.
+	/// The layout of the sum tree is not compared.
+	fn eq(&self, other: &Self) -> bool {
+		self.iter().eq(other.iter())
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> Eq for SumTreeSMA<Sample, Divisor, WINDOW_SIZE> where
+	Sample: Copy + Eq
+{
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> Hash for SumTreeSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Hash,
+{
+	/// Hashes the number of samples and the samples in the sample window, in order, consistent with
+	/// [PartialEq].
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		state.write_usize(self.samples.len());
+		for sample in self.iter() {
+			sample.hash(state);
+		}
+	}
+}
+
+/// Serializes the samples in the sample window as a sequence, oldest first. The internal nodes of
+/// the sum tree are not serialized, since they can be derived from the samples, which keeps the
+/// serialized state small and independent of the layout of the sum tree.
+#[cfg(feature = "serde")]
+impl<Sample, Divisor, const WINDOW_SIZE: usize> Serialize
+	for SumTreeSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Serialize,
+{
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_seq(self.iter())
+	}
+}
+
+/// Deserializes a sequence of samples, oldest first, as serialized by the [Serialize] impl, and
+/// rebuilds the sum tree from them in a single `O(N)` pass, like
+/// [from_slice](SumTreeSMA::from_slice). Fails if the sequence contains more than `WINDOW_SIZE`
+/// samples.
+#[cfg(all(feature = "serde", feature = "num-traits"))]
+impl<'de, Sample, Divisor, const WINDOW_SIZE: usize> Deserialize<'de>
+	for SumTreeSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + Zero + Add<Output = Sample> + Div<Divisor, Output = Sample> + Deserialize<'de>,
+	Divisor: DivisorFromCount,
+{
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let samples = Vec::<Sample>::deserialize(deserializer)?;
+		if WINDOW_SIZE < samples.len() {
+			return Err(de::Error::invalid_length(
+				samples.len(),
+				&format!("at most {} samples", WINDOW_SIZE).as_str(),
+			));
+		}
+
+		Ok(Self::from_slice(&samples))
+	}
+}
@@ -0,0 +1,170 @@
+use num_traits::{Float, Zero};
+use std::{
+	marker::{self, PhantomData},
+	ops::{Add, Mul},
+	time::{Duration, Instant},
+};
+
+/// Calculates an exponentially time-decayed moving average of samples that arrive at irregular
+/// intervals, where the influence of a sample decays with the time elapsed since it was added,
+/// rather than with the number of samples added after it.
+///
+/// Every time a sample is added at time `t`, the average is updated as
+/// `average * decay + new_sample * (1 - decay)`, where `decay = exp(-(t - last_t) / tau)`, `last_t`
+/// being the time of the previous sample and `tau` the time constant, i.e. the time it takes for
+/// the weight of a sample to decay by a factor of `e`. The average is seeded with the first sample
+/// added. Samples with a time before that of the previous sample are treated as if no time had
+/// elapsed, which means that they do not affect the average.
+///
+/// Only the current average is stored, so adding a sample and reading the average are both `O(1)`
+/// operations. Since there is no sample window, this type does not implement the
+/// [SMA](crate::SMA) trait.
+#[derive(Clone, Copy, Debug)]
+pub struct TimeDecayMovingAverage<Sample, Divisor> {
+	average: Sample,
+	tau: Duration,
+	last_instant: Option<Instant>,
+	_marker: marker::PhantomData<Divisor>,
+}
+
+impl<Sample, Divisor> TimeDecayMovingAverage<Sample, Divisor>
+where
+	Sample: Copy + Add<Output = Sample> + Mul<Divisor, Output = Sample>,
+	Divisor: Float,
+{
+	/// Adds a sample taken at `instant` to the series of samples.
+	pub fn add_sample_at(&mut self, new_sample: Sample, instant: Instant) {
+		let last_instant = match self.last_instant {
+			Some(last_instant) => last_instant,
+			None => {
+				self.average = new_sample;
+				self.last_instant = Some(instant);
+				return;
+			}
+		};
+
+		let elapsed = instant.saturating_duration_since(last_instant);
+		let decay = Divisor::from(-elapsed.as_secs_f64() / self.tau.as_secs_f64())
+			.unwrap_or_else(Divisor::neg_infinity)
+			.exp();
+		self.average = self.average * decay + new_sample * (Divisor::one() - decay);
+		self.last_instant = Some(last_instant.max(instant));
+	}
+
+	/// Returns the time-decayed average of all samples added so far.
+	pub fn get_average(&self) -> Sample {
+		self.average
+	}
+
+	/// Returns the time of the most recently added sample, if any.
+	pub fn get_last_instant(&self) -> Option<Instant> {
+		self.last_instant
+	}
+
+	/// Returns the time constant.
+	pub fn get_tau(&self) -> Duration {
+		self.tau
+	}
+}
+
+impl<Sample: Copy + Zero, Divisor> TimeDecayMovingAverage<Sample, Divisor> {
+	/// Constructs a new [TimeDecayMovingAverage] with time constant `tau`. This constructor is only
+	/// available for `Sample` types that implement [num_traits::Zero]. If the `Sample` type does
+	/// not, use the [from_zero](TimeDecayMovingAverage::from_zero) constructor instead.
+	///
+	/// Note that the `Divisor` type, which the decay factors are calculated in, usually cannot be
+	/// derived by the compiler when using this constructor and must be explicitly stated.
+	///
+	/// # Panics
+	///
+	/// Panics if `tau` is zero.
+	pub fn new(tau: Duration) -> Self {
+		Self::from_zero(Sample::zero(), tau)
+	}
+}
+
+impl<Sample: Copy, Divisor> TimeDecayMovingAverage<Sample, Divisor> {
+	/// Constructs a new [TimeDecayMovingAverage] with time constant `tau` from the given `zero`
+	/// sample. If the `Sample` type implements [num_traits::Zero], the
+	/// [new](TimeDecayMovingAverage::new) constructor might be preferable to this.
+	///
+	/// # Panics
+	///
+	/// Panics if `tau` is zero.
+	pub fn from_zero(zero: Sample, tau: Duration) -> Self {
+		assert!(!tau.is_zero(), "The time constant must be non-zero");
+		Self {
+			average: zero,
+			tau,
+			last_instant: None,
+			_marker: PhantomData,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn decay() {
+		let start = Instant::now();
+		let mut tdma = TimeDecayMovingAverage::<f64, f64>::new(Duration::from_secs(2));
+		assert_eq!(tdma.get_average(), 0.0);
+		assert_eq!(tdma.get_last_instant(), None);
+
+		tdma.add_sample_at(10.0, start);
+		assert_eq!(tdma.get_average(), 10.0);
+
+		tdma.add_sample_at(20.0, start + Duration::from_secs(1));
+		let decay = (-0.5f64).exp();
+		let expected = 10.0 * decay + 20.0 * (1.0 - decay);
+		assert!((tdma.get_average() - expected).abs() < 1e-12);
+
+		tdma.add_sample_at(0.0, start + Duration::from_secs(5));
+		let decay = (-2.0f64).exp();
+		let expected = expected * decay;
+		assert!((tdma.get_average() - expected).abs() < 1e-12);
+		assert_eq!(
+			tdma.get_last_instant(),
+			Some(start + Duration::from_secs(5))
+		);
+	}
+
+	#[test]
+	fn irregular_intervals() {
+		let start = Instant::now();
+		let mut tdma = TimeDecayMovingAverage::<f64, f64>::new(Duration::from_millis(100));
+
+		// A long gap makes the new sample dominate, a short one barely moves the average
+		tdma.add_sample_at(0.0, start);
+		tdma.add_sample_at(1.0, start + Duration::from_secs(10));
+		assert!((tdma.get_average() - 1.0).abs() < 1e-12);
+
+		tdma.add_sample_at(
+			100.0,
+			start + Duration::from_secs(10) + Duration::from_micros(1),
+		);
+		assert!((tdma.get_average() - 1.0).abs() < 0.001);
+	}
+
+	#[test]
+	fn out_of_order() {
+		let start = Instant::now();
+		let mut tdma = TimeDecayMovingAverage::<f32, f32>::new(Duration::from_secs(1));
+
+		tdma.add_sample_at(4.0, start + Duration::from_secs(3));
+		tdma.add_sample_at(8.0, start);
+		assert_eq!(tdma.get_average(), 4.0);
+		assert_eq!(
+			tdma.get_last_instant(),
+			Some(start + Duration::from_secs(3))
+		);
+	}
+
+	#[test]
+	#[should_panic]
+	fn zero_tau() {
+		TimeDecayMovingAverage::<f64, f64>::new(Duration::ZERO);
+	}
+}
@@ -0,0 +1,154 @@
+use super::SMA;
+use crate::{DivisorFromCount, Iter, SingleSumSMA};
+use num_traits::Zero;
+use std::{
+	fmt,
+	ops::{AddAssign, Div, SubAssign},
+};
+
+/// A [triangular moving average](https://en.wikipedia.org/wiki/Moving_average#Other_weightings)
+/// implementation, i.e. the simple moving average of the simple moving averages of the samples.
+/// This weights the samples in the middle of the combined `2 * WINDOW_SIZE - 1` samples long span
+/// most heavily, which smooths the average more than a single SMA does.
+///
+/// Internally, a [SingleSumSMA] of the samples feeds its averages into another [SingleSumSMA], so
+/// adding a sample and reading the average are both `O(1)` operations, with the same floating
+/// point rounding error caveat as [SingleSumSMA].
+///
+/// The extra smoothing comes at the cost of lag: the average trails the samples by `WINDOW_SIZE - 1`
+/// samples, twice as much as a single SMA of the same window size.
+#[derive(Clone, Copy)]
+pub struct TriangularSMA<Sample, Divisor, const WINDOW_SIZE: usize> {
+	samples: SingleSumSMA<Sample, Divisor, WINDOW_SIZE>,
+	averages: SingleSumSMA<Sample, Divisor, WINDOW_SIZE>,
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> SMA<Sample, Divisor, WINDOW_SIZE>
+	for TriangularSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + AddAssign + SubAssign + Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	fn add_sample(&mut self, new_sample: Sample) {
+		self.samples.add_sample(new_sample);
+		self.averages.add_sample(self.samples.get_average());
+	}
+
+	fn get_average(&self) -> Sample {
+		self.averages.get_average()
+	}
+
+	fn get_most_recent_sample(&self) -> Option<Sample> {
+		self.samples.get_most_recent_sample()
+	}
+
+	fn get_oldest_sample(&self) -> Option<Sample> {
+		self.samples.get_oldest_sample()
+	}
+
+	fn get_num_samples(&self) -> usize {
+		self.samples.get_num_samples()
+	}
+
+	fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
+		self.samples.get_sample_window_iter()
+	}
+}
+
+impl<Sample: Copy + Zero, Divisor, const WINDOW_SIZE: usize>
+	TriangularSMA<Sample, Divisor, WINDOW_SIZE>
+{
+	/// Constructs a new [TriangularSMA] with window size `WINDOW_SIZE`. This constructor is only
+	/// available for `Sample` types that implement [num_traits::Zero]. If the `Sample` type does
+	/// not, use the [from_zero](TriangularSMA::from_zero) constructor instead.
+	///
+	/// Note that the `Divisor` type usually cannot be derived by the compiler when using this
+	/// constructor and must be explicitly stated, even if it is the same as the `Sample` type.
+	pub fn new() -> Self {
+		Self::from_zero(Sample::zero())
+	}
+}
+
+impl<Sample: Copy + Zero, Divisor, const WINDOW_SIZE: usize> Default
+	for TriangularSMA<Sample, Divisor, WINDOW_SIZE>
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<Sample: Copy, Divisor, const WINDOW_SIZE: usize> TriangularSMA<Sample, Divisor, WINDOW_SIZE> {
+	/// Constructs a new [TriangularSMA] with window size `WINDOW_SIZE` from the given `zero`
+	/// sample. If the `Sample` type implements [num_traits::Zero], the [new](TriangularSMA::new)
+	/// constructor might be preferable to this.
+	pub const fn from_zero(zero: Sample) -> Self {
+		Self {
+			samples: SingleSumSMA::from_zero(zero),
+			averages: SingleSumSMA::from_zero(zero),
+		}
+	}
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> fmt::Debug
+	for TriangularSMA<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + AddAssign + SubAssign + Div<Divisor, Output = Sample> + fmt::Debug,
+	Divisor: DivisorFromCount,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("TriangularSMA")
+			.field("samples", &self.samples)
+			.field("averages", &self.averages)
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn sma_pass(samples: &[f64], window_size: usize) -> Vec<f64> {
+		(0..samples.len())
+			.map(|idx| {
+				let window = &samples[(idx + 1).saturating_sub(window_size)..=idx];
+				window.iter().sum::<f64>() / window.len() as f64
+			})
+			.collect()
+	}
+
+	#[test]
+	fn matches_two_sma_passes() {
+		let samples = [3.0, 6.0, 9.0, 12.0, 15.0, -4.0, 0.5, 7.0];
+		let expected = sma_pass(&sma_pass(&samples, 3), 3);
+
+		let mut tma = TriangularSMA::<f64, f64, 3>::new();
+		for (sample, expected) in samples.iter().zip(expected) {
+			tma.add_sample(*sample);
+			assert!((tma.get_average() - expected).abs() < 1e-12);
+		}
+
+		assert_eq!(tma.get_num_samples(), 3);
+		assert_eq!(tma.get_most_recent_sample(), Some(7.0));
+		assert_eq!(tma.get_oldest_sample(), Some(-4.0));
+	}
+
+	#[test]
+	fn step_response_lag() {
+		let mut tma = TriangularSMA::<u32, u32, 4>::new();
+		for _ in 0..4 {
+			tma.add_sample(0);
+		}
+
+		// The average reaches the new level only once 2 * WINDOW_SIZE - 1 samples have been added
+		for _ in 0..6 {
+			tma.add_sample(16);
+			assert!(tma.get_average() < 16);
+		}
+		tma.add_sample(16);
+		assert_eq!(tma.get_average(), 16);
+	}
+}
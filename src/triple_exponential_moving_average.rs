@@ -0,0 +1,113 @@
+use crate::ExponentialMovingAverage;
+use num_traits::{Float, Zero};
+use std::ops::{Add, Mul, Sub};
+
+/// Calculates the [triple exponential moving average](https://en.wikipedia.org/wiki/Triple_exponential_moving_average)
+/// (TEMA) of a series of samples, defined as `3 * EMA1 - 3 * EMA2 + EMA3`, where `EMA1` is the
+/// [ExponentialMovingAverage] of the samples, `EMA2` that of `EMA1` and `EMA3` that of `EMA2`, all
+/// three stages using the same smoothing factor. This cancels out even more of the lag of a single
+/// EMA than a [DoubleExponentialMovingAverage](crate::DoubleExponentialMovingAverage) does, at the
+/// cost of overshooting more when the samples change abruptly.
+#[derive(Clone, Copy, Debug)]
+pub struct TripleExponentialMovingAverage<Sample, Divisor> {
+	ema: ExponentialMovingAverage<Sample, Divisor>,
+	ema_of_ema: ExponentialMovingAverage<Sample, Divisor>,
+	ema_of_ema_of_ema: ExponentialMovingAverage<Sample, Divisor>,
+}
+
+impl<Sample, Divisor> TripleExponentialMovingAverage<Sample, Divisor>
+where
+	Sample: Copy + Add<Output = Sample> + Sub<Output = Sample> + Mul<Divisor, Output = Sample>,
+	Divisor: Float,
+{
+	/// Adds a sample to the series of samples, cascading it through the three EMA stages.
+	pub fn add_sample(&mut self, new_sample: Sample) {
+		self.ema.add_sample(new_sample);
+		self.ema_of_ema.add_sample(self.ema.get_average());
+		self.ema_of_ema_of_ema
+			.add_sample(self.ema_of_ema.get_average());
+	}
+
+	/// Returns the triple exponential moving average of all samples added so far.
+	pub fn get_average(&self) -> Sample {
+		let difference = self.ema.get_average() - self.ema_of_ema.get_average();
+		difference + difference + difference + self.ema_of_ema_of_ema.get_average()
+	}
+
+	/// Returns the total number of samples added so far.
+	pub fn get_num_samples(&self) -> usize {
+		self.ema.get_num_samples()
+	}
+}
+
+impl<Sample: Copy + Zero, Divisor: Float> TripleExponentialMovingAverage<Sample, Divisor> {
+	/// Constructs a new [TripleExponentialMovingAverage] with smoothing factor `alpha`. This
+	/// constructor is only available for `Sample` types that implement [num_traits::Zero]. If the
+	/// `Sample` type does not, use the [from_zero](TripleExponentialMovingAverage::from_zero)
+	/// constructor instead.
+	///
+	/// # Panics
+	///
+	/// Panics if `alpha` does not lie in the interval `(0, 1]`.
+	pub fn new(alpha: Divisor) -> Self {
+		Self::from_zero(Sample::zero(), alpha)
+	}
+}
+
+impl<Sample: Copy, Divisor: Float> TripleExponentialMovingAverage<Sample, Divisor> {
+	/// Constructs a new [TripleExponentialMovingAverage] with smoothing factor `alpha` from the
+	/// given `zero` sample. If the `Sample` type implements [num_traits::Zero], the
+	/// [new](TripleExponentialMovingAverage::new) constructor might be preferable to this.
+	///
+	/// # Panics
+	///
+	/// Panics if `alpha` does not lie in the interval `(0, 1]`.
+	pub fn from_zero(zero: Sample, alpha: Divisor) -> Self {
+		Self {
+			ema: ExponentialMovingAverage::from_zero(zero, alpha),
+			ema_of_ema: ExponentialMovingAverage::from_zero(zero, alpha),
+			ema_of_ema_of_ema: ExponentialMovingAverage::from_zero(zero, alpha),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn basics() {
+		let mut tema = TripleExponentialMovingAverage::<f64, f64>::new(0.5);
+		assert_eq!(tema.get_average(), 0.0);
+
+		tema.add_sample(4.0);
+		assert_eq!(tema.get_average(), 4.0);
+
+		// EMA1 = 6, EMA2 = 5, EMA3 = 4.5
+		tema.add_sample(8.0);
+		assert_eq!(tema.get_average(), 7.5);
+		assert_eq!(tema.get_num_samples(), 2);
+	}
+
+	#[test]
+	fn linear_ramp_lag() {
+		let mut ema = ExponentialMovingAverage::<f64, f64>::new(0.3);
+		let mut tema = TripleExponentialMovingAverage::<f64, f64>::new(0.3);
+
+		for idx in 0..200 {
+			let sample = 2.0 * idx as f64 + 5.0;
+			ema.add_sample(sample);
+			tema.add_sample(sample);
+
+			if 5 <= idx {
+				let tema_lag = (sample - tema.get_average()).abs();
+				assert!(tema_lag * 10.0 < (sample - ema.get_average()).abs());
+			}
+		}
+
+		// The lag of a single EMA converges to `slope * (1 - alpha) / alpha`, while TEMA tracks the
+		// ramp itself
+		assert!((ema.get_average() - (2.0 * 199.0 + 5.0 - 2.0 * 0.7 / 0.3)).abs() < 1e-9);
+		assert!((tema.get_average() - (2.0 * 199.0 + 5.0)).abs() < 1e-9);
+	}
+}
@@ -0,0 +1,182 @@
+use crate::ring_buffer::RingBuffer;
+use num_traits::Zero;
+use std::ops::{Add, Div, Mul, Sub};
+
+/// Calculates the [volume weighted moving average](https://en.wikipedia.org/wiki/Volume-weighted_average_price)
+/// (VWMA) of the samples in the sample window, where each sample is added together with its own
+/// weight, e.g. a price together with the traded volume. The average is
+/// `sum(sample * weight) / sum(weight)`.
+///
+/// Both the weighted sum of the samples and the sum of the weights are cached, which makes adding a
+/// sample and reading the average `O(1)` operations. Like [SingleSumSMA](crate::SingleSumSMA),
+/// this means that floating point rounding errors accumulate in the cached sums.
+///
+/// Since every sample is added together with its weight, this type does not implement the
+/// [SMA](crate::SMA) trait, but provides similar inherent methods instead.
+#[derive(Clone, Copy, Debug)]
+pub struct VolumeWeightedMovingAverage<Sample, Weight, const WINDOW_SIZE: usize> {
+	samples: RingBuffer<(Sample, Weight), WINDOW_SIZE>,
+	weighted_sum: Sample,
+	weight_sum: Weight,
+	zero: Sample,
+	zero_weight: Weight,
+}
+
+impl<Sample, Weight, const WINDOW_SIZE: usize>
+	VolumeWeightedMovingAverage<Sample, Weight, WINDOW_SIZE>
+where
+	Sample: Copy
+		+ Add<Output = Sample>
+		+ Sub<Output = Sample>
+		+ Mul<Weight, Output = Sample>
+		+ Div<Weight, Output = Sample>,
+	Weight: Copy + Add<Output = Weight> + Sub<Output = Weight> + PartialEq,
+{
+	/// Adds a sample with the given weight to the series of samples. If the sample window is full,
+	/// this will cause the oldest sample and its weight to be dropped, i.e. no longer contribute to
+	/// the average.
+	pub fn add_sample(&mut self, new_sample: Sample, weight: Weight) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+
+		self.weighted_sum = self.weighted_sum + new_sample * weight;
+		self.weight_sum = self.weight_sum + weight;
+
+		if let Some((shifted_sample, shifted_weight)) = self.samples.shift((new_sample, weight)) {
+			self.weighted_sum = self.weighted_sum - shifted_sample * shifted_weight;
+			self.weight_sum = self.weight_sum - shifted_weight;
+		}
+	}
+
+	/// Returns the volume weighted moving average of the samples in the sample window, or the zero
+	/// sample if the sum of their weights is zero.
+	pub fn get_average(&self) -> Sample {
+		if self.weight_sum == self.zero_weight {
+			return self.zero;
+		}
+
+		self.weighted_sum / self.weight_sum
+	}
+
+	/// Returns the sum of the weights of the samples in the sample window.
+	pub fn get_weight_sum(&self) -> Weight {
+		self.weight_sum
+	}
+
+	/// Returns the most recently added sample and its weight, if any.
+	pub fn get_most_recent_sample(&self) -> Option<(Sample, Weight)> {
+		self.samples.front().cloned()
+	}
+
+	/// Returns the total number of samples currently in the in the sample window. This value never
+	/// exceeds the sample window size.
+	pub fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	/// Returns the maximum number of samples that fit in the sample window.
+	pub fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+}
+
+impl<Sample: Copy + Zero, Weight: Copy + Zero, const WINDOW_SIZE: usize>
+	VolumeWeightedMovingAverage<Sample, Weight, WINDOW_SIZE>
+{
+	/// Constructs a new [VolumeWeightedMovingAverage] with window size `WINDOW_SIZE`. This
+	/// constructor is only available for `Sample` and `Weight` types that implement
+	/// [num_traits::Zero]. If they do not, use the
+	/// [from_zero](VolumeWeightedMovingAverage::from_zero) constructor instead.
+	pub fn new() -> Self {
+		Self::from_zero(Sample::zero(), Weight::zero())
+	}
+}
+
+impl<Sample: Copy + Zero, Weight: Copy + Zero, const WINDOW_SIZE: usize> Default
+	for VolumeWeightedMovingAverage<Sample, Weight, WINDOW_SIZE>
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<Sample: Copy, Weight: Copy, const WINDOW_SIZE: usize>
+	VolumeWeightedMovingAverage<Sample, Weight, WINDOW_SIZE>
+{
+	/// Constructs a new [VolumeWeightedMovingAverage] with window size `WINDOW_SIZE` from the given
+	/// `zero` sample and `zero_weight` weight. If the `Sample` and `Weight` types implement
+	/// [num_traits::Zero], the [new](VolumeWeightedMovingAverage::new) constructor might be
+	/// preferable to this.
+	pub const fn from_zero(zero: Sample, zero_weight: Weight) -> Self {
+		Self {
+			samples: RingBuffer::new((zero, zero_weight)),
+			weighted_sum: zero,
+			weight_sum: zero_weight,
+			zero,
+			zero_weight,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn basics() {
+		let mut vwma = VolumeWeightedMovingAverage::<f64, f64, 3>::new();
+		assert_eq!(vwma.get_average(), 0.0);
+
+		vwma.add_sample(10.0, 1.0);
+		assert_eq!(vwma.get_average(), 10.0);
+
+		// (10 * 1 + 20 * 3) / (1 + 3)
+		vwma.add_sample(20.0, 3.0);
+		assert_eq!(vwma.get_average(), 17.5);
+
+		// (10 * 1 + 20 * 3 + 30 * 6) / (1 + 3 + 6)
+		vwma.add_sample(30.0, 6.0);
+		assert_eq!(vwma.get_average(), 25.0);
+		assert_eq!(vwma.get_weight_sum(), 10.0);
+
+		// (20 * 3 + 30 * 6 + 12 * 2) / (3 + 6 + 2)
+		vwma.add_sample(12.0, 2.0);
+		assert_eq!(vwma.get_average(), 24.0);
+		assert_eq!(vwma.get_num_samples(), 3);
+		assert_eq!(vwma.get_most_recent_sample(), Some((12.0, 2.0)));
+	}
+
+	#[test]
+	fn integers() {
+		let mut vwma = VolumeWeightedMovingAverage::<u64, u64, 2>::new();
+
+		vwma.add_sample(100, 5);
+		vwma.add_sample(130, 1);
+		// (100 * 5 + 130 * 1) / 6
+		assert_eq!(vwma.get_average(), 105);
+
+		vwma.add_sample(90, 9);
+		// (130 * 1 + 90 * 9) / 10
+		assert_eq!(vwma.get_average(), 94);
+	}
+
+	#[test]
+	fn zero_total_weight() {
+		let mut vwma = VolumeWeightedMovingAverage::<i32, i32, 2>::new();
+		vwma.add_sample(7, 0);
+		vwma.add_sample(9, 0);
+		assert_eq!(vwma.get_average(), 0);
+
+		vwma.add_sample(5, 2);
+		assert_eq!(vwma.get_average(), 5);
+	}
+
+	#[test]
+	fn edge_case_zero_sized() {
+		let mut vwma = VolumeWeightedMovingAverage::<f32, f32, 0>::new();
+		vwma.add_sample(1.0, 1.0);
+		assert_eq!(vwma.get_average(), 0.0);
+		assert_eq!(vwma.get_num_samples(), 0);
+	}
+}
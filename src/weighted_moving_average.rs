@@ -0,0 +1,181 @@
+use super::SMA;
+use crate::{common::cast_to_divisor_type, ring_buffer::RingBuffer, DivisorFromCount, Iter};
+use num_traits::Zero;
+use std::{
+	marker::{self, PhantomData},
+	ops::{Add, Div, Mul, Sub},
+};
+
+/// Calculates the linearly [weighted moving average](https://en.wikipedia.org/wiki/Moving_average#Weighted_moving_average)
+/// (WMA) of the samples in the sample window, where the most recent sample has weight `N`, the
+/// sample before it weight `N - 1` and so on, down to weight `1` for the oldest sample, `N` being
+/// the number of samples in the sample window.
+///
+/// Both the plain sum and the weighted sum of the samples in the sample window are cached, which
+/// makes adding a sample and reading the average `O(1)` operations. Like [SingleSumSMA](crate::SingleSumSMA),
+/// this means that floating point rounding errors accumulate in the cached sums.
+#[derive(Clone, Copy, Debug)]
+pub struct WeightedMovingAverage<Sample, Divisor, const WINDOW_SIZE: usize> {
+	samples: RingBuffer<Sample, WINDOW_SIZE>,
+	sum: Sample,
+	weighted_sum: Sample,
+	_marker: marker::PhantomData<Divisor>,
+}
+
+impl<Sample, Divisor, const WINDOW_SIZE: usize> SMA<Sample, Divisor, WINDOW_SIZE>
+	for WeightedMovingAverage<Sample, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy
+		+ Add<Output = Sample>
+		+ Sub<Output = Sample>
+		+ Mul<Divisor, Output = Sample>
+		+ Div<Divisor, Output = Sample>,
+	Divisor: DivisorFromCount,
+{
+	fn add_sample(&mut self, new_sample: Sample) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+
+		// The weights of the samples already in the sample window decrease by one if the sample
+		// window is full, in which case the oldest sample's weight drops to zero
+		let num_samples = self.samples.len();
+		if let Some(shifted_sample) = self.samples.shift(new_sample) {
+			self.weighted_sum = self.weighted_sum - self.sum
+				+ new_sample * cast_to_divisor_type::<Divisor>(num_samples);
+			self.sum = self.sum - shifted_sample + new_sample;
+		} else {
+			self.weighted_sum =
+				self.weighted_sum + new_sample * cast_to_divisor_type::<Divisor>(num_samples + 1);
+			self.sum = self.sum + new_sample;
+		}
+	}
+
+	fn get_average(&self) -> Sample {
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return self.weighted_sum;
+		}
+
+		self.weighted_sum / cast_to_divisor_type::<Divisor>(num_samples * (num_samples + 1) / 2)
+	}
+
+	fn get_most_recent_sample(&self) -> Option<Sample> {
+		self.samples.front().cloned()
+	}
+
+	fn get_oldest_sample(&self) -> Option<Sample> {
+		self.samples.back().cloned()
+	}
+
+	fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
+		self.samples.iter()
+	}
+}
+
+impl<Sample: Copy + Zero, Divisor, const WINDOW_SIZE: usize>
+	WeightedMovingAverage<Sample, Divisor, WINDOW_SIZE>
+{
+	/// Constructs a new [WeightedMovingAverage] with window size `WINDOW_SIZE`. This constructor is
+	/// only available for `Sample` types that implement [num_traits::Zero]. If the `Sample` type
+	/// does not, use the [from_zero](WeightedMovingAverage::from_zero) constructor instead.
+	///
+	/// Note that the `Divisor` type usually cannot be derived by the compiler when using this
+	/// constructor and must be explicitly stated, even if it is the same as the `Sample` type.
+	pub fn new() -> Self {
+		Self::from_zero(Sample::zero())
+	}
+}
+
+impl<Sample: Copy + Zero, Divisor, const WINDOW_SIZE: usize> Default
+	for WeightedMovingAverage<Sample, Divisor, WINDOW_SIZE>
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<Sample: Copy, Divisor, const WINDOW_SIZE: usize>
+	WeightedMovingAverage<Sample, Divisor, WINDOW_SIZE>
+{
+	/// Constructs a new [WeightedMovingAverage] with window size `WINDOW_SIZE` from the given
+	/// `zero` sample. If the `Sample` type implements [num_traits::Zero], the
+	/// [new](WeightedMovingAverage::new) constructor might be preferable to this.
+	pub const fn from_zero(zero: Sample) -> Self {
+		Self {
+			samples: RingBuffer::new(zero),
+			sum: zero,
+			weighted_sum: zero,
+			_marker: PhantomData,
+		}
+	}
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+
+	pub(crate) fn wma_pass(samples: &[f64], window_size: usize) -> Vec<f64> {
+		(0..samples.len())
+			.map(|idx| {
+				let window = &samples[(idx + 1).saturating_sub(window_size)..=idx];
+				let weighted_sum: f64 = window
+					.iter()
+					.enumerate()
+					.map(|(idx, sample)| (idx + 1) as f64 * sample)
+					.sum();
+				weighted_sum / (window.len() * (window.len() + 1) / 2) as f64
+			})
+			.collect()
+	}
+
+	#[test]
+	fn basics() {
+		let mut wma = WeightedMovingAverage::<u32, u32, 3>::new();
+		assert_eq!(wma.get_average(), 0);
+
+		wma.add_sample(6);
+		assert_eq!(wma.get_average(), 6);
+
+		// (6 + 2 * 12) / 3
+		wma.add_sample(12);
+		assert_eq!(wma.get_average(), 10);
+
+		// (6 + 2 * 12 + 3 * 3) / 6
+		wma.add_sample(3);
+		assert_eq!(wma.get_average(), 6);
+
+		// (12 + 2 * 3 + 3 * 0) / 6
+		wma.add_sample(0);
+		assert_eq!(wma.get_average(), 3);
+	}
+
+	#[test]
+	fn matches_brute_force() {
+		let samples = [4.0, 8.0, 3.0, 7.0, 11.0, 0.0, -23.0, 5.5, 1.25, 9.0];
+		let expected = wma_pass(&samples, 4);
+
+		let mut wma = WeightedMovingAverage::<f64, f64, 4>::new();
+		for (sample, expected) in samples.iter().zip(expected) {
+			wma.add_sample(*sample);
+			assert!((wma.get_average() - expected).abs() < 1e-12);
+		}
+	}
+
+	#[test]
+	fn edge_case_zero_sized() {
+		let mut wma = WeightedMovingAverage::<f32, f32, 0>::new();
+		wma.add_sample(1.0);
+		assert_eq!(wma.get_average(), 0.0);
+		assert_eq!(wma.get_num_samples(), 0);
+	}
+}
@@ -0,0 +1,143 @@
+use super::SMA;
+use crate::{common::cast_to_divisor_type, ring_buffer::RingBuffer, DivisorFromCount, Iter};
+use num_traits::Zero;
+use std::{
+	convert::TryFrom,
+	marker::{self, PhantomData},
+	ops::{AddAssign, Div, SubAssign},
+};
+
+/// An SMA implementation that, like [SingleSumSMA](crate::SingleSumSMA), caches the sum of all
+/// samples currently in the sample window as a single value, but accumulates that sum in a wider
+/// `Wide` type, e.g. [u64] for [u32] samples. This prevents the sum from overflowing even if the
+/// sum of the samples in the sample window does not fit in the `Sample` type.
+///
+/// The average is calculated in the `Wide` type and then narrowed back to the `Sample` type. For
+/// integer types, the average always lies within the range of the `Sample` type, so the narrowing
+/// never fails. The `Divisor` type is the type that the `Wide` sum is divided by.
+///
+/// ```
+/// # use simple_moving_average::{SMA, WideningSumSMA};
+/// let mut ma = WideningSumSMA::<u32, u64, u64, 4>::new();
+/// ma.add_sample(u32::MAX);
+/// ma.add_sample(u32::MAX);
+/// assert_eq!(ma.get_average(), u32::MAX);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct WideningSumSMA<Sample, Wide, Divisor, const WINDOW_SIZE: usize> {
+	samples: RingBuffer<Sample, WINDOW_SIZE>,
+	sum: Wide,
+	_marker: marker::PhantomData<Divisor>,
+}
+
+impl<Sample, Wide, Divisor, const WINDOW_SIZE: usize> SMA<Sample, Divisor, WINDOW_SIZE>
+	for WideningSumSMA<Sample, Wide, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy + TryFrom<Wide>,
+	Wide: Copy + From<Sample> + AddAssign + SubAssign + Div<Divisor, Output = Wide>,
+	Divisor: DivisorFromCount,
+{
+	fn add_sample(&mut self, new_sample: Sample) {
+		if WINDOW_SIZE == 0 {
+			return;
+		}
+
+		self.sum += Wide::from(new_sample);
+
+		if let Some(shifted_sample) = self.samples.shift(new_sample) {
+			self.sum -= Wide::from(shifted_sample);
+		}
+	}
+
+	/// # Panics
+	///
+	/// Panics if the average can't be narrowed to the `Sample` type, which never happens for
+	/// integer types.
+	fn get_average(&self) -> Sample {
+		Sample::try_from(self.get_wide_average()).unwrap_or_else(|_| {
+			panic!("Failed to narrow the average to the sample type");
+		})
+	}
+
+	fn get_most_recent_sample(&self) -> Option<Sample> {
+		self.samples.front().cloned()
+	}
+
+	fn get_oldest_sample(&self) -> Option<Sample> {
+		self.samples.back().cloned()
+	}
+
+	fn get_num_samples(&self) -> usize {
+		self.samples.len()
+	}
+
+	fn get_sample_window_size(&self) -> usize {
+		WINDOW_SIZE
+	}
+
+	fn get_sample_window_iter(&self) -> Iter<'_, Sample, WINDOW_SIZE> {
+		self.samples.iter()
+	}
+}
+
+impl<Sample, Wide, Divisor, const WINDOW_SIZE: usize>
+	WideningSumSMA<Sample, Wide, Divisor, WINDOW_SIZE>
+where
+	Sample: Copy,
+	Wide: Copy + Div<Divisor, Output = Wide>,
+	Divisor: DivisorFromCount,
+{
+	/// Returns the simple moving average value of all the samples in the sample window, as the
+	/// `Wide` type.
+	pub fn get_wide_average(&self) -> Wide {
+		let num_samples = self.samples.len();
+
+		if num_samples == 0 {
+			return self.sum;
+		}
+
+		self.sum / cast_to_divisor_type::<Divisor>(num_samples)
+	}
+
+	/// Returns the sum of all the samples in the sample window, as the `Wide` type.
+	pub fn get_wide_sum(&self) -> Wide {
+		self.sum
+	}
+}
+
+impl<Sample: Copy + Zero, Wide: From<Sample>, Divisor, const WINDOW_SIZE: usize>
+	WideningSumSMA<Sample, Wide, Divisor, WINDOW_SIZE>
+{
+	/// Constructs a new [WideningSumSMA] with window size `WINDOW_SIZE`. This constructor is only
+	/// available for `Sample` types that implement [num_traits::Zero]. If the `Sample` type does
+	/// not, use the [from_zero](WideningSumSMA::from_zero) constructor instead.
+	///
+	/// Note that the `Wide` and `Divisor` types usually cannot be derived by the compiler when
+	/// using this constructor and must be explicitly stated.
+	pub fn new() -> Self {
+		Self::from_zero(Sample::zero())
+	}
+}
+
+impl<Sample: Copy + Zero, Wide: From<Sample>, Divisor, const WINDOW_SIZE: usize> Default
+	for WideningSumSMA<Sample, Wide, Divisor, WINDOW_SIZE>
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<Sample: Copy, Wide: From<Sample>, Divisor, const WINDOW_SIZE: usize>
+	WideningSumSMA<Sample, Wide, Divisor, WINDOW_SIZE>
+{
+	/// Constructs a new [WideningSumSMA] with window size `WINDOW_SIZE` from the given `zero`
+	/// sample. If the `Sample` type implements [num_traits::Zero], the [new](WideningSumSMA::new)
+	/// constructor might be preferable to this.
+	pub fn from_zero(zero: Sample) -> Self {
+		Self {
+			samples: RingBuffer::new(zero),
+			sum: Wide::from(zero),
+			_marker: PhantomData,
+		}
+	}
+}